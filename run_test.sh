@@ -13,9 +13,9 @@ cd forum-svelte
 
 hc-scaffold dna forum 
 hc-scaffold zome posts --integrity dnas/forum/zomes/integrity/ --coordinator dnas/forum/zomes/coordinator/
-hc-scaffold entry-type post --reference-entry-hash false --crud crud --link-from-original-to-each-update true --fields title:String:TextField,content:String:TextArea
-hc-scaffold entry-type comment --reference-entry-hash false --crud crud --link-from-original-to-each-update false --fields post_hash:ActionHash::Post
-hc-scaffold entry-type like --reference-entry-hash false --crud crd --fields like_hash:Option\<ActionHash\>::Like,string_list:Vec\<String\>
+hc-scaffold entry-type post --reference-entry-hash false --crud crud --link-from-original-to-each-update true --only-author-can-update-and-delete false --fields title:String:TextField,content:String:TextArea
+hc-scaffold entry-type comment --reference-entry-hash false --crud crud --link-from-original-to-each-update false --only-author-can-update-and-delete false --fields post_hash:ActionHash::Post
+hc-scaffold entry-type like --reference-entry-hash false --crud crd --only-author-can-update-and-delete false --fields like_hash:Option\<ActionHash\>::Like,string_list:Vec\<String\>
 hc-scaffold entry-type certificate --reference-entry-hash true --crud cr --fields post_hash:ActionHash::Post,agent:AgentPubKey::certified,certifications_hashes:Vec\<EntryHash\>::Certificate,certificate_type:Enum::CertificateType:TypeOne.TypeTwo,dna_hash:DnaHash
 
 hc-scaffold collection global all_posts post 
@@ -24,8 +24,8 @@ hc-scaffold collection global all_posts_entry_hash post:EntryHash
 hc-scaffold collection global all_likes like
 hc-scaffold collection by-author posts_by_author_entry_hash post:EntryHash
 
-hc-scaffold link-type post like --delete true --bidireccional false
-hc-scaffold link-type comment like:EntryHash --delete true --bidireccional true
+hc-scaffold link-type post like --delete true --only-creator-can-delete false --bidireccional false
+hc-scaffold link-type comment like:EntryHash --delete true --only-creator-can-delete false --bidireccional true
 hc-scaffold link-type certificate:EntryHash like --delete false --bidireccional false
 hc-scaffold link-type agent:creator post:EntryHash --delete false --bidireccional true
 
@@ -46,9 +46,9 @@ cd forum-vue
 
 hc-scaffold dna forum 
 hc-scaffold zome posts --integrity dnas/forum/zomes/integrity/ --coordinator dnas/forum/zomes/coordinator/
-hc-scaffold entry-type post --reference-entry-hash false --crud crud --link-from-original-to-each-update true --fields title:String:TextField,content:String:TextArea
-hc-scaffold entry-type comment --reference-entry-hash false --crud crud --link-from-original-to-each-update false --fields post_hash:ActionHash::Post
-hc-scaffold entry-type like --reference-entry-hash false --crud crd --fields like_hash:Option\<ActionHash\>::Like,string_list:Vec\<String\>
+hc-scaffold entry-type post --reference-entry-hash false --crud crud --link-from-original-to-each-update true --only-author-can-update-and-delete false --fields title:String:TextField,content:String:TextArea
+hc-scaffold entry-type comment --reference-entry-hash false --crud crud --link-from-original-to-each-update false --only-author-can-update-and-delete false --fields post_hash:ActionHash::Post
+hc-scaffold entry-type like --reference-entry-hash false --crud crd --only-author-can-update-and-delete false --fields like_hash:Option\<ActionHash\>::Like,string_list:Vec\<String\>
 hc-scaffold entry-type certificate --reference-entry-hash true --crud cr --fields post_hash:ActionHash::Post,agent:AgentPubKey::certified,certifications_hashes:Vec\<EntryHash\>::Certificate,certificate_type:Enum::CertificateType:TypeOne.TypeTwo,dna_hash:DnaHash
 
 hc-scaffold collection global all_posts post 
@@ -57,8 +57,8 @@ hc-scaffold collection global all_likes like
 hc-scaffold collection global all_posts_entry_hash post:EntryHash
 hc-scaffold collection by-author posts_by_author_entry_hash post:EntryHash
 
-hc-scaffold link-type post like --delete true --bidireccional false
-hc-scaffold link-type comment like:EntryHash --delete true --bidireccional true
+hc-scaffold link-type post like --delete true --only-creator-can-delete false --bidireccional false
+hc-scaffold link-type comment like:EntryHash --delete true --only-creator-can-delete false --bidireccional true
 hc-scaffold link-type certificate:EntryHash like --delete false --bidireccional false
 hc-scaffold link-type agent:creator post:EntryHash --delete false --bidireccional true
 
@@ -78,9 +78,9 @@ cd forum-lit
 
 hc-scaffold dna forum 
 hc-scaffold zome posts --integrity dnas/forum/zomes/integrity/ --coordinator dnas/forum/zomes/coordinator/
-hc-scaffold entry-type post --reference-entry-hash false --crud crud --link-from-original-to-each-update true --fields title:String:TextField,content:String:TextArea
-hc-scaffold entry-type comment --reference-entry-hash false --crud crud --link-from-original-to-each-update false --fields post_hash:ActionHash::Post
-hc-scaffold entry-type like --reference-entry-hash false --crud crd --fields like_hash:Option\<ActionHash\>::Like,string_list:Vec\<String\>
+hc-scaffold entry-type post --reference-entry-hash false --crud crud --link-from-original-to-each-update true --only-author-can-update-and-delete false --fields title:String:TextField,content:String:TextArea
+hc-scaffold entry-type comment --reference-entry-hash false --crud crud --link-from-original-to-each-update false --only-author-can-update-and-delete false --fields post_hash:ActionHash::Post
+hc-scaffold entry-type like --reference-entry-hash false --crud crd --only-author-can-update-and-delete false --fields like_hash:Option\<ActionHash\>::Like,string_list:Vec\<String\>
 hc-scaffold entry-type certificate --reference-entry-hash true --crud cr --fields post_hash:ActionHash::Post,agent:AgentPubKey::certified,certifications_hashes:Vec\<EntryHash\>::Certificate,certificate_type:Enum::CertificateType:TypeOne.TypeTwo,dna_hash:DnaHash
 
 hc-scaffold collection global all_posts post 
@@ -89,8 +89,8 @@ hc-scaffold collection global all_posts_entry_hash post:EntryHash
 hc-scaffold collection by-author posts_by_author_entry_hash post:EntryHash
 hc-scaffold collection global all_likes like
 
-hc-scaffold link-type post like --delete true --bidireccional false
-hc-scaffold link-type comment like:EntryHash --delete true --bidireccional true
+hc-scaffold link-type post like --delete true --only-creator-can-delete false --bidireccional false
+hc-scaffold link-type comment like:EntryHash --delete true --only-creator-can-delete false --bidireccional true
 hc-scaffold link-type certificate:EntryHash like --delete false --bidireccional false
 hc-scaffold link-type agent:creator post:EntryHash --delete false --bidireccional true
 
@@ -113,9 +113,9 @@ cd forum-vanilla
 
 hc-scaffold dna forum 
 hc-scaffold zome posts --integrity dnas/forum/zomes/integrity/ --coordinator dnas/forum/zomes/coordinator/
-hc-scaffold entry-type post --reference-entry-hash false --crud crud --link-from-original-to-each-update true --fields title:String:TextField,content:String:TextArea
-hc-scaffold entry-type comment --reference-entry-hash false --crud crud --link-from-original-to-each-update false --fields post_hash:ActionHash::Post
-hc-scaffold entry-type like --reference-entry-hash false --crud crd --fields like_hash:Option\<ActionHash\>::Like,string_list:Vec\<String\>
+hc-scaffold entry-type post --reference-entry-hash false --crud crud --link-from-original-to-each-update true --only-author-can-update-and-delete false --fields title:String:TextField,content:String:TextArea
+hc-scaffold entry-type comment --reference-entry-hash false --crud crud --link-from-original-to-each-update false --only-author-can-update-and-delete false --fields post_hash:ActionHash::Post
+hc-scaffold entry-type like --reference-entry-hash false --crud crd --only-author-can-update-and-delete false --fields like_hash:Option\<ActionHash\>::Like,string_list:Vec\<String\>
 hc-scaffold entry-type certificate --reference-entry-hash true --crud cr --fields post_hash:ActionHash::Post,agent:AgentPubKey::certified,certifications_hashes:Vec\<EntryHash\>::Certificate,certificate_type:Enum::CertificateType:TypeOne.TypeTwo,dna_hash:DnaHash
 
 hc-scaffold collection global all_posts post 
@@ -124,8 +124,8 @@ hc-scaffold collection global all_posts_entry_hash post:EntryHash
 hc-scaffold collection global all_likes like
 hc-scaffold collection by-author posts_by_author_entry_hash post:EntryHash
 
-hc-scaffold link-type post like --delete true --bidireccional false
-hc-scaffold link-type comment like:EntryHash --delete true --bidireccional true
+hc-scaffold link-type post like --delete true --only-creator-can-delete false --bidireccional false
+hc-scaffold link-type comment like:EntryHash --delete true --only-creator-can-delete false --bidireccional true
 hc-scaffold link-type certificate:EntryHash like --delete false --bidireccional false
 hc-scaffold link-type agent:creator post:EntryHash --delete false --bidireccional true
 
@@ -137,6 +137,69 @@ npm t
 npm run package
 "
 
+rm -rf /tmp/forum-spec
+cd /tmp
+
+cat > /tmp/forum-spec.yaml << EOF
+name: forum-spec
+template: lit
+setup_nix: true
+dnas:
+  - name: forum
+    zomes:
+      - name: posts
+        entry_types:
+          - name: comment
+            crud: crd
+            fields:
+              - comment:String:TextArea
+              - post_hash:ActionHash::post
+          - name: post
+            fields:
+              - title:String:TextField[non_empty]
+              - content:String:TextArea
+        link_types:
+          - from: agent
+            to: post
+            delete: true
+        collections:
+          - name: all_posts
+            type: global
+            entry_type: post
+EOF
+
+hc-scaffold apply forum-spec.yaml
+# Applying the same spec again skips everything that already exists
+hc-scaffold apply forum-spec.yaml
+
+cd forum-spec
+
+hc-scaffold list
+hc-scaffold --output json list
+hc-scaffold doctor
+
+# Scaffolds an entry type with a link type and a collection, and removes them again
+hc-scaffold entry-type like --reference-entry-hash false --crud crd --only-author-can-update-and-delete false --fields post_hash:ActionHash::post
+hc-scaffold collection global all_likes like
+hc-scaffold link-type agent like --delete true --only-creator-can-delete false --bidireccional false
+hc-scaffold remove collection all_likes
+hc-scaffold remove link-type AgentToLikes
+hc-scaffold remove entry-type like
+
+hc-scaffold rename entry-type comment reply
+hc-scaffold field add post subtitle:String:TextField
+hc-scaffold field remove post content
+# Brings back the post entry type as it was before adding and removing its fields
+hc-scaffold undo 2
+hc-scaffold entry-type-version post
+hc-scaffold doctor
+
+nix develop --command bash -c "
+set -e
+npm i
+npm t
+"
+
 rm -rf /tmp/hello-world
 cd /tmp
 
@@ -1,23 +1,48 @@
 use crate::error::{ScaffoldError, ScaffoldResult};
-use crate::file_tree::{dir_content, file_content, load_directory_into_memory, FileTree};
+use crate::file_tree::{
+    diff_file_tree_with_directory, dir_content, file_content, load_directory_into_memory,
+    removed_dirs, removed_files, unified_diff, FileChange, FileTree,
+};
+use crate::history::{build_and_record, record_changes, undo};
+use crate::output::{OutputFormat, ScaffoldOutput};
 use crate::scaffold::app::cargo::exec_metadata;
 use crate::scaffold::app::nix::setup_nix_developer_environment;
-use crate::scaffold::app::AppFileTree;
+use crate::scaffold::app::{find_app_manifests, AppFileTree};
 use crate::scaffold::collection::{scaffold_collection, CollectionType};
-use crate::scaffold::dna::{scaffold_dna, DnaFileTree};
+use crate::scaffold::dna::{find_dna_manifests, scaffold_dna, DnaFileTree};
+use crate::scaffold::doctor::{apply_repair, check_app_consistency, DoctorReport, Inconsistency};
+use crate::scaffold::entry_type::constraints::FieldConstraints;
 use crate::scaffold::entry_type::crud::{parse_crud, Crud};
 use crate::scaffold::entry_type::definitions::{
-    parse_entry_type_reference, parse_referenceable, Cardinality, EntryTypeReference,
-    FieldDefinition, FieldType, Referenceable,
+    parse_entry_type_reference, parse_referenceable, parse_visibility, Cardinality,
+    EntryDefSettings, EntryTypeReference, EntryVisibility, FieldDefinition, FieldType,
+    Referenceable,
+};
+use crate::scaffold::entry_type::integrity::get_all_entry_types;
+use crate::scaffold::entry_type::{
+    fields::{choose_field, parse_fields},
+    from_struct::{
+        parse_struct_reference, read_struct_entry_type, StructEntryType, StructReference,
+    },
+    scaffold_entry_type,
 };
-use crate::scaffold::entry_type::{fields::parse_fields, scaffold_entry_type};
+use crate::scaffold::entry_type_version::version_entry_type;
 use crate::scaffold::example::{choose_example, Example};
+use crate::scaffold::field::{add_field, entry_type_field_names, remove_field};
+use crate::scaffold::link_type::integrity::get_all_link_types;
 use crate::scaffold::link_type::scaffold_link_type;
+use crate::scaffold::list::{list_apps, render_apps_tree};
+use crate::scaffold::remove::{
+    remove_collection, remove_dna, remove_entry_type, remove_link_type, remove_zome,
+};
+use crate::scaffold::rename::rename_entry_type;
+use crate::scaffold::spec::{apply_app_spec, read_app_spec, AppliedSpec};
 use crate::scaffold::web_app::scaffold_web_app;
 use crate::scaffold::web_app::uis::{
     choose_non_vanilla_ui_framework, choose_ui_framework, template_for_ui_framework, UiFramework,
 };
-use crate::scaffold::zome::utils::select_integrity_zomes;
+use crate::scaffold::zome::coordinator::find_extern_function_in_zomes;
+use crate::scaffold::zome::utils::{get_coordinator_zomes_for_integrity, select_integrity_zomes};
 use crate::scaffold::zome::{
     integrity_zome_name, scaffold_coordinator_zome, scaffold_coordinator_zome_in_path,
     scaffold_integrity_zome, scaffold_integrity_zome_with_path, ZomeFileTree,
@@ -28,23 +53,53 @@ use crate::templates::{
     choose_or_get_template, choose_or_get_template_file_tree, templates_path, ScaffoldedTemplate,
 };
 use crate::utils::{
-    check_case, check_no_whitespace, input_no_whitespace, input_with_case, input_yes_or_no,
+    check_can_prompt, check_case, check_no_whitespace, disable_prompts, input_no_whitespace,
+    input_with_case, input_yes_or_no,
 };
 
 use build_fs_tree::{dir, Build, MergeableFileSystemTree};
-use convert_case::Case;
+use convert_case::{Case, Casing};
 use dialoguer::Input;
 use dialoguer::{theme::ColorfulTheme, Select};
+use holochain_types::prelude::DnaManifest;
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::IsTerminal;
 use std::process::{Command, Stdio};
 use std::str::FromStr;
-use std::{ffi::OsString, path::PathBuf};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
+use temp_dir::TempDir;
+
+#[derive(Debug, StructOpt)]
+#[structopt(setting = structopt::clap::AppSettings::InferSubcommands)]
+pub struct HcScaffold {
+    #[structopt(long, global = true)]
+    /// Print the changes that would be made as a diff, without writing anything to disk
+    dry_run: bool,
+
+    #[structopt(long, global = true)]
+    /// Never prompt for missing values: fail with an error listing all the missing arguments instead
+    /// This is enabled automatically when stdin is not a terminal
+    no_prompt: bool,
+
+    #[structopt(long, global = true, default_value = "text")]
+    /// Output format, either "text" or "json"
+    /// With "json", a single JSON document is printed with the created and modified files, the next instructions
+    /// and the identifiers of everything that was added
+    output: OutputFormat,
+
+    #[structopt(subcommand)]
+    command: HcScaffoldCommand,
+}
 
 /// The list of subcommands for `hc scaffold`
 #[derive(Debug, StructOpt)]
 #[structopt(setting = structopt::clap::AppSettings::InferSubcommands)]
-pub enum HcScaffold {
+pub enum HcScaffoldCommand {
     /// Scaffold a new, empty web app
     WebApp {
         /// Name of the app to scaffold
@@ -85,6 +140,11 @@ pub enum HcScaffold {
         /// Name of the DNA being scaffolded
         name: Option<String>,
 
+        #[structopt(long)]
+        /// Path of the folder in which to scaffold the DNA
+        /// Only needed if the app has no "dnas" folder
+        path: Option<PathBuf>,
+
         #[structopt(short, long)]
         /// The template to scaffold the dna from
         /// The template must be located at the ".templates/<TEMPLATE NAME>" folder of the repository
@@ -107,6 +167,11 @@ pub enum HcScaffold {
         /// Scaffold a coordinator zome at the given path
         coordinator: Option<PathBuf>,
 
+        #[structopt(long, value_delimiter = ",")]
+        /// Names of the integrity zomes that the coordinator zome depends on, eg. "posts_integrity,comments_integrity"
+        /// Only used when scaffolding only a coordinator zome, pass "" if it doesn't depend on any of them
+        dependencies: Option<Vec<String>>,
+
         #[structopt(short, long)]
         /// The template to scaffold the dna from
         /// The template must be located at the ".templates/<TEMPLATE NAME>" folder of the repository
@@ -122,6 +187,11 @@ pub enum HcScaffold {
         /// Name of the integrity zome in which you want to scaffold the entry definition
         zome: Option<String>,
 
+        #[structopt(long)]
+        /// Name of the coordinator zome in which you want to scaffold the CRUD functions
+        /// Only needed if more than one coordinator zome depends on the integrity zome
+        coordinator_zome: Option<String>,
+
         /// Name of the entry type being scaffolded
         name: Option<String>,
 
@@ -140,17 +210,55 @@ pub enum HcScaffold {
         /// Only applies if update is selected in the "crud" argument
         link_from_original_to_each_update: Option<bool>,
 
+        #[structopt(long)]
+        /// Whether only the author of an entry can update and delete it
+        /// Only applies if update or delete are selected in the "crud" argument
+        only_author_can_update_and_delete: Option<bool>,
+
         #[structopt(long, value_delimiter = ",", parse(try_from_str = parse_fields))]
         /// The fields that the entry type struct should contain
-        /// Grammar: <FIELD_NAME>:<FIELD_TYPE>:<WIDGET>:<LINKED_FROM> , (widget and linked_from are optional)
-        /// Eg. "title:String:TextField" , "posts_hashes:Vec\<ActionHash\>::Post"
+        /// Grammar: <FIELD_NAME>:<FIELD_TYPE>:<WIDGET>:<LINKED_FROM>[<CONSTRAINTS>] , (widget, linked_from and constraints are optional)
+        /// Eg. "title:String:TextField" , "posts_hashes:Vec\<ActionHash\>::Post" , "rating:u32:Slider[min=1;max=5]" , "post_hash:ActionHash::Post[immutable]"
         fields: Option<Vec<FieldDefinition>>,
 
+        #[structopt(long, parse(try_from_str = parse_struct_reference))]
+        /// A hand-written struct to read the fields of the entry type from, instead of "--fields"
+        /// Grammar: <PATH>::<STRUCT_NAME> , eg. "design/post.rs::Post" . The name of the entry type defaults to the one of the struct
+        from_struct: Option<StructReference>,
+
+        #[structopt(long, parse(try_from_str = parse_visibility))]
+        /// Whether the entries are published to the DHT ("public") or only kept in the source chain of their author ("private")
+        /// Defaults to "public". The zome functions for private entries read them from the source chain of the agent calling them
+        visibility: Option<EntryVisibility>,
+
+        #[structopt(long)]
+        /// Number of validation receipts that the author of an entry waits for
+        /// Defaults to the default of holochain
+        required_validations: Option<u8>,
+
+        #[structopt(long)]
+        /// Whether the entries should be cached at the agent activity authorities of their author
+        cache_at_agent_activity: bool,
+
         #[structopt(short, long)]
         /// The template to scaffold the dna from
         /// The template must be located at the ".templates/<TEMPLATE NAME>" folder of the repository
         template: Option<String>,
     },
+    /// Snapshot the current struct of an entry type as its next version ("<ENTRY_TYPE>V<N>"), so that it can be changed
+    /// while the entries created with the previous versions can still be read
+    EntryTypeVersion {
+        #[structopt(long)]
+        /// Name of the dna in which you want to version the entry type
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome in which you want to version the entry type
+        zome: Option<String>,
+
+        /// Name of the entry type being versioned
+        name: Option<String>,
+    },
     /// Scaffold a link type and its appropriate zome functions into an existing zome
     LinkType {
         #[structopt(long)]
@@ -161,6 +269,11 @@ pub enum HcScaffold {
         /// Name of the integrity zome in which you want to scaffold the link type
         zome: Option<String>,
 
+        #[structopt(long)]
+        /// Name of the coordinator zome in which you want to scaffold the link type functions
+        /// Only needed if more than one coordinator zome depends on the integrity zome
+        coordinator_zome: Option<String>,
+
         #[structopt(parse(try_from_str = parse_referenceable))]
         /// Entry type (or agent role) used as the base for the links
         from_referenceable: Option<Referenceable>,
@@ -177,6 +290,28 @@ pub enum HcScaffold {
         /// Whether this link type can be deleted
         delete: Option<bool>,
 
+        #[structopt(long)]
+        /// Whether only the agent that created a link can delete it
+        /// Only applies if "--delete" is true
+        only_creator_can_delete: Option<bool>,
+
+        #[structopt(long, value_delimiter = ",", parse(try_from_str = parse_fields))]
+        /// The fields of the typed tag that each link carries, serialized into its link tag
+        /// Same grammar as the "--fields" argument of "hc scaffold entry-type", eg. "role:String:TextField,weight:u32:Slider"
+        tag_fields: Option<Vec<FieldDefinition>>,
+
+        #[structopt(long)]
+        /// Index the "<FROM_REFERENCEABLE>" entry type by the value of this field instead, linking each entry
+        /// from a path anchor named after its value, eg. "posts by tag"
+        anchor_field: Option<String>,
+
+        #[structopt(long)]
+        /// The maximum number of links of this type from the same base, eg. 1 for a "profile for agent" link
+        /// Only the agent or the author of the base can create them, and adding a link over the maximum replaces
+        /// the oldest one, so "--delete" must be true
+        /// Only the links deleted by their creator are counted as deleted, so "--only-creator-can-delete" must be true
+        max_links: Option<usize>,
+
         #[structopt(short, long)]
         /// The template to scaffold the dna from
         /// The template must be located at the ".templates/<TEMPLATE NAME>" folder of the repository
@@ -192,7 +327,12 @@ pub enum HcScaffold {
         /// Name of the integrity zome in which you want to scaffold the link type
         zome: Option<String>,
 
-        /// Collection type: "global" or "by-author"
+        #[structopt(long)]
+        /// Name of the coordinator zome in which you want to scaffold the collection getter functions
+        /// Only needed if more than one coordinator zome depends on the integrity zome
+        coordinator_zome: Option<String>,
+
+        /// Collection type: "global", "by-author" or "time-indexed"
         collection_type: Option<CollectionType>,
 
         /// Collection name, just to differentiate it from other collections
@@ -207,6 +347,43 @@ pub enum HcScaffold {
         /// The template must be located at the ".templates/<TEMPLATE NAME>" folder of the repository
         template: Option<String>,
     },
+    /// Scaffold everything described in an app spec file (YAML or JSON), skipping what already exists
+    /// Creates the web-app first if it doesn't exist yet
+    Apply {
+        /// Path to the app spec file
+        spec: PathBuf,
+    },
+    /// List the apps in this repository, with their roles, DNAs, zomes, entry types, link types, collections and extern functions
+    /// Use "--output json" to get the listing as JSON
+    List,
+    /// Check the app for inconsistencies between its manifests, its cargo workspace and its code,
+    /// with a suggested fix for each of them
+    Doctor {
+        #[structopt(long)]
+        /// Repair the inconsistencies that only need changes in the manifests (happ.yaml, dna.yaml and the workspace Cargo.toml)
+        fix: bool,
+
+        #[structopt(long)]
+        /// With "--fix", also remove from the DNA manifests the zomes whose crate can't be found in this repository
+        /// Check first that the crate is really gone, and not just at a different path
+        remove_missing_zomes: bool,
+    },
+    /// Remove an entry type, link type, collection, zome or DNA, together with everything that was scaffolded for it
+    Remove(HcScaffoldRemove),
+    /// Rename an entry type, together with everything that was scaffolded for it
+    Rename(HcScaffoldRename),
+    /// Add a field to an existing entry type, or remove one from it
+    Field(HcScaffoldField),
+    /// Undo the changes made to this folder by the last commands of the scaffolding tool
+    Undo {
+        #[structopt(default_value = "1")]
+        /// Number of commands to undo, starting from the most recent one
+        count: usize,
+
+        #[structopt(long)]
+        /// Restore the files even if they were changed after the commands being undone
+        force: bool,
+    },
 
     Example {
         /// Name of the example to scaffold. One of ['hello-world', 'forum'].
@@ -221,8 +398,20 @@ pub enum HcScaffold {
 
 impl HcScaffold {
     pub async fn run(self) -> anyhow::Result<()> {
-        match self {
-            HcScaffold::WebApp {
+        let dry_run = self.dry_run;
+        let output = self.output;
+
+        if self.no_prompt || !std::io::stdin().is_terminal() {
+            disable_prompts();
+
+            let missing_arguments = self.command.missing_arguments();
+            if !missing_arguments.is_empty() {
+                return Err(ScaffoldError::MissingArguments(missing_arguments))?;
+            }
+        }
+
+        match self.command {
+            HcScaffoldCommand::WebApp {
                 name,
                 description,
                 setup_nix,
@@ -310,17 +499,19 @@ impl HcScaffold {
                     holo_enabled,
                 )?;
 
-                let file_tree = MergeableFileSystemTree::<OsString, String>::from(dir! {
+                let file_tree = dir! {
                     name.clone() => file_tree
-                });
+                };
 
-                file_tree.build(&".".into())?;
+                let changes = write_file_tree(file_tree, Path::new("."), dry_run)?;
 
                 let mut maybe_nix = "";
 
                 let app_dir = std::env::current_dir()?.join(&name);
-                if setup_nix {
-                    if let Err(err) = setup_nix_developer_environment(&app_dir) {
+                if setup_nix && !dry_run {
+                    if let Err(err) =
+                        setup_nix_developer_environment(&app_dir, output == OutputFormat::Json)
+                    {
                         fs::remove_dir_all(&app_dir)?;
 
                         return Err(err)?;
@@ -329,7 +520,13 @@ impl HcScaffold {
                     maybe_nix = "\n  nix develop";
                 }
 
-                setup_git_environment(&app_dir)?;
+                if !dry_run {
+                    setup_git_environment(&app_dir, output == OutputFormat::Json)?;
+                }
+
+                if report_changes(&changes, &next_instructions, dry_run, output)? {
+                    return Ok(());
+                }
 
                 println!(
                     r#"
@@ -365,10 +562,25 @@ Then, at any point in time you can start your application with:
                     );
                 }
             }
-            HcScaffold::Template(template) => template.run()?,
-            HcScaffold::Dna {
+            HcScaffoldCommand::Template(template) => template.run(dry_run, output)?,
+            HcScaffoldCommand::Remove(remove) => remove.run(dry_run, output)?,
+            HcScaffoldCommand::Rename(rename) => rename.run(dry_run, output)?,
+            HcScaffoldCommand::Field(field) => field.run(dry_run, output)?,
+            HcScaffoldCommand::Undo { count, force } => {
+                let (undone, changes) = undo(Path::new("."), count, force, dry_run)?;
+
+                if report_changes(&changes, &None, dry_run, output)? {
+                    return Ok(());
+                }
+
+                for entry in undone {
+                    println!(r#"Undid "{}""#, entry.command_line());
+                }
+            }
+            HcScaffoldCommand::Dna {
                 app,
                 name,
+                path,
                 template,
             } => {
                 let prompt = String::from("DNA name (snake_case):");
@@ -390,11 +602,13 @@ Then, at any point in time you can start your application with:
                 let ScaffoldedTemplate {
                     file_tree,
                     next_instructions,
-                } = scaffold_dna(app_file_tree, &template_file_tree, &name)?;
+                } = scaffold_dna(app_file_tree, &template_file_tree, &name, &path)?;
 
-                let file_tree = MergeableFileSystemTree::<OsString, String>::from(file_tree);
+                let changes = write_file_tree(file_tree, Path::new("."), dry_run)?;
 
-                file_tree.build(&".".into())?;
+                if report_changes(&changes, &next_instructions, dry_run, output)? {
+                    return Ok(());
+                }
 
                 println!(
                     r#"
@@ -414,11 +628,12 @@ Add new zomes to your DNA with:
                     );
                 }
             }
-            HcScaffold::Zome {
+            HcScaffoldCommand::Zome {
                 dna,
                 name,
                 integrity,
                 coordinator,
+                dependencies,
                 template,
             } => {
                 let current_dir = std::env::current_dir()?;
@@ -451,6 +666,12 @@ Add new zomes to your DNA with:
                         _ => (integrity.is_some(), coordinator.is_some()),
                     };
 
+                if dependencies.is_some() && (scaffold_integrity || !scaffold_coordinator) {
+                    return Err(ScaffoldError::InvalidArguments(String::from(
+                        "\"--dependencies\" can only be given when scaffolding only a coordinator zome, the coordinator zome of a zome-pair depends on its integrity zome",
+                    )))?;
+                }
+
                 let name_prompt = match (scaffold_integrity, scaffold_coordinator) {
                     (true, true) => String::from("Enter coordinator zome name (snake_case):\n (The integrity zome will automatically be named '{name of coordinator zome}_integrity')\n"),
                     _ => String::from("Enter zome name (snake_case):"),
@@ -483,7 +704,9 @@ Add new zomes to your DNA with:
 
                     zome_next_instructions.0 = next_instructions;
 
-                    println!(r#"Integrity zome "{}" scaffolded!"#, integrity_zome_name);
+                    if output == OutputFormat::Text {
+                        println!(r#"Integrity zome "{}" scaffolded!"#, integrity_zome_name);
+                    }
 
                     dna_file_tree =
                         DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
@@ -492,12 +715,20 @@ Add new zomes to your DNA with:
                 if scaffold_coordinator {
                     let dependencies = match scaffold_integrity {
                         true => Some(vec![integrity_zome_name(&name)]),
-                        false => {
-                            let integrity_zomes = select_integrity_zomes(&dna_file_tree.dna_manifest, Some(&String::from(
-                              "Select integrity zome(s) this coordinator zome depends on (SPACE to select/unselect, ENTER to continue):"
-                            )))?;
-                            Some(integrity_zomes)
-                        }
+                        false => match dependencies {
+                            Some(dependencies) => Some(
+                                dependencies
+                                    .into_iter()
+                                    .filter(|d| !d.trim().is_empty())
+                                    .collect(),
+                            ),
+                            None => {
+                                let integrity_zomes = select_integrity_zomes(&dna_file_tree.dna_manifest, Some(&String::from(
+                                  "Select integrity zome(s) this coordinator zome depends on (SPACE to select/unselect, ENTER to continue):"
+                                )))?;
+                                Some(integrity_zomes)
+                            }
+                        },
                     };
                     let ScaffoldedTemplate {
                         file_tree,
@@ -511,7 +742,9 @@ Add new zomes to your DNA with:
                     )?;
                     zome_next_instructions.1 = next_instructions;
 
-                    println!(r#"Coordinator zome "{}" scaffolded!"#, name);
+                    if output == OutputFormat::Text {
+                        println!(r#"Coordinator zome "{}" scaffolded!"#, name);
+                    }
 
                     dna_file_tree =
                         DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
@@ -519,15 +752,24 @@ Add new zomes to your DNA with:
 
                 // TODO: implement scaffold_zome_template
 
-                let file_tree =
-                    MergeableFileSystemTree::<OsString, String>::from(dna_file_tree.file_tree());
+                let file_tree = dna_file_tree.file_tree();
 
-                let f = file_tree.clone();
+                let f = MergeableFileSystemTree::<OsString, String>::from(file_tree.clone());
 
-                file_tree.build(&".".into())?;
+                let changes = write_file_tree(file_tree, Path::new("."), dry_run)?;
 
-                // Execute cargo metadata to set up the cargo workspace in case this zome is the first crate
-                exec_metadata(&f)?;
+                if !dry_run {
+                    // Execute cargo metadata to set up the cargo workspace in case this zome is the first crate
+                    exec_metadata(&f)?;
+                }
+
+                let next_instructions = match zome_next_instructions.clone() {
+                    (Some(ii), Some(ci)) => Some(format!("{}\n{}", ii, ci)),
+                    (ii, ci) => ii.or(ci),
+                };
+                if report_changes(&changes, &next_instructions, dry_run, output)? {
+                    return Ok(());
+                }
 
                 match zome_next_instructions {
                     (Some(ii), Some(ci)) => {
@@ -545,26 +787,35 @@ Add new entry definitions to your zome with:
                     ),
                 }
             }
-            HcScaffold::EntryType {
+            HcScaffoldCommand::EntryType {
                 dna,
                 zome,
+                coordinator_zome,
                 name,
                 crud,
                 reference_entry_hash,
                 link_from_original_to_each_update,
+                only_author_can_update_and_delete,
                 fields,
+                from_struct,
+                visibility,
+                required_validations,
+                cache_at_agent_activity,
                 template,
             } => {
                 let current_dir = std::env::current_dir()?;
                 let file_tree = load_directory_into_memory(&current_dir)?;
                 let template_file_tree = choose_or_get_template_file_tree(&file_tree, &template)?;
 
-                let name: String = match name {
-                    Some(n) => {
+                let name: String = match (name, &from_struct) {
+                    (Some(n), _) => {
                         check_case(&n, "entry type name", Case::Snake)?;
                         n
                     }
-                    None => input_with_case(
+                    (None, Some(struct_reference)) => {
+                        struct_reference.struct_name.to_case(Case::Snake)
+                    }
+                    (None, None) => input_with_case(
                         &String::from("Entry type name (snake_case):"),
                         Case::Snake,
                     )?,
@@ -574,6 +825,37 @@ Add new entry definitions to your zome with:
 
                 let zome_file_tree = ZomeFileTree::get_or_choose_integrity(dna_file_tree, &zome)?;
 
+                let settings = EntryDefSettings {
+                    visibility: visibility.unwrap_or_default(),
+                    required_validations,
+                    cache_at_agent_activity,
+                };
+
+                let (fields, settings, keep_existing_definition) = match &from_struct {
+                    None => (fields, settings, false),
+                    Some(_) if fields.is_some() => {
+                        return Err(ScaffoldError::InvalidArguments(String::from(
+                            "\"--fields\" and \"--from-struct\" can't be given together",
+                        )))?;
+                    }
+                    Some(struct_reference) => {
+                        let StructEntryType {
+                            fields,
+                            existing_settings,
+                        } = read_struct_entry_type(
+                            &zome_file_tree,
+                            &template_file_tree,
+                            &name,
+                            struct_reference,
+                        )?;
+
+                        match existing_settings {
+                            Some(existing_settings) => (Some(fields), existing_settings, true),
+                            None => (Some(fields), settings, false),
+                        }
+                    }
+                };
+
                 let ScaffoldedTemplate {
                     file_tree,
                     next_instructions,
@@ -584,12 +866,18 @@ Add new entry definitions to your zome with:
                     &crud,
                     &reference_entry_hash,
                     &link_from_original_to_each_update,
+                    &only_author_can_update_and_delete,
                     &fields,
+                    &coordinator_zome,
+                    &settings,
+                    keep_existing_definition,
                 )?;
 
-                let file_tree = MergeableFileSystemTree::<OsString, String>::from(file_tree);
+                let changes = write_file_tree(file_tree, Path::new("."), dry_run)?;
 
-                file_tree.build(&".".into())?;
+                if report_changes(&changes, &next_instructions, dry_run, output)? {
+                    return Ok(());
+                }
 
                 println!(
                     r#"
@@ -609,13 +897,69 @@ Add new collections for that entry type with:
                     );
                 }
             }
-            HcScaffold::LinkType {
+            HcScaffoldCommand::EntryTypeVersion { dna, zome, name } => {
+                let current_dir = std::env::current_dir()?;
+                let file_tree = load_directory_into_memory(&current_dir)?;
+
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &dna)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(dna_file_tree, &zome)?;
+
+                let name = match name {
+                    Some(name) => name,
+                    None => {
+                        check_can_prompt("entry type name", "<NAME>")?;
+
+                        let entry_types: Vec<String> = get_all_entry_types(&zome_file_tree)?
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|et| et.entry_type.to_case(Case::Snake))
+                            .collect();
+                        if entry_types.is_empty() {
+                            return Err(ScaffoldError::InvalidArguments(String::from(
+                                "there is no entry type to version",
+                            )))?;
+                        }
+
+                        let selection = Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Which entry type do you want to version?")
+                            .default(0)
+                            .items(&entry_types[..])
+                            .interact()?;
+                        entry_types[selection].clone()
+                    }
+                };
+
+                let (version_name, file_tree) = version_entry_type(zome_file_tree, &name)?;
+
+                let changes = write_file_tree(file_tree, Path::new("."), dry_run)?;
+
+                if report_changes(&changes, &None, dry_run, output)? {
+                    return Ok(());
+                }
+
+                let pascal_name = name.to_case(Case::Pascal);
+                println!(
+                    r#"
+Entry type "{}" versioned: its current struct was kept as "{}"!
+
+You can now change the fields of the "{}" struct, and then update the conversion
+from the previous version in "impl From<{}> for {}" to fill them in.
+"#,
+                    name, version_name, pascal_name, version_name, pascal_name
+                );
+            }
+            HcScaffoldCommand::LinkType {
                 dna,
                 zome,
+                coordinator_zome,
                 from_referenceable,
                 to_referenceable,
                 delete,
+                only_creator_can_delete,
                 bidireccional,
+                tag_fields,
+                anchor_field,
+                max_links,
                 template,
             } => {
                 let current_dir = std::env::current_dir()?;
@@ -635,12 +979,19 @@ Add new collections for that entry type with:
                     &from_referenceable,
                     &to_referenceable,
                     &delete,
+                    &only_creator_can_delete,
                     &bidireccional,
+                    &tag_fields,
+                    &anchor_field,
+                    &max_links,
+                    &coordinator_zome,
                 )?;
 
-                let file_tree = MergeableFileSystemTree::<OsString, String>::from(file_tree);
+                let changes = write_file_tree(file_tree, Path::new("."), dry_run)?;
 
-                file_tree.build(&".".into())?;
+                if report_changes(&changes, &next_instructions, dry_run, output)? {
+                    return Ok(());
+                }
 
                 println!(
                     r#"
@@ -651,9 +1002,10 @@ Link type scaffolded!
                     println!("{}", i);
                 }
             }
-            HcScaffold::Collection {
+            HcScaffoldCommand::Collection {
                 dna,
                 zome,
+                coordinator_zome,
                 collection_name,
                 collection_type,
                 entry_type,
@@ -685,11 +1037,14 @@ Link type scaffolded!
                     &name,
                     &collection_type,
                     &entry_type,
+                    &coordinator_zome,
                 )?;
 
-                let file_tree = MergeableFileSystemTree::<OsString, String>::from(file_tree);
+                let changes = write_file_tree(file_tree, Path::new("."), dry_run)?;
 
-                file_tree.build(&".".into())?;
+                if report_changes(&changes, &next_instructions, dry_run, output)? {
+                    return Ok(());
+                }
 
                 println!(
                     r#"
@@ -702,14 +1057,175 @@ Collection "{}" scaffolded!
                     println!("{}", i);
                 }
             }
-            HcScaffold::Example { example, template } => {
+            HcScaffoldCommand::Apply { spec } => {
+                let spec = read_app_spec(&spec)?;
+
+                let current_dir = std::env::current_dir()?;
+
+                let existing_app_dir = match current_dir.join(&spec.name).exists() {
+                    true => Some(current_dir.join(&spec.name)),
+                    false => {
+                        let file_tree = load_directory_into_memory(&current_dir)?;
+                        match find_app_manifests(&file_tree)?
+                            .values()
+                            .any(|m| m.app_name().to_string().eq(&spec.name))
+                        {
+                            true => Some(current_dir.clone()),
+                            false => None,
+                        }
+                    }
+                };
+
+                let (app_dir, file_tree, template_file_tree) = match existing_app_dir {
+                    Some(app_dir) => {
+                        let file_tree = load_directory_into_memory(&app_dir)?;
+                        let template_file_tree =
+                            choose_or_get_template_file_tree(&file_tree, &spec.template)?;
+                        (app_dir, file_tree, template_file_tree)
+                    }
+                    None => {
+                        let ui_framework = match &spec.template {
+                            Some(t) => UiFramework::from_str(t.as_str())?,
+                            None => {
+                                check_can_prompt(
+                                    "template",
+                                    "the \"template\" field of the app spec",
+                                )?;
+                                choose_ui_framework()?
+                            }
+                        };
+                        let template_file_tree = template_for_ui_framework(&ui_framework)?;
+
+                        let ScaffoldedTemplate { file_tree, .. } = scaffold_web_app(
+                            spec.name.clone(),
+                            spec.description.clone(),
+                            !spec.setup_nix,
+                            &template_file_tree,
+                            format!("{:?}", ui_framework),
+                            false,
+                            false,
+                        )?;
+                        (current_dir.join(&spec.name), file_tree, template_file_tree)
+                    }
+                };
+                let app_is_new = !app_dir.exists();
+
+                // The intermediate steps are built in a temporary directory that gets removed afterwards,
+                // so that the final changes include every file of the spec, both in a dry run and in a real one
+                let spec_temp_dir = TempDir::new()?;
+
+                let AppliedSpec {
+                    file_tree,
+                    scaffolded,
+                    skipped,
+                } = apply_app_spec(file_tree, &template_file_tree, &spec, spec_temp_dir.path())?;
+
+                std::env::set_current_dir(&current_dir)?;
+
+                let changes = write_file_tree(file_tree, &app_dir, dry_run)?;
+
+                if app_is_new && !dry_run {
+                    if spec.setup_nix {
+                        if let Err(err) =
+                            setup_nix_developer_environment(&app_dir, output == OutputFormat::Json)
+                        {
+                            fs::remove_dir_all(&app_dir)?;
+
+                            return Err(err)?;
+                        }
+                    }
+
+                    setup_git_environment(&app_dir, output == OutputFormat::Json)?;
+                }
+
+                let changes = relative_to(changes, &app_dir, &current_dir);
+                if report_changes(&changes, &None, dry_run, output)? {
+                    return Ok(());
+                }
+
+                println!(
+                    r#"
+App spec for "{}" applied!
+"#,
+                    spec.name
+                );
+                for s in scaffolded {
+                    println!("  scaffolded: {}", s);
+                }
+                for s in skipped {
+                    println!("  skipped (already exists): {}", s);
+                }
+            }
+            HcScaffoldCommand::List => {
+                let current_dir = std::env::current_dir()?;
+                let file_tree = load_directory_into_memory(&current_dir)?;
+
+                let apps = list_apps(&file_tree)?;
+
+                match output {
+                    OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&apps)?),
+                    OutputFormat::Text if apps.is_empty() => {
+                        println!("No apps were found in this directory.")
+                    }
+                    OutputFormat::Text => print!("{}", render_apps_tree(&apps)),
+                }
+            }
+            HcScaffoldCommand::Doctor {
+                fix,
+                remove_missing_zomes,
+            } => {
+                let current_dir = std::env::current_dir()?;
+                let mut file_tree = load_directory_into_memory(&current_dir)?;
+
+                let inconsistencies = check_app_consistency(&file_tree)?;
+
+                let (repaired, remaining): (Vec<Inconsistency>, Vec<Inconsistency>) = match fix {
+                    true => inconsistencies.into_iter().partition(|i| match &i.repair {
+                        Some(repair) => remove_missing_zomes || !repair.is_destructive(),
+                        None => false,
+                    }),
+                    false => (vec![], inconsistencies),
+                };
+
+                for repair in repaired.iter().filter_map(|i| i.repair.as_ref()) {
+                    file_tree = apply_repair(file_tree, repair)?;
+                }
+
+                let changes = match repaired.is_empty() {
+                    true => BTreeMap::new(),
+                    false => write_file_tree(file_tree, Path::new("."), dry_run)?,
+                };
+
+                match output {
+                    OutputFormat::Json => {
+                        let report = DoctorReport {
+                            inconsistencies: remaining.clone(),
+                            repaired,
+                            modified: changes.keys().cloned().collect(),
+                        };
+                        println!("{}", serde_json::to_string_pretty(&report)?);
+                    }
+                    OutputFormat::Text => {
+                        if dry_run && !changes.is_empty() {
+                            print!("{}", unified_diff(&changes));
+                        }
+                        print_doctor_report(&remaining, &repaired, dry_run);
+                    }
+                }
+
+                if !remaining.is_empty() {
+                    return Err(ScaffoldError::InconsistenciesFound(remaining.len()))?;
+                }
+            }
+            HcScaffoldCommand::Example { example, template } => {
                 let example = match example {
                     Some(e) => e,
                     None => choose_example()?,
                 };
                 let name = example.to_string();
 
-                let app_dir = std::env::current_dir()?.join(&name);
+                let current_dir = std::env::current_dir()?;
+                let app_dir = current_dir.join(&name);
                 if app_dir.as_path().exists() {
                     return Err(ScaffoldError::FolderAlreadyExists(app_dir.clone()))?;
                 }
@@ -725,6 +1241,10 @@ Collection "{}" scaffolded!
                 let template_file_tree = template_for_ui_framework(&ui_framework)?;
                 let template_name = format!("{:?}", ui_framework);
 
+                // The intermediate app is built in a temporary directory, so that the final changes
+                // include every file of the example, both in a dry run and in a real one
+                let example_temp_dir = TempDir::new()?;
+
                 // Match on example types
                 let file_tree = match example {
                     Example::HelloWorld => {
@@ -759,7 +1279,7 @@ Collection "{}" scaffolded!
                         let app_file_tree =
                             AppFileTree::get_or_choose(file_tree, &Some(name.clone()))?;
                         let ScaffoldedTemplate { file_tree, .. } =
-                            scaffold_dna(app_file_tree, &template_file_tree, &dna_name)?;
+                            scaffold_dna(app_file_tree, &template_file_tree, &dna_name, &None)?;
 
                         // scaffold integrity zome posts
                         let dna_file_tree =
@@ -800,9 +1320,9 @@ Collection "{}" scaffolded!
 
                         // Scaffold the app here to enable ZomeFileTree::from_manifest(), which calls `cargo metadata`
                         MergeableFileSystemTree::<OsString, String>::from(file_tree.clone())
-                            .build(&app_dir)?;
+                            .build(&example_temp_dir.path().to_path_buf())?;
 
-                        std::env::set_current_dir(&app_dir)?;
+                        std::env::set_current_dir(example_temp_dir.path())?;
 
                         let dna_file_tree =
                             DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
@@ -819,9 +1339,11 @@ Collection "{}" scaffolded!
                             &Some(Crud {
                                 update: true,
                                 delete: true,
+                                author_only: false,
                             }),
                             &Some(false),
                             &Some(true),
+                            &Some(false),
                             &Some(vec![
                                 FieldDefinition {
                                     field_name: String::from("title"),
@@ -829,6 +1351,7 @@ Collection "{}" scaffolded!
                                     widget: Some(String::from("TextField")),
                                     cardinality: Cardinality::Single,
                                     linked_from: None,
+                                    constraints: FieldConstraints::default(),
                                 },
                                 FieldDefinition {
                                     field_name: String::from("content"),
@@ -836,8 +1359,12 @@ Collection "{}" scaffolded!
                                     widget: Some(String::from("TextArea")),
                                     cardinality: Cardinality::Single,
                                     linked_from: None,
+                                    constraints: FieldConstraints::default(),
                                 },
                             ]),
+                            &None,
+                            &EntryDefSettings::default(),
+                            false,
                         )?;
 
                         let dna_file_tree =
@@ -855,9 +1382,11 @@ Collection "{}" scaffolded!
                             &Some(Crud {
                                 update: false,
                                 delete: true,
+                                author_only: false,
                             }),
                             &Some(false),
                             &Some(true),
+                            &Some(false),
                             &Some(vec![
                                 FieldDefinition {
                                     field_name: String::from("comment"),
@@ -865,6 +1394,7 @@ Collection "{}" scaffolded!
                                     widget: Some(String::from("TextArea")),
                                     cardinality: Cardinality::Single,
                                     linked_from: None,
+                                    constraints: FieldConstraints::default(),
                                 },
                                 FieldDefinition {
                                     field_name: String::from("post_hash"),
@@ -877,8 +1407,12 @@ Collection "{}" scaffolded!
                                             reference_entry_hash: false,
                                         },
                                     )),
+                                    constraints: FieldConstraints::default(),
                                 },
                             ]),
+                            &None,
+                            &EntryDefSettings::default(),
+                            false,
                         )?;
 
                         let dna_file_tree =
@@ -898,6 +1432,7 @@ Collection "{}" scaffolded!
                                 entry_type: String::from("post"),
                                 reference_entry_hash: false,
                             }),
+                            &None,
                         )?;
 
                         file_tree
@@ -909,18 +1444,27 @@ Collection "{}" scaffolded!
                     next_instructions,
                 } = scaffold_example(file_tree, &template_file_tree, &example)?;
 
-                let file_tree = MergeableFileSystemTree::<OsString, String>::from(file_tree);
+                std::env::set_current_dir(&current_dir)?;
 
-                file_tree.build(&app_dir)?;
+                let changes = write_file_tree(file_tree, &app_dir, dry_run)?;
+
+                if !dry_run {
+                    // set up nix
+                    if let Err(err) =
+                        setup_nix_developer_environment(&app_dir, output == OutputFormat::Json)
+                    {
+                        fs::remove_dir_all(&app_dir)?;
 
-                // set up nix
-                if let Err(err) = setup_nix_developer_environment(&app_dir) {
-                    fs::remove_dir_all(&app_dir)?;
+                        return Err(err)?;
+                    }
 
-                    return Err(err)?;
+                    setup_git_environment(&app_dir, output == OutputFormat::Json)?;
                 }
 
-                setup_git_environment(&app_dir)?;
+                let changes = relative_to(changes, &app_dir, &current_dir);
+                if report_changes(&changes, &next_instructions, dry_run, output)? {
+                    return Ok(());
+                }
 
                 println!(
                     r#"
@@ -939,73 +1483,243 @@ Example "{}" scaffolded!
     }
 }
 
-#[derive(Debug, StructOpt)]
-#[structopt(setting = structopt::clap::AppSettings::InferSubcommands)]
-pub enum HcScaffoldTemplate {
-    /// Download a custom template from a remote repository to this folder
-    Get {
-        /// The git repository URL from which to download the template
-        template_url: String,
-
-        #[structopt(long)]
-        /// The template to get from the given repository (located at ".templates/<FROM TEMPLATE>")
-        from_template: Option<String>,
-
-        #[structopt(long)]
-        /// The folder to download the template to, will end up at ".templates/<TO TEMPLATE>"
-        to_template: Option<String>,
-    },
-    /// Initialize a new custom template from a built-in one
-    Init {
-        /// The UI framework to use as the template for this web-app
-        template: Option<UiFramework>,
-
-        #[structopt(long)]
-        /// The folder to download the template to, will end up at ".templates/<TO TEMPLATE>"
-        to_template: Option<String>,
-    },
-}
-
-fn existing_templates_names(file_tree: &FileTree) -> ScaffoldResult<Vec<String>> {
-    let templates_path = PathBuf::new().join(templates_path());
-
-    match dir_content(file_tree, &templates_path) {
-        Ok(templates_dir_content) => {
-            let templates: Vec<String> = templates_dir_content
-                .into_keys()
-                .map(|k| k.to_str().unwrap().to_string())
-                .collect();
-            Ok(templates)
-        }
-        _ => Ok(vec![]),
-    }
-}
-
-fn choose_existing_template(file_tree: &FileTree) -> ScaffoldResult<String> {
-    let templates = existing_templates_names(file_tree)?;
-    match templates.len() {
-        0 => Err(ScaffoldError::NoTemplatesFound),
-        1 => Ok(templates[0].clone()),
-        _ => {
-            let option = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt("Which existing template should the new template be merged into?")
-                .default(0)
-                .items(&templates[..])
-                .interact()?;
-
-            Ok(templates[option].clone())
-        }
-    }
-}
-
-impl HcScaffoldTemplate {
-    pub fn run(self) -> anyhow::Result<()> {
-        let (template_name, template_file_tree) = self.get_template_file_tree()?;
+impl HcScaffoldCommand {
+    /// Returns the arguments that weren't given and would otherwise be prompted for,
+    /// together with the flag that supplies each of them
+    pub fn missing_arguments(&self) -> Vec<(String, String)> {
+        let mut missing: Vec<(&str, &str)> = vec![];
 
-        let target_template = match self.target_template() {
-            Some(t) => t,
-            None => {
-                let current_dir = std::env::current_dir()?;
+        // "--coordinator-zome" is only needed if more than one coordinator zome depends on the integrity zome,
+        // so it's checked while scaffolding instead
+        match self {
+            HcScaffoldCommand::WebApp {
+                name,
+                setup_nix,
+                templates_url,
+                templates_path,
+                template,
+                ..
+            } => {
+                if name.is_none() {
+                    missing.push(("app name", "<NAME>"));
+                }
+                if setup_nix.is_none() {
+                    missing.push(("whether to set up holonix", "--setup-nix <true|false>"));
+                }
+                if templates_url.is_none() && templates_path.is_none() && template.is_none() {
+                    missing.push(("template", "--template <TEMPLATE>"));
+                }
+            }
+            HcScaffoldCommand::Template(template) => return template.missing_arguments(),
+            HcScaffoldCommand::Remove(remove) => return remove.missing_arguments(),
+            HcScaffoldCommand::Rename(rename) => return rename.missing_arguments(),
+            HcScaffoldCommand::Field(field) => return field.missing_arguments(),
+            HcScaffoldCommand::Dna { name, .. } => {
+                if name.is_none() {
+                    missing.push(("DNA name", "<NAME>"));
+                }
+            }
+            HcScaffoldCommand::Zome {
+                name,
+                integrity,
+                coordinator,
+                dependencies,
+                ..
+            } => {
+                if name.is_none() {
+                    missing.push(("zome name", "<NAME>"));
+                }
+                if integrity.is_none() && coordinator.is_none() {
+                    missing.push((
+                        "which zomes to scaffold",
+                        "--integrity <PATH> and/or --coordinator <PATH>",
+                    ));
+                }
+                if integrity.is_none() && coordinator.is_some() && dependencies.is_none() {
+                    missing.push((
+                        "integrity zomes the coordinator zome depends on",
+                        "--dependencies <ZOME,...>",
+                    ));
+                }
+            }
+            HcScaffoldCommand::EntryType {
+                name,
+                crud,
+                link_from_original_to_each_update,
+                only_author_can_update_and_delete,
+                fields,
+                from_struct,
+                ..
+            } => {
+                if name.is_none() && from_struct.is_none() {
+                    missing.push(("entry type name", "<NAME>"));
+                }
+                if fields.is_none() && from_struct.is_none() {
+                    missing.push((
+                        "fields",
+                        "--fields <FIELDS> or --from-struct <PATH>::<STRUCT>",
+                    ));
+                }
+                match crud {
+                    None => missing.push(("CRUD functions", "--crud <CRUD>")),
+                    Some(c) => {
+                        if c.update && link_from_original_to_each_update.is_none() {
+                            missing.push((
+                                "whether to link the original entry to each update",
+                                "--link-from-original-to-each-update <true|false>",
+                            ));
+                        }
+                        if (c.update || c.delete) && only_author_can_update_and_delete.is_none() {
+                            missing.push((
+                                "whether only the author of an entry can update and delete it",
+                                "--only-author-can-update-and-delete <true|false>",
+                            ));
+                        }
+                    }
+                }
+            }
+            HcScaffoldCommand::EntryTypeVersion { name, .. } => {
+                if name.is_none() {
+                    missing.push(("entry type name", "<NAME>"));
+                }
+            }
+            HcScaffoldCommand::LinkType {
+                from_referenceable,
+                to_referenceable,
+                bidireccional,
+                delete,
+                only_creator_can_delete,
+                anchor_field,
+                ..
+            } => {
+                if from_referenceable.is_none() {
+                    missing.push(("base entry type", "<FROM_REFERENCEABLE>"));
+                }
+                // The links of an index have no target entry type and can always be deleted
+                if anchor_field.is_none() {
+                    if to_referenceable.is_none() {
+                        missing.push(("target entry type", "<TO_REFERENCEABLE>"));
+                    }
+                    if bidireccional.is_none() {
+                        missing.push((
+                            "whether the link is bidireccional",
+                            "--bidireccional <true|false>",
+                        ));
+                    }
+                    if delete.is_none() {
+                        missing.push(("whether the link can be deleted", "--delete <true|false>"));
+                    }
+                    if *delete == Some(true) && only_creator_can_delete.is_none() {
+                        missing.push((
+                            "whether only the creator of a link can delete it",
+                            "--only-creator-can-delete <true|false>",
+                        ));
+                    }
+                }
+            }
+            HcScaffoldCommand::Collection {
+                collection_type,
+                collection_name,
+                entry_type,
+                ..
+            } => {
+                if collection_type.is_none() {
+                    missing.push(("collection type", "<COLLECTION_TYPE>"));
+                }
+                if collection_name.is_none() {
+                    missing.push(("collection name", "<COLLECTION_NAME>"));
+                }
+                if entry_type.is_none() {
+                    missing.push(("entry type", "<ENTRY_TYPE>"));
+                }
+            }
+            HcScaffoldCommand::Apply { .. } => {}
+            HcScaffoldCommand::List => {}
+            HcScaffoldCommand::Doctor { .. } => {}
+            HcScaffoldCommand::Undo { .. } => {}
+            HcScaffoldCommand::Example { example, template } => match example {
+                None => missing.push(("example", "<EXAMPLE>")),
+                Some(Example::Forum) if template.is_none() => {
+                    missing.push(("template", "--template <TEMPLATE>"))
+                }
+                _ => {}
+            },
+        }
+
+        missing
+            .into_iter()
+            .map(|(argument, flag)| (argument.to_string(), flag.to_string()))
+            .collect()
+    }
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(setting = structopt::clap::AppSettings::InferSubcommands)]
+pub enum HcScaffoldTemplate {
+    /// Download a custom template from a remote repository to this folder
+    Get {
+        /// The git repository URL from which to download the template
+        template_url: String,
+
+        #[structopt(long)]
+        /// The template to get from the given repository (located at ".templates/<FROM TEMPLATE>")
+        from_template: Option<String>,
+
+        #[structopt(long)]
+        /// The folder to download the template to, will end up at ".templates/<TO TEMPLATE>"
+        to_template: Option<String>,
+    },
+    /// Initialize a new custom template from a built-in one
+    Init {
+        /// The UI framework to use as the template for this web-app
+        template: Option<UiFramework>,
+
+        #[structopt(long)]
+        /// The folder to download the template to, will end up at ".templates/<TO TEMPLATE>"
+        to_template: Option<String>,
+    },
+}
+
+fn existing_templates_names(file_tree: &FileTree) -> ScaffoldResult<Vec<String>> {
+    let templates_path = PathBuf::new().join(templates_path());
+
+    match dir_content(file_tree, &templates_path) {
+        Ok(templates_dir_content) => {
+            let templates: Vec<String> = templates_dir_content
+                .into_keys()
+                .map(|k| k.to_str().unwrap().to_string())
+                .collect();
+            Ok(templates)
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+fn choose_existing_template(file_tree: &FileTree) -> ScaffoldResult<String> {
+    let templates = existing_templates_names(file_tree)?;
+    match templates.len() {
+        0 => Err(ScaffoldError::NoTemplatesFound),
+        1 => Ok(templates[0].clone()),
+        _ => {
+            let option = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Which existing template should the new template be merged into?")
+                .default(0)
+                .items(&templates[..])
+                .interact()?;
+
+            Ok(templates[option].clone())
+        }
+    }
+}
+
+impl HcScaffoldTemplate {
+    pub fn run(self, dry_run: bool, output: OutputFormat) -> anyhow::Result<()> {
+        let (template_name, template_file_tree) = self.get_template_file_tree()?;
+
+        let target_template = match self.target_template() {
+            Some(t) => t,
+            None => {
+                let current_dir = std::env::current_dir()?;
 
                 let file_tree = load_directory_into_memory(&current_dir)?;
 
@@ -1044,9 +1758,11 @@ impl HcScaffoldTemplate {
             templates_path().join(&target_template) => template_file_tree
         };
 
-        let file_tree = MergeableFileSystemTree::<OsString, String>::from(template_file_tree);
+        let changes = write_file_tree(template_file_tree, Path::new("."), dry_run)?;
 
-        file_tree.build(&".".into())?;
+        if report_changes(&changes, &None, dry_run, output)? {
+            return Ok(());
+        }
 
         match self {
             HcScaffoldTemplate::Get { .. } => {
@@ -1067,6 +1783,24 @@ impl HcScaffoldTemplate {
         Ok(())
     }
 
+    /// Returns the arguments that weren't given and would otherwise be prompted for,
+    /// together with the flag that supplies each of them
+    pub fn missing_arguments(&self) -> Vec<(String, String)> {
+        let mut missing: Vec<(String, String)> = vec![];
+
+        if let HcScaffoldTemplate::Init { template: None, .. } = self {
+            missing.push((String::from("template"), String::from("<TEMPLATE>")));
+        }
+        if self.target_template().is_none() {
+            missing.push((
+                String::from("target template"),
+                String::from("--to-template <TO_TEMPLATE>"),
+            ));
+        }
+
+        missing
+    }
+
     pub fn target_template(&self) -> Option<String> {
         match self {
             HcScaffoldTemplate::Get {
@@ -1102,22 +1836,772 @@ impl HcScaffoldTemplate {
     }
 }
 
-fn setup_git_environment(path: &PathBuf) -> ScaffoldResult<()> {
+#[derive(Debug, StructOpt)]
+#[structopt(setting = structopt::clap::AppSettings::InferSubcommands)]
+pub enum HcScaffoldRemove {
+    /// Remove an entry type, with its zome functions, the link types to and from it, and its UI components and tests
+    EntryType {
+        #[structopt(long)]
+        /// Name of the dna from which you want to remove the entry type
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome from which you want to remove the entry type
+        zome: Option<String>,
+
+        /// Name of the entry type being removed
+        name: Option<String>,
+    },
+    /// Remove a link type, with its zome functions, its inverse link type, and its UI components and tests
+    LinkType {
+        #[structopt(long)]
+        /// Name of the dna from which you want to remove the link type
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome from which you want to remove the link type
+        zome: Option<String>,
+
+        /// Name of the link type being removed, eg. "PostToComments"
+        name: Option<String>,
+    },
+    /// Remove a collection, with its link type, its zome functions, and its UI components and tests
+    Collection {
+        #[structopt(long)]
+        /// Name of the dna from which you want to remove the collection
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome from which you want to remove the collection
+        zome: Option<String>,
+
+        /// Name of the collection being removed
+        name: Option<String>,
+    },
+    /// Remove a zome, with its crate and its UI components and tests
+    /// Removing a coordinator zome also removes its integrity zome, if no other coordinator zome depends on it
+    Zome {
+        #[structopt(long)]
+        /// Name of the dna from which you want to remove the zome
+        dna: Option<String>,
+
+        /// Name of the zome being removed
+        name: Option<String>,
+    },
+    /// Remove a DNA, with all its zomes, its roles in the apps and its UI components and tests
+    Dna {
+        /// Name of the DNA being removed
+        name: Option<String>,
+    },
+}
+
+impl HcScaffoldRemove {
+    pub fn run(self, dry_run: bool, output: OutputFormat) -> anyhow::Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+        let previous_file_tree = file_tree.clone();
+
+        let (removed, file_tree) = match self {
+            HcScaffoldRemove::EntryType { dna, zome, name } => {
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &dna)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(dna_file_tree, &zome)?;
+
+                let entry_types = get_all_entry_types(&zome_file_tree)?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|et| et.entry_type.to_case(Case::Snake))
+                    .collect();
+                let name = get_or_choose_to_remove("entry type", name, entry_types)?;
+
+                (
+                    format!(r#"Entry type "{}""#, name),
+                    remove_entry_type(zome_file_tree, &name)?,
+                )
+            }
+            HcScaffoldRemove::LinkType { dna, zome, name } => {
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &dna)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(dna_file_tree, &zome)?;
+
+                let link_types = get_all_link_types(&zome_file_tree)?;
+                let name = get_or_choose_to_remove("link type", name, link_types)?;
+
+                (
+                    format!(r#"Link type "{}""#, name),
+                    remove_link_type(zome_file_tree, &name)?,
+                )
+            }
+            HcScaffoldRemove::Collection { dna, zome, name } => {
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &dna)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(dna_file_tree, &zome)?;
+
+                // Collections are link types with a "get_{collection_name}" extern function to retrieve them
+                let coordinator_zomes = get_coordinator_zomes_for_integrity(
+                    &zome_file_tree.dna_file_tree.dna_manifest,
+                    &zome_file_tree.zome_manifest.name.0.to_string(),
+                );
+                let mut collections = vec![];
+                for link_type in get_all_link_types(&zome_file_tree)? {
+                    let collection = link_type.to_case(Case::Snake);
+                    if find_extern_function_in_zomes(
+                        &zome_file_tree.dna_file_tree,
+                        &coordinator_zomes,
+                        &format!("get_{}", collection),
+                    )?
+                    .is_some()
+                    {
+                        collections.push(collection);
+                    }
+                }
+                let name = get_or_choose_to_remove("collection", name, collections)?;
+
+                (
+                    format!(r#"Collection "{}""#, name),
+                    remove_collection(zome_file_tree, &name)?,
+                )
+            }
+            HcScaffoldRemove::Zome { dna, name } => {
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &dna)?;
+
+                let zomes = match &dna_file_tree.dna_manifest {
+                    DnaManifest::V1(m) => m
+                        .coordinator
+                        .zomes
+                        .iter()
+                        .chain(m.integrity.zomes.iter())
+                        .map(|z| z.name.0.to_string())
+                        .collect(),
+                };
+                let name = get_or_choose_to_remove("zome", name, zomes)?;
+
+                (
+                    format!(r#"Zome "{}""#, name),
+                    remove_zome(dna_file_tree, &name)?,
+                )
+            }
+            HcScaffoldRemove::Dna { name } => {
+                let dnas = find_dna_manifests(&file_tree)?
+                    .into_values()
+                    .map(|m| m.name())
+                    .collect();
+                let name = get_or_choose_to_remove("DNA", name, dnas)?;
+
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &Some(name.clone()))?;
+
+                (format!(r#"DNA "{}""#, name), remove_dna(dna_file_tree)?)
+            }
+        };
+
+        let changes =
+            write_file_tree_with_removals(&previous_file_tree, file_tree, Path::new("."), dry_run)?;
+
+        if report_changes(&changes, &None, dry_run, output)? {
+            return Ok(());
+        }
+
+        println!(
+            r#"
+{} removed!
+"#,
+            removed
+        );
+
+        Ok(())
+    }
+
+    /// Returns the arguments that weren't given and would otherwise be prompted for,
+    /// together with the flag that supplies each of them
+    pub fn missing_arguments(&self) -> Vec<(String, String)> {
+        let argument = match self {
+            HcScaffoldRemove::EntryType { name: None, .. } => Some("entry type name"),
+            HcScaffoldRemove::LinkType { name: None, .. } => Some("link type name"),
+            HcScaffoldRemove::Collection { name: None, .. } => Some("collection name"),
+            HcScaffoldRemove::Zome { name: None, .. } => Some("zome name"),
+            HcScaffoldRemove::Dna { name: None } => Some("DNA name"),
+            _ => None,
+        };
+
+        argument
+            .map(|argument| vec![(argument.to_string(), String::from("<NAME>"))])
+            .unwrap_or_default()
+    }
+}
+
+fn get_or_choose_to_remove(
+    kind: &str,
+    name: Option<String>,
+    options: Vec<String>,
+) -> ScaffoldResult<String> {
+    match name {
+        Some(name) => Ok(name),
+        None => {
+            check_can_prompt(kind, "<NAME>")?;
+
+            if options.is_empty() {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "there is no {} to remove",
+                    kind
+                )));
+            }
+
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Which {} do you want to remove?", kind))
+                .default(0)
+                .items(&options[..])
+                .interact()?;
+
+            Ok(options[selection].clone())
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(setting = structopt::clap::AppSettings::InferSubcommands)]
+pub enum HcScaffoldRename {
+    /// Rename an entry type, with its zome functions, the link types and collections named after it,
+    /// and its UI components and tests
+    EntryType {
+        #[structopt(long)]
+        /// Name of the dna in which you want to rename the entry type
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome in which you want to rename the entry type
+        zome: Option<String>,
+
+        /// Name of the entry type being renamed
+        name: Option<String>,
+
+        /// New name for the entry type (snake_case)
+        new_name: Option<String>,
+    },
+}
+
+impl HcScaffoldRename {
+    pub fn run(self, dry_run: bool, output: OutputFormat) -> anyhow::Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+        let previous_file_tree = file_tree.clone();
+
+        let (renamed, file_tree) = match self {
+            HcScaffoldRename::EntryType {
+                dna,
+                zome,
+                name,
+                new_name,
+            } => {
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &dna)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(dna_file_tree, &zome)?;
+
+                let name = match name {
+                    Some(name) => name,
+                    None => {
+                        check_can_prompt("entry type name", "<NAME>")?;
+
+                        let entry_types: Vec<String> = get_all_entry_types(&zome_file_tree)?
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|et| et.entry_type.to_case(Case::Snake))
+                            .collect();
+                        if entry_types.is_empty() {
+                            return Err(ScaffoldError::InvalidArguments(String::from(
+                                "there is no entry type to rename",
+                            )))?;
+                        }
+
+                        let selection = Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Which entry type do you want to rename?")
+                            .default(0)
+                            .items(&entry_types[..])
+                            .interact()?;
+                        entry_types[selection].clone()
+                    }
+                };
+                let new_name = match new_name {
+                    Some(n) => {
+                        check_case(&n, "entry type name", Case::Snake)?;
+                        n
+                    }
+                    None => input_with_case(
+                        &String::from("New entry type name (snake_case):"),
+                        Case::Snake,
+                    )?,
+                };
+
+                (
+                    format!(r#"Entry type "{}" renamed to "{}""#, name, new_name),
+                    rename_entry_type(zome_file_tree, &name, &new_name)?,
+                )
+            }
+        };
+
+        let changes =
+            write_file_tree_with_removals(&previous_file_tree, file_tree, Path::new("."), dry_run)?;
+
+        if report_changes(&changes, &None, dry_run, output)? {
+            return Ok(());
+        }
+
+        println!(
+            r#"
+{}!
+"#,
+            renamed
+        );
+
+        Ok(())
+    }
+
+    /// Returns the arguments that weren't given and would otherwise be prompted for,
+    /// together with the flag that supplies each of them
+    pub fn missing_arguments(&self) -> Vec<(String, String)> {
+        let mut missing = vec![];
+
+        match self {
+            HcScaffoldRename::EntryType { name, new_name, .. } => {
+                if name.is_none() {
+                    missing.push((String::from("entry type name"), String::from("<NAME>")));
+                }
+                if new_name.is_none() {
+                    missing.push((
+                        String::from("new entry type name"),
+                        String::from("<NEW_NAME>"),
+                    ));
+                }
+            }
+        }
+
+        missing
+    }
+}
+
+// Fields are boxed so that their definition doesn't make every command as large as it
+fn parse_boxed_field(field_str: &str) -> ScaffoldResult<Box<FieldDefinition>> {
+    parse_fields(field_str).map(Box::new)
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(setting = structopt::clap::AppSettings::InferSubcommands)]
+pub enum HcScaffoldField {
+    /// Add a field to an existing entry type, with the validation and the link that it needs
+    /// and its widgets in the UI components
+    Add {
+        #[structopt(long)]
+        /// Name of the dna in which you want to add the field
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome in which you want to add the field
+        zome: Option<String>,
+
+        /// Name of the entry type to add the field to
+        entry_type: Option<String>,
+
+        #[structopt(parse(try_from_str = parse_boxed_field))]
+        /// The field to add, with the same grammar as the "--fields" argument of "hc scaffold entry-type"
+        /// Eg. "subtitle:String:TextField" , "post_hash:ActionHash::post"
+        field: Option<Box<FieldDefinition>>,
+
+        #[structopt(short, long)]
+        /// The template to render the UI components of the entry type from
+        /// The template must be located at the ".templates/<TEMPLATE NAME>" folder of the repository
+        template: Option<String>,
+    },
+    /// Remove a field from an existing entry type, together with its validation, its link
+    /// and its widgets in the UI components
+    Remove {
+        #[structopt(long)]
+        /// Name of the dna in which you want to remove the field
+        dna: Option<String>,
+
+        #[structopt(long)]
+        /// Name of the integrity zome in which you want to remove the field
+        zome: Option<String>,
+
+        /// Name of the entry type to remove the field from
+        entry_type: Option<String>,
+
+        /// Name of the field being removed
+        field_name: Option<String>,
+
+        #[structopt(short, long)]
+        /// The template to render the UI components of the entry type from
+        /// The template must be located at the ".templates/<TEMPLATE NAME>" folder of the repository
+        template: Option<String>,
+    },
+}
+
+impl HcScaffoldField {
+    pub fn run(self, dry_run: bool, output: OutputFormat) -> anyhow::Result<()> {
+        let current_dir = std::env::current_dir()?;
+        let file_tree = load_directory_into_memory(&current_dir)?;
+        let previous_file_tree = file_tree.clone();
+
+        let (edited, file_tree) = match self {
+            HcScaffoldField::Add {
+                dna,
+                zome,
+                entry_type,
+                field,
+                template,
+            } => {
+                let template_file_tree = choose_or_get_template_file_tree(&file_tree, &template)?;
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &dna)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(dna_file_tree, &zome)?;
+
+                let entry_type = get_or_choose_entry_type(&zome_file_tree, entry_type)?;
+
+                let field = match field {
+                    Some(field) => *field,
+                    None => {
+                        check_can_prompt("field", "<FIELD>")?;
+
+                        let v: Vec<OsString> = PathBuf::from("field-types")
+                            .iter()
+                            .map(|s| s.to_os_string())
+                            .collect();
+                        let empty_dir = dir! {};
+                        choose_field(
+                            &entry_type,
+                            &zome_file_tree,
+                            template_file_tree.path(&mut v.iter()).unwrap_or(&empty_dir),
+                        )?
+                    }
+                };
+
+                (
+                    format!(
+                        r#"Field "{}" added to entry type "{}""#,
+                        field.field_name, entry_type
+                    ),
+                    add_field(zome_file_tree, &template_file_tree, &entry_type, &field)?,
+                )
+            }
+            HcScaffoldField::Remove {
+                dna,
+                zome,
+                entry_type,
+                field_name,
+                template,
+            } => {
+                let template_file_tree = choose_or_get_template_file_tree(&file_tree, &template)?;
+                let dna_file_tree = DnaFileTree::get_or_choose(file_tree, &dna)?;
+                let zome_file_tree = ZomeFileTree::get_or_choose_integrity(dna_file_tree, &zome)?;
+
+                let entry_type = get_or_choose_entry_type(&zome_file_tree, entry_type)?;
+
+                let field_name = match field_name {
+                    Some(field_name) => field_name,
+                    None => {
+                        check_can_prompt("field name", "<FIELD_NAME>")?;
+
+                        let fields = entry_type_field_names(&zome_file_tree, &entry_type)?;
+                        if fields.is_empty() {
+                            return Err(ScaffoldError::InvalidArguments(format!(
+                                "entry type \"{}\" has no fields to remove",
+                                entry_type
+                            )))?;
+                        }
+
+                        let selection = Select::with_theme(&ColorfulTheme::default())
+                            .with_prompt("Which field do you want to remove?")
+                            .default(0)
+                            .items(&fields[..])
+                            .interact()?;
+                        fields[selection].clone()
+                    }
+                };
+
+                (
+                    format!(
+                        r#"Field "{}" removed from entry type "{}""#,
+                        field_name, entry_type
+                    ),
+                    remove_field(
+                        zome_file_tree,
+                        &template_file_tree,
+                        &entry_type,
+                        &field_name,
+                    )?,
+                )
+            }
+        };
+
+        let changes =
+            write_file_tree_with_removals(&previous_file_tree, file_tree, Path::new("."), dry_run)?;
+
+        if report_changes(&changes, &None, dry_run, output)? {
+            return Ok(());
+        }
+
+        println!(
+            r#"
+{}!
+"#,
+            edited
+        );
+
+        Ok(())
+    }
+
+    /// Returns the arguments that weren't given and would otherwise be prompted for,
+    /// together with the flag that supplies each of them
+    pub fn missing_arguments(&self) -> Vec<(String, String)> {
+        let mut missing = vec![];
+
+        let entry_type = match self {
+            HcScaffoldField::Add {
+                entry_type, field, ..
+            } => {
+                if field.is_none() {
+                    missing.push((String::from("field"), String::from("<FIELD>")));
+                }
+                entry_type
+            }
+            HcScaffoldField::Remove {
+                entry_type,
+                field_name,
+                ..
+            } => {
+                if field_name.is_none() {
+                    missing.push((String::from("field name"), String::from("<FIELD_NAME>")));
+                }
+                entry_type
+            }
+        };
+
+        if entry_type.is_none() {
+            missing.insert(
+                0,
+                (
+                    String::from("entry type name"),
+                    String::from("<ENTRY_TYPE>"),
+                ),
+            );
+        }
+
+        missing
+    }
+}
+
+fn get_or_choose_entry_type(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: Option<String>,
+) -> ScaffoldResult<String> {
+    match entry_type {
+        Some(entry_type) => Ok(entry_type),
+        None => {
+            check_can_prompt("entry type name", "<ENTRY_TYPE>")?;
+
+            let entry_types: Vec<String> = get_all_entry_types(zome_file_tree)?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|et| et.entry_type.to_case(Case::Snake))
+                .collect();
+            if entry_types.is_empty() {
+                return Err(ScaffoldError::InvalidArguments(String::from(
+                    "there is no entry type to edit",
+                )));
+            }
+
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Which entry type do you want to edit?")
+                .default(0)
+                .items(&entry_types[..])
+                .interact()?;
+
+            Ok(entry_types[selection].clone())
+        }
+    }
+}
+
+/// Writes the file tree to the given path, unless in a dry run, recording the changes in the history of that path,
+/// and returns the files that get created or modified by it
+fn write_file_tree(
+    file_tree: FileTree,
+    path: &Path,
+    dry_run: bool,
+) -> anyhow::Result<BTreeMap<PathBuf, FileChange>> {
+    match dry_run {
+        true => Ok(diff_file_tree_with_directory(&file_tree, path)?),
+        false => Ok(build_and_record(file_tree, path)?),
+    }
+}
+
+/// Like `write_file_tree`, but also deletes the files and folders of `previous_file_tree`
+/// that are no longer in the given file tree, and includes them in the returned changes
+fn write_file_tree_with_removals(
+    previous_file_tree: &FileTree,
+    file_tree: FileTree,
+    path: &Path,
+    dry_run: bool,
+) -> anyhow::Result<BTreeMap<PathBuf, FileChange>> {
+    let deleted_files = removed_files(previous_file_tree, &file_tree);
+    let deleted_dirs = removed_dirs(previous_file_tree, &file_tree);
+
+    let mut changes = write_file_tree(file_tree, path, dry_run)?;
+
+    if !dry_run {
+        for dir in &deleted_dirs {
+            fs::remove_dir_all(path.join(dir))?;
+        }
+        for file in deleted_files
+            .keys()
+            .filter(|file| !deleted_dirs.iter().any(|dir| file.starts_with(dir)))
+        {
+            fs::remove_file(path.join(file))?;
+        }
+        record_changes(path, &deleted_files)?;
+    }
+
+    changes.extend(deleted_files);
+
+    Ok(changes)
+}
+
+/// Makes the paths of the changes made in `path` relative to `base_path`
+fn relative_to(
+    changes: BTreeMap<PathBuf, FileChange>,
+    path: &Path,
+    base_path: &Path,
+) -> BTreeMap<PathBuf, FileChange> {
+    let prefix = path.strip_prefix(base_path).unwrap_or(path);
+
+    changes
+        .into_iter()
+        .map(|(p, change)| (prefix.join(p), change))
+        .collect()
+}
+
+/// Prints the JSON output or the dry run summary for the given changes,
+/// returning true if the usual text messages should be skipped
+fn report_changes(
+    changes: &BTreeMap<PathBuf, FileChange>,
+    next_instructions: &Option<String>,
+    dry_run: bool,
+    output: OutputFormat,
+) -> anyhow::Result<bool> {
+    match (output, dry_run) {
+        (OutputFormat::Json, _) => {
+            let output = ScaffoldOutput::new(changes, next_instructions.clone(), dry_run);
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            Ok(true)
+        }
+        (OutputFormat::Text, true) => {
+            print_dry_run(changes);
+            Ok(true)
+        }
+        (OutputFormat::Text, false) => Ok(false),
+    }
+}
+
+/// Prints the diff of the given changes,
+/// followed by a summary of the files that would be created, modified or deleted
+fn print_dry_run(changes: &BTreeMap<PathBuf, FileChange>) {
+    print!("{}", unified_diff(changes));
+
+    let created: Vec<&PathBuf> = changes
+        .iter()
+        .filter(|(_, change)| matches!(change, FileChange::Created(_)))
+        .map(|(path, _)| path)
+        .collect();
+    let modified: Vec<&PathBuf> = changes
+        .iter()
+        .filter(|(_, change)| matches!(change, FileChange::Modified { .. }))
+        .map(|(path, _)| path)
+        .collect();
+    let deleted: Vec<&PathBuf> = changes
+        .iter()
+        .filter(|(_, change)| matches!(change, FileChange::Deleted(_)))
+        .map(|(path, _)| path)
+        .collect();
+
+    let deleted_summary = match deleted.is_empty() {
+        true => String::new(),
+        false => format!(", {} file(s) would be deleted", deleted.len()),
+    };
+
+    println!(
+        r#"
+Dry run: no files were written.
+{} file(s) would be created, {} file(s) would be modified{}."#,
+        created.len(),
+        modified.len(),
+        deleted_summary
+    );
+
+    for path in created {
+        println!("  created:  {}", path.to_string_lossy());
+    }
+    for path in modified {
+        println!("  modified: {}", path.to_string_lossy());
+    }
+    for path in deleted {
+        println!("  deleted:  {}", path.to_string_lossy());
+    }
+}
+
+fn print_doctor_report(remaining: &[Inconsistency], repaired: &[Inconsistency], dry_run: bool) {
+    if !repaired.is_empty() {
+        println!(
+            "{} {} inconsistencies:",
+            match dry_run {
+                true => "Would repair",
+                false => "Repaired",
+            },
+            repaired.len()
+        );
+        for inconsistency in repaired {
+            println!("  - {}", inconsistency.description);
+        }
+        println!();
+    }
+
+    if remaining.is_empty() {
+        println!("No inconsistencies found.");
+        return;
+    }
+
+    println!("Found {} inconsistencies:", remaining.len());
+    for inconsistency in remaining {
+        println!(
+            r#"
+  - {}
+    Suggested fix: {}"#,
+            inconsistency.description, inconsistency.suggestion
+        );
+        match &inconsistency.repair {
+            Some(repair) if repair.is_destructive() => println!(
+                "    This can be repaired automatically with \"hc scaffold doctor --fix --remove-missing-zomes\""
+            ),
+            Some(_) => {
+                println!("    This can be repaired automatically with \"hc scaffold doctor --fix\"")
+            }
+            None => {}
+        }
+    }
+}
+
+fn setup_git_environment(path: &PathBuf, quiet: bool) -> ScaffoldResult<()> {
+    let stdout = || match quiet {
+        true => Stdio::null(),
+        false => Stdio::inherit(),
+    };
+
     let output = Command::new("git")
-        .stdout(Stdio::inherit())
+        .stdout(stdout())
         .current_dir(path)
         .args(["init", "--initial-branch=main"])
         .output()?;
 
     if !output.status.success() {
         let output = Command::new("git")
-            .stdout(Stdio::inherit())
+            .stdout(stdout())
             .stderr(Stdio::inherit())
             .current_dir(path)
             .args(["init"])
             .output()?;
         if !output.status.success() {
-            println!("Warning: error running \"git init\"");
+            eprintln!("Warning: error running \"git init\"");
             return Ok(());
         }
 
@@ -1128,14 +2612,14 @@ fn setup_git_environment(path: &PathBuf) -> ScaffoldResult<()> {
     }
 
     let output = Command::new("git")
-        .stdout(Stdio::inherit())
+        .stdout(stdout())
         .stderr(Stdio::inherit())
         .current_dir(&path)
         .args(["add", "."])
         .output()?;
 
     if !output.status.success() {
-        println!("Warning: error running \"git add .\"");
+        eprintln!("Warning: error running \"git add .\"");
     }
     Ok(())
 }
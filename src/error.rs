@@ -144,16 +144,65 @@ pub enum ScaffoldError {
     #[error("Entry type \"{0}\" was not found in dna \"{1}\" for the integrity zome \"{2}\"")]
     EntryTypeNotFound(String, String, String),
 
+    #[error("Field \"{0}\" already exists in entry type \"{1}\"")]
+    FieldAlreadyExists(String, String),
+
+    #[error("Field \"{0}\" was not found in entry type \"{1}\"")]
+    FieldNotFound(String, String),
+
     #[error("Link type \"{0}\" already exists in dna \"{1}\" for the integrity zome \"{2}\"")]
     LinkTypeAlreadyExists(String, String, String),
 
+    #[error("Link type \"{0}\" was not found in dna \"{1}\" for the integrity zome \"{2}\"")]
+    LinkTypeNotFound(String, String, String),
+
+    #[error("Collection \"{0}\" was not found in dna \"{1}\" for the integrity zome \"{2}\"")]
+    CollectionNotFound(String, String, String),
+
+    #[error("Zome \"{0}\" was not found in dna \"{1}\"")]
+    ZomeNotFound(String, String),
+
+    #[error("Entry type \"{0}\" can't be removed because it is still used in {1}, remove the entry types that depend on it first")]
+    EntryTypeStillReferenced(String, String),
+
+    #[error("Integrity zome \"{0}\" can't be removed because the coordinator zomes {1} depend on it, remove them first")]
+    IntegrityZomeHasDependents(String, String),
+
     #[error("Invalid arguments: \"{0}\"")]
     InvalidArguments(String),
 
+    #[error("Missing arguments, which can't be prompted for when running with \"--no-prompt\" or without a terminal:\n{}", format_missing_arguments(.0))]
+    MissingArguments(Vec<(String, String)>),
+
+    #[error("{0} inconsistencies were found in the app")]
+    InconsistenciesFound(usize),
+
+    #[error("There is nothing to undo: no changes were recorded in \".hc-scaffold/history\" for this directory")]
+    NothingToUndo,
+
+    #[error("These files were changed after the commands being undone, run with \"--force\" to overwrite them anyway:\n{}", format_paths(.0))]
+    UndoConflicts(Vec<PathBuf>),
+
     /// anything else
     #[error("Unknown error: {0}")]
     MiscError(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
 
+fn format_missing_arguments(missing_arguments: &[(String, String)]) -> String {
+    missing_arguments
+        .iter()
+        .map(|(argument, supplied_by)| format!("  - {}: {}", argument, supplied_by))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn format_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("  - {}", path.to_string_lossy()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 /// HcBundle Result type.
 pub type ScaffoldResult<T> = Result<T, ScaffoldError>;
@@ -1,11 +1,17 @@
 use build_fs_tree::{dir, file, FileSystemTree};
 use ignore::WalkBuilder;
 use include_dir::Dir;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
 use std::collections::BTreeMap;
 use std::ffi::OsString;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use crate::error::{ScaffoldError, ScaffoldResult};
+use crate::history::SCAFFOLD_FOLDER;
 use crate::utils::unparse;
 
 pub type FileTree = FileSystemTree<OsString, String>;
@@ -14,7 +20,11 @@ pub type FileTree = FileSystemTree<OsString, String>;
 pub fn load_directory_into_memory(path: &PathBuf) -> ScaffoldResult<FileTree> {
     let mut file_tree: FileTree = dir! {};
 
-    for result in WalkBuilder::new(&path).hidden(false).build() {
+    for result in WalkBuilder::new(path)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != SCAFFOLD_FOLDER)
+        .build()
+    {
         let dir_entry = result?
             .path()
             .to_path_buf()
@@ -99,6 +109,23 @@ pub fn insert_file(
     )
 }
 
+/// Removes the file or directory at the given path from the file tree, if it exists
+pub fn remove_path(file_tree: &mut FileTree, path: &Path) -> ScaffoldResult<()> {
+    let folder_path = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let v: Vec<OsString> = folder_path.iter().map(|s| s.to_os_string()).collect();
+
+    if let (Some(contents), Some(file_name)) = (
+        file_tree
+            .path_mut(&mut v.iter())
+            .and_then(|f| f.dir_content_mut()),
+        path.file_name(),
+    ) {
+        contents.remove(file_name);
+    }
+
+    Ok(())
+}
+
 pub fn insert_file_tree_in_dir(
     file_tree: &mut FileTree,
     folder_path: &PathBuf,
@@ -314,6 +341,119 @@ pub fn create_dir_all(file_tree: &mut FileTree, path: &PathBuf) -> ScaffoldResul
     Ok(())
 }
 
+/// A change that writing a file tree to disk would make to a single file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum FileChange {
+    Created(String),
+    Modified { previous: String, new: String },
+    Deleted(String),
+}
+
+/// Compares the given file tree with the contents of the given directory, returning only the files that would change
+pub fn diff_file_tree_with_directory(
+    file_tree: &FileTree,
+    path: &Path,
+) -> ScaffoldResult<BTreeMap<PathBuf, FileChange>> {
+    let mut changes: BTreeMap<PathBuf, FileChange> = BTreeMap::new();
+
+    for (file_path, maybe_contents) in flatten_file_tree(file_tree) {
+        if let Some(contents) = maybe_contents {
+            match fs::read_to_string(path.join(&file_path)) {
+                Ok(previous) => {
+                    if previous != contents {
+                        changes.insert(
+                            file_path,
+                            FileChange::Modified {
+                                previous,
+                                new: contents,
+                            },
+                        );
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    changes.insert(file_path, FileChange::Created(contents));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Compares two versions of the same file tree, returning the files that are no longer present in the current one
+pub fn removed_files(
+    previous_file_tree: &FileTree,
+    current_file_tree: &FileTree,
+) -> BTreeMap<PathBuf, FileChange> {
+    let current = flatten_file_tree(current_file_tree);
+
+    flatten_file_tree(previous_file_tree)
+        .into_iter()
+        .filter(|(path, _)| !current.contains_key(path))
+        .filter_map(|(path, maybe_contents)| {
+            maybe_contents.map(|contents| (path, FileChange::Deleted(contents)))
+        })
+        .collect()
+}
+
+/// Compares two versions of the same file tree, returning the outermost directories
+/// that are no longer present in the current one
+pub fn removed_dirs(previous_file_tree: &FileTree, current_file_tree: &FileTree) -> Vec<PathBuf> {
+    let current = flatten_file_tree(current_file_tree);
+
+    let removed: Vec<PathBuf> = flatten_file_tree(previous_file_tree)
+        .into_iter()
+        .filter(|(path, maybe_contents)| maybe_contents.is_none() && !current.contains_key(path))
+        .map(|(path, _)| path)
+        .collect();
+
+    removed
+        .iter()
+        .filter(|path| {
+            !removed
+                .iter()
+                .any(|other| *other != **path && path.starts_with(other))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Renders the given changes as a unified diff, one section per file
+pub fn unified_diff(changes: &BTreeMap<PathBuf, FileChange>) -> String {
+    let mut diff = String::new();
+
+    for (file_path, change) in changes {
+        let (previous, new, previous_header) = match change {
+            FileChange::Created(contents) => ("", contents.as_str(), String::from("/dev/null")),
+            FileChange::Modified { previous, new } => (
+                previous.as_str(),
+                new.as_str(),
+                format!("a/{}", file_path.to_string_lossy()),
+            ),
+            FileChange::Deleted(contents) => (
+                contents.as_str(),
+                "",
+                format!("a/{}", file_path.to_string_lossy()),
+            ),
+        };
+        let new_header = match change {
+            FileChange::Deleted(_) => String::from("/dev/null"),
+            _ => format!("b/{}", file_path.to_string_lossy()),
+        };
+
+        diff.push_str(
+            &TextDiff::from_lines(previous, new)
+                .unified_diff()
+                .context_radius(3)
+                .header(&previous_header, &new_header)
+                .to_string(),
+        );
+    }
+
+    diff
+}
+
 pub fn dir_to_file_tree(dir: &Dir<'_>) -> ScaffoldResult<FileTree> {
     let flattened = walk_dir(dir);
 
@@ -0,0 +1,339 @@
+use build_fs_tree::{Build, MergeableFileSystemTree};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{ScaffoldError, ScaffoldResult};
+use crate::file_tree::{diff_file_tree_with_directory, FileChange, FileTree};
+
+/// The changes that a single invocation of the scaffolding tool made to the files of a directory
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    /// The arguments the scaffolding tool was invoked with
+    pub command: Vec<String>,
+    /// Milliseconds since the UNIX epoch at which the invocation made its first change
+    pub timestamp: u128,
+    pub changes: BTreeMap<PathBuf, FileChange>,
+}
+
+impl HistoryEntry {
+    pub fn command_line(&self) -> String {
+        let mut command_line = vec![String::from("hc scaffold")];
+        command_line.extend(self.command.clone());
+        command_line.join(" ")
+    }
+}
+
+static INVOCATION_TIMESTAMP: OnceLock<u128> = OnceLock::new();
+
+// All the changes made during the same invocation get recorded in the same history entry
+fn invocation_timestamp() -> u128 {
+    *INVOCATION_TIMESTAMP.get_or_init(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default()
+    })
+}
+
+/// The folder in which the scaffolding tool keeps its own files, like the history, which is never part of the app
+pub const SCAFFOLD_FOLDER: &str = ".hc-scaffold";
+
+/// The folder in which the history of the changes made to the given directory is kept
+pub fn history_path(path: &Path) -> PathBuf {
+    path.join(SCAFFOLD_FOLDER).join("history")
+}
+
+/// Writes the file tree to the given path, and records the changes it makes in the history of that path
+pub fn build_and_record(
+    file_tree: FileTree,
+    path: &Path,
+) -> ScaffoldResult<BTreeMap<PathBuf, FileChange>> {
+    let changes = diff_file_tree_with_directory(&file_tree, path)?;
+
+    MergeableFileSystemTree::<OsString, String>::from(file_tree)
+        .build(&path.to_path_buf())
+        .map_err(|e| ScaffoldError::MiscError(Box::new(e)))?;
+
+    record_changes(path, &changes)?;
+
+    Ok(changes)
+}
+
+/// Records the given changes, made to the files of the given path, in the history entry of this invocation
+pub fn record_changes(path: &Path, changes: &BTreeMap<PathBuf, FileChange>) -> ScaffoldResult<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let timestamp = invocation_timestamp();
+    let entry_path = history_path(path).join(format!("{}.json", timestamp));
+
+    let mut entry = match entry_path.exists() {
+        true => serde_json::from_str(&fs::read_to_string(&entry_path)?)?,
+        false => HistoryEntry {
+            command: std::env::args().skip(1).collect(),
+            timestamp,
+            changes: BTreeMap::new(),
+        },
+    };
+
+    for (file_path, change) in changes {
+        let combined = match entry.changes.remove(file_path) {
+            Some(earlier_change) => combine_changes(earlier_change, change.clone()),
+            None => Some(change.clone()),
+        };
+        if let Some(combined) = combined {
+            entry.changes.insert(file_path.clone(), combined);
+        }
+    }
+
+    match entry.changes.is_empty() {
+        true => {
+            if entry_path.exists() {
+                fs::remove_file(&entry_path)?;
+            }
+        }
+        false => {
+            fs::create_dir_all(history_path(path))?;
+            fs::write(&entry_path, serde_json::to_string_pretty(&entry)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Combines two consecutive changes to the same file into one, or returns None if they cancel each other out
+fn combine_changes(earlier: FileChange, later: FileChange) -> Option<FileChange> {
+    let previous = match earlier {
+        FileChange::Created(_) => None,
+        FileChange::Modified { previous, .. } | FileChange::Deleted(previous) => Some(previous),
+    };
+    let new = match later {
+        FileChange::Created(new) | FileChange::Modified { new, .. } => Some(new),
+        FileChange::Deleted(_) => None,
+    };
+
+    match (previous, new) {
+        (None, None) => None,
+        (None, Some(new)) => Some(FileChange::Created(new)),
+        (Some(previous), None) => Some(FileChange::Deleted(previous)),
+        (Some(previous), Some(new)) => match previous == new {
+            true => None,
+            false => Some(FileChange::Modified { previous, new }),
+        },
+    }
+}
+
+/// Reads the history entries recorded for the given path, from the oldest to the most recent
+pub fn read_history(path: &Path) -> ScaffoldResult<Vec<HistoryEntry>> {
+    let history_path = history_path(path);
+    if !history_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut entries: Vec<HistoryEntry> = vec![];
+    for dir_entry in fs::read_dir(&history_path)? {
+        let file_path = dir_entry?.path();
+        if file_path.extension().is_some_and(|e| e == "json") {
+            entries.push(serde_json::from_str(&fs::read_to_string(&file_path)?)?);
+        }
+    }
+    entries.sort_by_key(|entry| entry.timestamp);
+
+    Ok(entries)
+}
+
+/// Restores the files of the given path to their state before the last `count` recorded invocations
+///
+/// Returns the undone history entries, from the most recent to the oldest, and the changes that restoring them makes
+/// Fails if any of the files was changed after those invocations, unless `force` is true
+pub fn undo(
+    path: &Path,
+    count: usize,
+    force: bool,
+    dry_run: bool,
+) -> ScaffoldResult<(Vec<HistoryEntry>, BTreeMap<PathBuf, FileChange>)> {
+    let mut entries = read_history(path)?;
+    if entries.is_empty() {
+        return Err(ScaffoldError::NothingToUndo);
+    }
+    let undone: Vec<HistoryEntry> = entries
+        .split_off(entries.len().saturating_sub(count))
+        .into_iter()
+        .rev()
+        .collect();
+
+    // Walks back through the entries, tracking the content that each file should have after undoing them
+    let mut contents: BTreeMap<PathBuf, Option<String>> = BTreeMap::new();
+    let mut conflicts: Vec<PathBuf> = vec![];
+    for entry in &undone {
+        for (file_path, change) in &entry.changes {
+            let current = contents
+                .remove(file_path)
+                .unwrap_or_else(|| fs::read_to_string(path.join(file_path)).ok());
+            let (previous, new) = match change.clone() {
+                FileChange::Created(new) => (None, Some(new)),
+                FileChange::Modified { previous, new } => (Some(previous), Some(new)),
+                FileChange::Deleted(previous) => (Some(previous), None),
+            };
+
+            if current != new && !conflicts.contains(file_path) {
+                conflicts.push(file_path.clone());
+            }
+            contents.insert(file_path.clone(), previous);
+        }
+    }
+
+    if !conflicts.is_empty() && !force {
+        return Err(ScaffoldError::UndoConflicts(conflicts));
+    }
+
+    let mut changes: BTreeMap<PathBuf, FileChange> = BTreeMap::new();
+    for (file_path, content) in contents {
+        let change = match (fs::read_to_string(path.join(&file_path)).ok(), content) {
+            (Some(previous), Some(new)) if previous != new => {
+                FileChange::Modified { previous, new }
+            }
+            (None, Some(new)) => FileChange::Created(new),
+            (Some(previous), None) => FileChange::Deleted(previous),
+            _ => continue,
+        };
+        changes.insert(file_path, change);
+    }
+
+    if !dry_run {
+        for (file_path, change) in &changes {
+            let full_path = path.join(file_path);
+            match change {
+                FileChange::Created(content) | FileChange::Modified { new: content, .. } => {
+                    if let Some(parent) = full_path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&full_path, content)?;
+                }
+                FileChange::Deleted(_) => {
+                    fs::remove_file(&full_path)?;
+                    remove_empty_parents(path, &full_path)?;
+                }
+            }
+        }
+
+        for entry in &undone {
+            let entry_path = history_path(path).join(format!("{}.json", entry.timestamp));
+            if entry_path.exists() {
+                fs::remove_file(entry_path)?;
+            }
+        }
+    }
+
+    Ok((undone, changes))
+}
+
+// Removes the folders containing the given file that were left empty, up to the given root path
+fn remove_empty_parents(root: &Path, file_path: &Path) -> ScaffoldResult<()> {
+    let mut current = file_path.parent();
+
+    while let Some(dir) = current {
+        if dir == root || !dir.starts_with(root) || fs::read_dir(dir)?.next().is_some() {
+            break;
+        }
+        fs::remove_dir(dir)?;
+        current = dir.parent();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use temp_dir::TempDir;
+
+    fn modified(previous: &str, new: &str) -> FileChange {
+        FileChange::Modified {
+            previous: previous.to_string(),
+            new: new.to_string(),
+        }
+    }
+
+    fn write_entry(path: &Path, timestamp: u128, changes: Vec<(&str, FileChange)>) {
+        let entry = HistoryEntry {
+            command: vec![],
+            timestamp,
+            changes: changes
+                .into_iter()
+                .map(|(file_path, change)| (PathBuf::from(file_path), change))
+                .collect(),
+        };
+        fs::create_dir_all(history_path(path)).unwrap();
+        fs::write(
+            history_path(path).join(format!("{}.json", timestamp)),
+            serde_json::to_string(&entry).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_combine_changes() {
+        assert!(matches!(
+            combine_changes(FileChange::Created("a".into()), modified("a", "b")),
+            Some(FileChange::Created(new)) if new == "b"
+        ));
+        assert!(combine_changes(
+            FileChange::Created("a".into()),
+            FileChange::Deleted("a".into())
+        )
+        .is_none());
+        assert!(combine_changes(modified("a", "b"), modified("b", "a")).is_none());
+        assert!(matches!(
+            combine_changes(modified("a", "b"), FileChange::Deleted("b".into())),
+            Some(FileChange::Deleted(previous)) if previous == "a"
+        ));
+        assert!(matches!(
+            combine_changes(FileChange::Deleted("a".into()), FileChange::Created("b".into())),
+            Some(FileChange::Modified { previous, new }) if previous == "a" && new == "b"
+        ));
+    }
+
+    #[test]
+    fn test_undo() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+
+        assert!(matches!(
+            undo(path, 1, false, false),
+            Err(ScaffoldError::NothingToUndo)
+        ));
+
+        fs::create_dir_all(path.join("src")).unwrap();
+        fs::write(path.join("README.md"), "new").unwrap();
+        fs::write(path.join("src/post.rs"), "post").unwrap();
+        write_entry(path, 1, vec![("README.md", modified("old", "new"))]);
+        write_entry(
+            path,
+            2,
+            vec![("src/post.rs", FileChange::Created("post".into()))],
+        );
+
+        let (undone, _) = undo(path, 1, false, false).unwrap();
+        assert_eq!(undone.len(), 1);
+        assert_eq!(undone[0].timestamp, 2);
+        assert!(!path.join("src").exists());
+        assert_eq!(fs::read_to_string(path.join("README.md")).unwrap(), "new");
+
+        fs::write(path.join("README.md"), "edited").unwrap();
+        assert!(matches!(
+            undo(path, 1, false, false),
+            Err(ScaffoldError::UndoConflicts(conflicts)) if conflicts == vec![PathBuf::from("README.md")]
+        ));
+
+        undo(path, 1, true, false).unwrap();
+        assert_eq!(fs::read_to_string(path.join("README.md")).unwrap(), "old");
+        assert!(read_history(path).unwrap().is_empty());
+    }
+}
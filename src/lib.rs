@@ -245,6 +245,8 @@
 pub mod cli;
 pub mod error;
 pub mod file_tree;
+pub mod history;
+pub mod output;
 pub mod reserved_words;
 pub mod scaffold;
 pub mod templates;
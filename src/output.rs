@@ -0,0 +1,228 @@
+use convert_case::{Case, Casing};
+use holochain_types::prelude::{AppManifest, DnaManifest};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::{ScaffoldError, ScaffoldResult};
+use crate::file_tree::FileChange;
+use crate::utils::has_attribute;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = ScaffoldError;
+
+    fn from_str(s: &str) -> ScaffoldResult<OutputFormat> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(ScaffoldError::InvalidArguments(format!(
+                "invalid output format \"{}\", supported formats are: text, json",
+                s
+            ))),
+        }
+    }
+}
+
+/// Identifiers of everything that a command added to or removed from the app
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Identifiers {
+    pub roles: Vec<String>,
+    pub dnas: Vec<String>,
+    pub zomes: Vec<String>,
+    pub entry_types: Vec<String>,
+    pub link_types: Vec<String>,
+    pub extern_functions: Vec<String>,
+}
+
+/// The JSON document printed at the end of each command when running with `--output json`
+#[derive(Serialize, Debug, Clone)]
+pub struct ScaffoldOutput {
+    pub dry_run: bool,
+    pub created: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+    pub deleted: Vec<PathBuf>,
+    pub next_instructions: Option<String>,
+    pub added: Identifiers,
+    pub removed: Identifiers,
+}
+
+impl ScaffoldOutput {
+    pub fn new(
+        changes: &BTreeMap<PathBuf, FileChange>,
+        next_instructions: Option<String>,
+        dry_run: bool,
+    ) -> ScaffoldOutput {
+        let mut created = vec![];
+        let mut modified = vec![];
+        let mut deleted = vec![];
+
+        for (path, change) in changes {
+            match change {
+                FileChange::Created(_) => created.push(path.clone()),
+                FileChange::Modified { .. } => modified.push(path.clone()),
+                FileChange::Deleted(_) => deleted.push(path.clone()),
+            }
+        }
+
+        ScaffoldOutput {
+            dry_run,
+            created,
+            modified,
+            deleted,
+            next_instructions,
+            added: added_identifiers(changes),
+            removed: removed_identifiers(changes),
+        }
+    }
+}
+
+/// Finds the roles, dnas, zomes, entry types, link types and extern functions that are present
+/// in the new contents of the changed files but not in their previous contents
+pub fn added_identifiers(changes: &BTreeMap<PathBuf, FileChange>) -> Identifiers {
+    changed_identifiers(changes, false)
+}
+
+/// Finds the roles, dnas, zomes, entry types, link types and extern functions that are present
+/// in the previous contents of the changed files but not in their new contents
+pub fn removed_identifiers(changes: &BTreeMap<PathBuf, FileChange>) -> Identifiers {
+    changed_identifiers(changes, true)
+}
+
+fn changed_identifiers(changes: &BTreeMap<PathBuf, FileChange>, removed: bool) -> Identifiers {
+    let mut identifiers = Identifiers::default();
+
+    for (path, change) in changes {
+        let (previous, new) = match change {
+            FileChange::Created(new) => ("", new.as_str()),
+            FileChange::Modified { previous, new } => (previous.as_str(), new.as_str()),
+            FileChange::Deleted(previous) => (previous.as_str(), ""),
+        };
+        let (previous, new) = match removed {
+            true => (new, previous),
+            false => (previous, new),
+        };
+
+        match file_name(path) {
+            Some("happ.yaml") => {
+                identifiers
+                    .roles
+                    .extend(new_items(previous, new, app_roles));
+            }
+            Some("dna.yaml") => {
+                identifiers.dnas.extend(new_items(previous, new, dna_name));
+                identifiers
+                    .zomes
+                    .extend(new_items(previous, new, dna_zomes));
+            }
+            _ if path.extension().is_some_and(|e| e == "rs") => {
+                identifiers
+                    .entry_types
+                    .extend(new_items(previous, new, |code| {
+                        enum_variants(code, "hdk_entry_defs")
+                            .into_iter()
+                            .map(|v| v.to_case(Case::Snake))
+                            .collect()
+                    }));
+                identifiers
+                    .link_types
+                    .extend(new_items(previous, new, |code| {
+                        enum_variants(code, "hdk_link_types")
+                    }));
+                identifiers
+                    .extern_functions
+                    .extend(new_items(previous, new, extern_functions));
+            }
+            _ => {}
+        }
+    }
+
+    identifiers
+}
+
+fn file_name(path: &Path) -> Option<&str> {
+    path.file_name().and_then(|f| f.to_str())
+}
+
+fn new_items<F>(previous: &str, new: &str, items: F) -> Vec<String>
+where
+    F: Fn(&str) -> Vec<String>,
+{
+    let previous_items: BTreeSet<String> = items(previous).into_iter().collect();
+
+    items(new)
+        .into_iter()
+        .filter(|i| !previous_items.contains(i))
+        .collect()
+}
+
+fn app_roles(manifest: &str) -> Vec<String> {
+    match serde_yaml::from_str::<AppManifest>(manifest) {
+        Ok(manifest) => manifest
+            .app_roles()
+            .into_iter()
+            .map(|r| r.name.to_string())
+            .collect(),
+        Err(_) => vec![],
+    }
+}
+
+fn dna_name(manifest: &str) -> Vec<String> {
+    match serde_yaml::from_str::<DnaManifest>(manifest) {
+        Ok(manifest) => vec![manifest.name()],
+        Err(_) => vec![],
+    }
+}
+
+fn dna_zomes(manifest: &str) -> Vec<String> {
+    match serde_yaml::from_str::<DnaManifest>(manifest) {
+        Ok(DnaManifest::V1(m)) => m
+            .integrity
+            .zomes
+            .into_iter()
+            .chain(m.coordinator.zomes)
+            .map(|z| z.name.0.to_string())
+            .collect(),
+        Err(_) => vec![],
+    }
+}
+
+fn enum_variants(code: &str, attribute: &str) -> Vec<String> {
+    let file = match syn::parse_file(code) {
+        Ok(file) => file,
+        Err(_) => return vec![],
+    };
+
+    file.items
+        .into_iter()
+        .filter_map(|item| match item {
+            syn::Item::Enum(e) if has_attribute(&e.attrs, attribute) => Some(e.variants),
+            _ => None,
+        })
+        .flatten()
+        .map(|v| v.ident.to_string())
+        .collect()
+}
+
+fn extern_functions(code: &str) -> Vec<String> {
+    let file = match syn::parse_file(code) {
+        Ok(file) => file,
+        Err(_) => return vec![],
+    };
+
+    file.items
+        .into_iter()
+        .filter_map(|item| match item {
+            syn::Item::Fn(f) if has_attribute(&f.attrs, "hdk_extern") => {
+                Some(f.sig.ident.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
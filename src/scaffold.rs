@@ -1,8 +1,15 @@
 pub mod app;
 pub mod collection;
 pub mod dna;
+pub mod doctor;
 pub mod entry_type;
+pub mod entry_type_version;
 pub mod example;
+pub mod field;
 pub mod link_type;
+pub mod list;
+pub mod remove;
+pub mod rename;
+pub mod spec;
 pub mod web_app;
 pub mod zome;
@@ -7,6 +7,7 @@ use mr_bundle::Manifest;
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
     file_tree::{find_files_by_name, FileTree},
+    utils::check_can_prompt,
 };
 
 pub mod cargo;
@@ -41,7 +42,10 @@ impl AppFileTree {
                 .into_iter()
                 .last()
                 .ok_or(ScaffoldError::AppManifestNotFound),
-            (_, None) => choose_app(app_manifests),
+            (_, None) => {
+                check_can_prompt("app", "--app <APP_NAME>")?;
+                choose_app(app_manifests)
+            }
             (_, Some(name)) => app_manifests
                 .into_iter()
                 .find(|(_, m)| m.app_name().to_string().eq(name))
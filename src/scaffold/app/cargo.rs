@@ -1,4 +1,8 @@
-use std::{path::PathBuf, process::Stdio, str::from_utf8};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    str::from_utf8,
+};
 
 use crate::file_tree::{file_content, insert_file, FileTree};
 use cargo_metadata::{Metadata, MetadataCommand};
@@ -104,6 +108,108 @@ fn add_workspace_dependency(
     Ok(app_file_tree)
 }
 
+pub fn add_workspace_member(
+    mut app_file_tree: FileTree,
+    member_path_from_workspace_root: &Path,
+) -> ScaffoldResult<FileTree> {
+    let mut members = get_workspace_members(&app_file_tree)?;
+    members.push(
+        member_path_from_workspace_root
+            .to_string_lossy()
+            .to_string(),
+    );
+
+    let mut workspace_cargo_toml = get_workspace_cargo_toml(&app_file_tree)?;
+    let workspace_table = workspace_cargo_toml
+        .as_table_mut()
+        .and_then(|t| t.get_mut("workspace"))
+        .and_then(|w| w.as_table_mut())
+        .ok_or(ScaffoldError::MalformedFile(
+            workspace_cargo_toml_path(&app_file_tree),
+            String::from("no workspace table found in workspace root"),
+        ))?;
+
+    workspace_table.insert(
+        String::from("members"),
+        toml::Value::Array(members.into_iter().map(toml::Value::String).collect()),
+    );
+
+    let path = workspace_cargo_toml_path(&app_file_tree);
+
+    let cargo_toml_str = toml::to_string(&workspace_cargo_toml)?;
+
+    insert_file(&mut app_file_tree, &path, &cargo_toml_str)?;
+
+    Ok(app_file_tree)
+}
+
+/// Removes the given crate from the dependencies of the workspace, if it was there
+pub fn remove_workspace_dependency(
+    mut app_file_tree: FileTree,
+    crate_name: &str,
+) -> ScaffoldResult<FileTree> {
+    let mut workspace_cargo_toml = get_workspace_cargo_toml(&app_file_tree)?;
+
+    let removed = workspace_cargo_toml
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut("dependencies"))
+        .and_then(|d| d.as_table_mut())
+        .and_then(|d| d.remove(crate_name))
+        .is_some();
+
+    if removed {
+        let path = workspace_cargo_toml_path(&app_file_tree);
+        insert_file(
+            &mut app_file_tree,
+            &path,
+            &toml::to_string(&workspace_cargo_toml)?,
+        )?;
+    }
+
+    Ok(app_file_tree)
+}
+
+/// Removes the members of the workspace that are located inside the given directory,
+/// leaving the glob members untouched
+pub fn remove_workspace_members_in(
+    mut app_file_tree: FileTree,
+    dir_path_from_workspace_root: &Path,
+) -> ScaffoldResult<FileTree> {
+    let members = get_workspace_members(&app_file_tree)?;
+    let remaining: Vec<String> = members
+        .iter()
+        .filter(|m| !Path::new(m).starts_with(dir_path_from_workspace_root))
+        .cloned()
+        .collect();
+
+    if remaining.len() == members.len() {
+        return Ok(app_file_tree);
+    }
+
+    let mut workspace_cargo_toml = get_workspace_cargo_toml(&app_file_tree)?;
+    let workspace_table = workspace_cargo_toml
+        .as_table_mut()
+        .and_then(|t| t.get_mut("workspace"))
+        .and_then(|w| w.as_table_mut())
+        .ok_or(ScaffoldError::MalformedFile(
+            workspace_cargo_toml_path(&app_file_tree),
+            String::from("no workspace table found in workspace root"),
+        ))?;
+
+    workspace_table.insert(
+        String::from("members"),
+        toml::Value::Array(remaining.into_iter().map(toml::Value::String).collect()),
+    );
+
+    let path = workspace_cargo_toml_path(&app_file_tree);
+
+    let cargo_toml_str = toml::to_string(&workspace_cargo_toml)?;
+
+    insert_file(&mut app_file_tree, &path, &cargo_toml_str)?;
+
+    Ok(app_file_tree)
+}
+
 fn workspace_cargo_toml_path(_app_file_tree: &FileTree) -> PathBuf {
     PathBuf::new().join("Cargo.toml")
 }
@@ -11,6 +11,7 @@ pub fn gitignore() -> String {
 *.dna
 .hc*
 .hc
+.hc-scaffold/
 .running
 "#
     )
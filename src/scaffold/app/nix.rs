@@ -52,7 +52,10 @@ pub fn flake_nix() -> FileTree {
     ))
 }
 
-pub fn setup_nix_developer_environment(dir: &PathBuf) -> ScaffoldResult<()> {
+/// Runs `nix flake update` in the given directory
+///
+/// With `quiet`, its stdout is discarded, so that it doesn't get mixed with the JSON output of the scaffolding tool
+pub fn setup_nix_developer_environment(dir: &PathBuf, quiet: bool) -> ScaffoldResult<()> {
     if cfg!(target_os = "windows") {
         return Err(ScaffoldError::NixSetupError(
             "Windows doesn't support nix".to_string(),
@@ -76,12 +79,17 @@ pub fn setup_nix_developer_environment(dir: &PathBuf) -> ScaffoldResult<()> {
             Err(_) => {} // Ignore errors, Git isn't necessarily available.
         }
 
-    println!("Setting up nix development environment...");
+    eprintln!("Setting up nix development environment...");
 
     add_extra_experimental_features()?;
 
+    let stdout = match quiet {
+        true => Stdio::null(),
+        false => Stdio::inherit(),
+    };
+
     let output = Command::new("nix")
-        .stdout(Stdio::inherit())
+        .stdout(stdout)
         .stderr(Stdio::inherit())
         .current_dir(dir)
         .args(["flake", "update"])
@@ -120,7 +128,7 @@ pub fn add_extra_experimental_features() -> ScaffoldResult<()> {
     {
         file.write_all(EXTRA_EXPERIMENTAL_FEATURES_LINE.as_bytes())?;
     } else {
-        println!("Warning: could not write extra-experimental-features to nix.conf");
+        eprintln!("Warning: could not write extra-experimental-features to nix.conf");
     }
     Ok(())
 }
@@ -1,6 +1,7 @@
 use crate::{
     file_tree::{file_content, FileTree},
     scaffold::dna::read_dna_manifest,
+    utils::check_can_prompt,
 };
 use holochain_types::prelude::AppManifest;
 use mr_bundle::Location;
@@ -22,16 +23,18 @@ pub fn read_app_manifest(
     Ok(manifest)
 }
 
+/// Returns the path of the app manifest with the given name among the ones that include the given DNA,
+/// or the only one there is, or the one chosen by the user among all of them
 pub fn get_or_choose_app_manifest_path_for_dna_manifest(
     app_file_tree: &FileTree,
     dna_manifest_path: &PathBuf,
+    app_name: &Option<String>,
 ) -> ScaffoldResult<PathBuf> {
     let dna_manifest = read_dna_manifest(&app_file_tree, dna_manifest_path)?;
 
     let app_manifests = find_app_manifests(&app_file_tree)?;
 
     let apps_for_dna: BTreeMap<PathBuf, AppManifest> = app_manifests
-        .clone()
         .into_iter()
         .filter(|(app_manifest_path, _manifest)| {
             match bundled_dnas_paths(&app_file_tree, &app_manifest_path) {
@@ -41,13 +44,20 @@ pub fn get_or_choose_app_manifest_path_for_dna_manifest(
         })
         .collect();
 
-    let (path, _manifest) = match apps_for_dna.len() {
-        0 => Err(ScaffoldError::NoAppsFoundForDna(dna_manifest.name())),
-        1 => app_manifests
+    let (path, _manifest) = match (apps_for_dna.len(), app_name) {
+        (0, _) => Err(ScaffoldError::NoAppsFoundForDna(dna_manifest.name())),
+        (_, Some(name)) => apps_for_dna
             .into_iter()
-            .last()
+            .find(|(_, m)| m.app_name().to_string().eq(name))
+            .ok_or(ScaffoldError::AppManifestNotFound),
+        (1, None) => apps_for_dna
+            .into_iter()
+            .next()
             .ok_or(ScaffoldError::NoAppsFoundForDna(dna_manifest.name())),
-        _ => choose_app(apps_for_dna),
+        (_, None) => {
+            check_can_prompt("app", "--app <APP_NAME>")?;
+            choose_app(apps_for_dna)
+        }
     }?;
 
     Ok(path)
@@ -9,6 +9,7 @@ use crate::{
     file_tree::FileTree,
     reserved_words::check_for_reserved_words,
     templates::{collection::scaffold_collection_templates, ScaffoldedTemplate},
+    utils::check_can_prompt,
 };
 
 use self::coordinator::add_collection_to_coordinators;
@@ -17,7 +18,7 @@ use super::{
     app::AppFileTree,
     entry_type::{
         definitions::{EntryTypeReference, Referenceable},
-        integrity::get_all_entry_types,
+        integrity::{check_entry_type_is_public, get_all_entry_types},
         utils::choose_entry_type_reference,
     },
     link_type::integrity::add_link_type_to_integrity_zome,
@@ -31,6 +32,7 @@ pub mod coordinator;
 pub enum CollectionType {
     Global,
     ByAuthor,
+    TimeIndexed,
 }
 
 impl FromStr for CollectionType {
@@ -39,24 +41,29 @@ impl FromStr for CollectionType {
         match s {
             "global" => Ok(CollectionType::Global),
             "by-author" => Ok(CollectionType::ByAuthor),
+            "time-indexed" => Ok(CollectionType::TimeIndexed),
             _ => Err(ScaffoldError::InvalidCollectionType(
                 s.to_string(),
-                "global, by-author".to_string(),
+                "global, by-author, time-indexed".to_string(),
             )),
         }
     }
 }
 
 pub fn choose_collection_type() -> ScaffoldResult<CollectionType> {
+    check_can_prompt("collection type", "<COLLECTION_TYPE>")?;
+
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Which type of collection should be scaffolded?")
         .default(0)
         .item("Global (get all entries of the selected entry types)")
         .item("By author (get entries of the selected entry types that a given author has created)")
+        .item("Time indexed (get all entries of the selected entry types, sharded by the hour in which they were created)")
         .interact()?;
     match selection {
         0 => Ok(CollectionType::Global),
         1 => Ok(CollectionType::ByAuthor),
+        2 => Ok(CollectionType::TimeIndexed),
         _ => Err(ScaffoldError::InvalidCollectionType(
             selection.to_string(),
             "".into(),
@@ -64,12 +71,17 @@ pub fn choose_collection_type() -> ScaffoldResult<CollectionType> {
     }
 }
 
+pub fn shards_link_type_name(collection_name: &str) -> String {
+    format!("{}Shards", collection_name.to_case(Case::Pascal))
+}
+
 pub fn scaffold_collection(
     integrity_zome_file_tree: ZomeFileTree,
     template_file_tree: &FileTree,
     collection_name: &String,
     maybe_collection_type: &Option<CollectionType>,
     maybe_entry_type: &Option<EntryTypeReference>,
+    coordinator_zome: &Option<String>,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     check_for_reserved_words(collection_name)?;
 
@@ -102,26 +114,54 @@ pub fn scaffold_collection(
         None => choose_entry_type_reference(
             &all_entries,
             &"Which entry type should be collected?".to_string(),
+            "<ENTRY_TYPE>",
         ),
     }?;
+    check_entry_type_is_public(
+        &integrity_zome_file_tree,
+        &entry_type.entry_type,
+        "collected",
+    )?;
 
     let link_type_name = collection_name.to_case(Case::Pascal);
 
-    let zome_file_tree = add_link_type_to_integrity_zome(
+    let link_type_file_name =
+        PathBuf::from(format!("{}.rs", entry_type.entry_type.to_case(Case::Snake)));
+
+    let mut zome_file_tree = add_link_type_to_integrity_zome(
         integrity_zome_file_tree,
         &link_type_name,
         &None,
         &Some(Referenceable::EntryType(entry_type.clone())),
         true,
-        &PathBuf::from(format!("{}.rs", entry_type.entry_type.to_case(Case::Snake))),
+        false,
+        None,
+        &link_type_file_name,
+        &None,
     )?;
 
+    // The year, month, day and hour shards of a time indexed collection are linked with each other by their own link type
+    if let CollectionType::TimeIndexed = collection_type {
+        zome_file_tree = add_link_type_to_integrity_zome(
+            zome_file_tree,
+            &shards_link_type_name(collection_name),
+            &None,
+            &None,
+            false,
+            false,
+            None,
+            &link_type_file_name,
+            &None,
+        )?;
+    }
+
     let (dna_file_tree, coordinator_zome, deletable) = add_collection_to_coordinators(
         zome_file_tree,
         collection_name,
         &link_type_name,
         &collection_type,
         &entry_type,
+        coordinator_zome,
     )?;
 
     let dna_name = dna_file_tree.dna_manifest.name();
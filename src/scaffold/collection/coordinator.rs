@@ -1,7 +1,6 @@
 use std::ffi::OsString;
 
 use convert_case::{Case, Casing};
-use dialoguer::{theme::ColorfulTheme, Select};
 use holochain_types::prelude::ZomeManifest;
 
 use crate::{
@@ -12,13 +11,15 @@ use crate::{
         entry_type::definitions::EntryTypeReference,
         zome::{
             coordinator::{find_extern_function_in_zomes, find_extern_function_or_choose},
-            utils::get_coordinator_zomes_for_integrity,
+            utils::{
+                get_coordinator_zomes_for_integrity, get_or_choose_coordinator_zome_for_integrity,
+            },
             ZomeFileTree,
         },
     },
 };
 
-use super::CollectionType;
+use super::{shards_link_type_name, CollectionType};
 
 fn global_collection_getter(
     integrity_zome_name: &String,
@@ -58,6 +59,174 @@ pub fn get_{collection_name}(author: AgentPubKey) -> ExternResult<Vec<Link>> {{
     )
 }
 
+fn time_indexed_collection_getter(
+    integrity_zome_name: &String,
+    collection_name: &String,
+    link_type_name: &String,
+) -> String {
+    let snake_collection_name = collection_name.to_case(Case::Snake);
+    let pascal_collection_name = collection_name.to_case(Case::Pascal);
+    let shards_link_type_name = shards_link_type_name(collection_name);
+
+    format!(
+        r#"use hdk::prelude::*;
+use {integrity_zome_name}::*;
+
+/// The year, month, day and hour in which the given timestamp falls, in UTC
+///
+/// They are zero-padded so that the shards sort in the same order as the time they stand for
+fn {snake_collection_name}_shard_components(timestamp: Timestamp) -> Vec<String> {{
+    let hours = timestamp.as_micros().div_euclid(3_600_000_000);
+    let days = hours.div_euclid(24);
+
+    // Converts the days since the unix epoch into a date of the gregorian calendar
+    let shifted_days = days + 719_468;
+    let era = shifted_days.div_euclid(146_097);
+    let day_of_era = shifted_days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {{ shifted_month + 3 }} else {{ shifted_month - 9 }};
+    let year = year_of_era + era * 400 + if month <= 2 {{ 1 }} else {{ 0 }};
+
+    vec![
+        format!("{{:04}}", year),
+        format!("{{:02}}", month),
+        format!("{{:02}}", day),
+        format!("{{:02}}", hours.rem_euclid(24)),
+    ]
+}}
+
+/// The path of the hour shard in which the entries created at the given timestamp are collected
+pub fn {snake_collection_name}_shard(timestamp: Timestamp) -> ExternResult<TypedPath> {{
+    let mut components = vec![Component::from("{snake_collection_name}")];
+    components.extend({snake_collection_name}_shard_components(timestamp).into_iter().map(Component::from));
+
+    Path::from(components).typed(LinkTypes::{shards_link_type_name})
+}}
+
+/// The tag of the links of the collection, which holds the timestamp that their shard was derived from
+pub fn {snake_collection_name}_tag(timestamp: Timestamp) -> ExternResult<LinkTag> {{
+    Ok(LinkTag::new(ExternIO::encode(timestamp).map_err(|e| wasm_error!(e))?.0))
+}}
+
+/// The position of the given link in the collection, which is sorted by the timestamp in its tag and not by the
+/// time at which the link was created, so that each link falls in the same hour as its shard
+///
+/// The links with the same timestamp are sorted by their hash, so that pages never skip or repeat any of them
+fn {snake_collection_name}_link_key(link: &Link) -> ExternResult<(Timestamp, ActionHash)> {{
+    let timestamp: Timestamp = ExternIO(link.tag.clone().into_inner())
+        .decode()
+        .map_err(|e| wasm_error!(e))?;
+
+    Ok((timestamp, link.create_link_hash.clone()))
+}}
+
+/// Walks the shards under the given path from the newest to the oldest, collecting the links between
+/// "from" and "until" that are included by the given filter until there are "limit" of them
+fn collect_{snake_collection_name}(
+    path: TypedPath,
+    from: Timestamp,
+    until: Timestamp,
+    is_included: &dyn Fn(&(Timestamp, ActionHash)) -> bool,
+    limit: usize,
+    links: &mut Vec<Link>,
+) -> ExternResult<()> {{
+    let shard_depth = path.as_ref().len() - 1;
+
+    // The hour shards are the ones that link to the entries
+    if shard_depth == 4 {{
+        let mut shard_links = get_links(path.path_entry_hash()?, LinkTypes::{link_type_name}, None)?
+            .into_iter()
+            .map(|link| Ok(({snake_collection_name}_link_key(&link)?, link)))
+            .collect::<ExternResult<Vec<((Timestamp, ActionHash), Link)>>>()?;
+        shard_links.retain(|(key, _)| is_included(key));
+        shard_links.sort_by(|(key_a, _), (key_b, _)| key_b.cmp(key_a));
+        let remaining = limit - links.len();
+        links.extend(shard_links.into_iter().take(remaining).map(|(_, link)| link));
+
+        return Ok(());
+    }}
+
+    let from_shard = {snake_collection_name}_shard_components(from);
+    let until_shard = {snake_collection_name}_shard_components(until);
+
+    let mut children = path
+        .children_paths()?
+        .into_iter()
+        .map(|child| {{
+            let shard = child.as_ref()[1..]
+                .iter()
+                .map(|component| String::try_from(component).map_err(|e| wasm_error!(e)))
+                .collect::<ExternResult<Vec<String>>>()?;
+            Ok((shard, child))
+        }})
+        .collect::<ExternResult<Vec<(Vec<String>, TypedPath)>>>()?;
+    children.sort_by(|(shard_a, _), (shard_b, _)| shard_b.cmp(shard_a));
+
+    for (shard, child) in children {{
+        if links.len() >= limit || shard[..] < from_shard[..shard.len()] {{
+            break;
+        }}
+        if shard[..] > until_shard[..shard.len()] {{
+            continue;
+        }}
+        collect_{snake_collection_name}(child, from, until, is_included, limit, links)?;
+    }}
+
+    Ok(())
+}}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Get{pascal_collection_name}Input {{
+    /// Only the links that come after this one are returned: pass the last link of a page to get the next one
+    pub before: Option<Link>,
+    pub limit: usize,
+}}
+
+#[hdk_extern]
+pub fn get_{snake_collection_name}(input: Get{pascal_collection_name}Input) -> ExternResult<Vec<Link>> {{
+    let before = input.before.as_ref().map({snake_collection_name}_link_key).transpose()?;
+    let until = before.as_ref().map(|(timestamp, _)| *timestamp).unwrap_or(Timestamp::max());
+
+    let mut links = vec![];
+    collect_{snake_collection_name}(
+        Path::from("{snake_collection_name}").typed(LinkTypes::{shards_link_type_name})?,
+        Timestamp::from_micros(0),
+        until,
+        &|key| before.as_ref().map_or(true, |before| key < before),
+        input.limit,
+        &mut links,
+    )?;
+
+    Ok(links)
+}}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Get{pascal_collection_name}InRangeInput {{
+    pub from: Timestamp,
+    pub until: Timestamp,
+}}
+
+#[hdk_extern]
+pub fn get_{snake_collection_name}_in_range(input: Get{pascal_collection_name}InRangeInput) -> ExternResult<Vec<Link>> {{
+    let mut links = vec![];
+    collect_{snake_collection_name}(
+        Path::from("{snake_collection_name}").typed(LinkTypes::{shards_link_type_name})?,
+        input.from,
+        input.until,
+        &|(timestamp, _)| *timestamp >= input.from && *timestamp < input.until,
+        usize::MAX,
+        &mut links,
+    )?;
+
+    Ok(links)
+}}
+"#,
+    )
+}
+
 fn add_create_link_in_create_function(
     dna_file_tree: DnaFileTree,
     coordinator_zomes_for_integrity: &Vec<ZomeManifest>,
@@ -115,6 +284,21 @@ fn add_create_link_in_create_function(
                 link_to_variable, link_type_name
             ));
         }
+        CollectionType::TimeIndexed => {
+            create_link_stmts.push(format!(
+                r#"let path = crate::{}::{}_shard(record.action().timestamp())?;"#,
+                collection_name.to_case(Case::Snake),
+                collection_name.to_case(Case::Snake)
+            ));
+            create_link_stmts.push(String::from("path.ensure()?;"));
+            create_link_stmts.push(format!(
+                r#"create_link(path.path_entry_hash()?, {}.clone(), LinkTypes::{}, crate::{}::{}_tag(record.action().timestamp())?)?;"#,
+                link_to_variable,
+                link_type_name,
+                collection_name.to_case(Case::Snake),
+                collection_name.to_case(Case::Snake)
+            ));
+        }
     };
 
     let stmts = create_link_stmts
@@ -243,6 +427,24 @@ fn add_delete_link_in_delete_function(
                 }}"#,
             ));
         }
+        CollectionType::TimeIndexed => {
+            let snake_collection_name = collection_name.to_case(Case::Snake);
+            delete_link_stmts.push(format!(
+                r#"let path = crate::{snake_collection_name}::{snake_collection_name}_shard(record.action().timestamp())?;"#,
+            ));
+            delete_link_stmts.push(format!(
+                r#"let links = get_links(path.path_entry_hash()?, LinkTypes::{link_type_name}, None)?;"#,
+            ));
+            delete_link_stmts.push(format!(
+                r#"for link in links {{
+                    if let Some(hash) = link.target.{into_hash_fn} {{
+                       if hash.eq({target_hash_variable}) {{
+                            delete_link(link.create_link_hash)?;
+                        }}
+                    }}
+                }}"#,
+            ));
+        }
     };
 
     let stmts = delete_link_stmts
@@ -310,6 +512,7 @@ pub fn add_collection_to_coordinators(
     link_type_name: &String,
     collection_type: &CollectionType,
     entry_type: &EntryTypeReference,
+    coordinator_zome: &Option<String>,
 ) -> ScaffoldResult<(DnaFileTree, ZomeManifest, bool)> {
     let integrity_zome_name = integrity_zome_file_tree.zome_manifest.name.0.to_string();
     let dna_manifest_path = integrity_zome_file_tree
@@ -322,28 +525,12 @@ pub fn add_collection_to_coordinators(
         &integrity_zome_name,
     );
 
-    let coordinator_zome = match coordinator_zomes_for_integrity.len() {
-        0 => Err(ScaffoldError::NoCoordinatorZomesFoundForIntegrityZome(
-            integrity_zome_file_tree.dna_file_tree.dna_manifest.name(),
-            integrity_zome_file_tree.zome_manifest.name.0.to_string(),
-        )),
-        1 => Ok(coordinator_zomes_for_integrity[0].clone()),
-        _ => {
-            let names: Vec<String> = coordinator_zomes_for_integrity
-                .iter()
-                .map(|z| z.name.0.to_string())
-                .collect();
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt(
-                    "Which coordinator zome should the collection getter functions be scaffolded in?",
-                )
-                .default(0)
-                .items(&names[..])
-                .interact()?;
-
-            Ok(coordinator_zomes_for_integrity[selection].clone())
-        }
-    }?;
+    let coordinator_zome = get_or_choose_coordinator_zome_for_integrity(
+        &integrity_zome_file_tree.dna_file_tree.dna_manifest,
+        &integrity_zome_name,
+        coordinator_zome,
+        "Which coordinator zome should the collection getter functions be scaffolded in?",
+    )?;
 
     // 1. Create an INDEX_NAME.rs in "src/", with the appropriate zome functions
 
@@ -361,6 +548,9 @@ pub fn add_collection_to_coordinators(
         CollectionType::ByAuthor => {
             by_author_collection_getter(&integrity_zome_name, collection_name, link_type_name)
         }
+        CollectionType::TimeIndexed => {
+            time_indexed_collection_getter(&integrity_zome_name, collection_name, link_type_name)
+        }
     };
 
     let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
@@ -1,4 +1,7 @@
-use std::{collections::BTreeMap, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use crate::{
     file_tree::{
@@ -7,7 +10,7 @@ use crate::{
     },
     reserved_words::check_for_reserved_words,
     templates::{dna::scaffold_dna_templates, ScaffoldedTemplate},
-    utils::choose_directory_path,
+    utils::{check_can_prompt, choose_directory_path},
 };
 use build_fs_tree::{dir, file};
 use dialoguer::{theme::ColorfulTheme, Select};
@@ -46,7 +49,10 @@ impl DnaFileTree {
                 .into_iter()
                 .last()
                 .ok_or(ScaffoldError::NoDnasFound),
-            (_, None) => choose_dna(dna_manifests.into_iter().collect()),
+            (_, None) => {
+                check_can_prompt("dna", "--dna <DNA_NAME>")?;
+                choose_dna(dna_manifests.into_iter().collect())
+            }
             (_, Some(name)) => dna_manifests
                 .into_iter()
                 .find(|(_, m)| m.name().to_string().eq(name))
@@ -150,22 +156,73 @@ pub fn read_dna_manifest(
     Ok(manifest)
 }
 
-pub fn get_or_choose_dnas_dir_path(app_file_tree: &FileTree) -> ScaffoldResult<PathBuf> {
+/// Returns the given directory to scaffold the DNA in, or the default "dnas" one if it exists,
+/// or the one chosen by the user otherwise
+pub fn get_or_choose_dnas_dir_path(
+    app_file_tree: &FileTree,
+    path: &Option<PathBuf>,
+) -> ScaffoldResult<PathBuf> {
+    if let Some(path) = path {
+        return Ok(path.clone());
+    }
+
     let default_path = default_dnas_dir_path();
     if dir_exists(app_file_tree, &default_path) {
         return Ok(default_path.clone());
     } else {
         choose_directory_path(
             &String::from("Which directory should the DNA be scaffolded in?"),
+            "--path <PATH>",
             &app_file_tree,
         )
     }
 }
 
+/// Returns the location of the bundled DNA, relative to the app manifest that includes it
+pub fn dna_bundle_path(
+    app_manifest_path: &Path,
+    dna_workdir_path: &Path,
+    dna_name: &String,
+) -> PathBuf {
+    let mut dna_workdir_relative_to_app_manifest = PathBuf::new();
+
+    let app_workdir_path = app_manifest_path.parent();
+
+    if let Some(path) = app_workdir_path {
+        for _path_segment in path.components() {
+            dna_workdir_relative_to_app_manifest = dna_workdir_relative_to_app_manifest.join("..");
+        }
+    }
+
+    dna_workdir_relative_to_app_manifest =
+        dna_workdir_relative_to_app_manifest.join(dna_workdir_path);
+
+    dna_workdir_relative_to_app_manifest.join(format!("{}.dna", dna_name))
+}
+
+pub fn new_app_role_manifest(role_name: &str, dna_bundle_path: PathBuf) -> AppRoleManifest {
+    AppRoleManifest {
+        name: role_name.to_string(),
+        dna: AppRoleDnaManifest {
+            location: Some(Location::Bundled(dna_bundle_path)),
+            modifiers: DnaModifiersOpt {
+                network_seed: None,
+                origin_time: None,
+                properties: None,
+                quantum_time: None,
+            },
+            installed_hash: None,
+            clone_limit: 0,
+        },
+        provisioning: Some(CellProvisioning::Create { deferred: false }),
+    }
+}
+
 pub fn scaffold_dna(
     app_file_tree: AppFileTree,
     template_file_tree: &FileTree,
     dna_name: &String,
+    path: &Option<PathBuf>,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     check_for_reserved_words(dna_name)?;
 
@@ -179,26 +236,18 @@ pub fn scaffold_dna(
         }
     };
 
-    let dnas_path = get_or_choose_dnas_dir_path(app_file_tree.file_tree_ref())?;
+    let dnas_path = get_or_choose_dnas_dir_path(app_file_tree.file_tree_ref(), path)?;
 
     let dna_workdir_path = PathBuf::new()
         .join(&dnas_path)
         .join(dna_name.clone())
         .join("workdir");
-    let mut dna_workdir_relative_to_app_manifest = PathBuf::new();
-
-    let app_workdir_path = app_file_tree.app_manifest_path.parent();
 
-    if let Some(path) = app_workdir_path {
-        for _path_segment in path.components() {
-            dna_workdir_relative_to_app_manifest = dna_workdir_relative_to_app_manifest.join("..");
-        }
-    }
-
-    dna_workdir_relative_to_app_manifest =
-        dna_workdir_relative_to_app_manifest.join(&dna_workdir_path);
-
-    let dna_bundle_path = dna_workdir_relative_to_app_manifest.join(format!("{}.dna", dna_name));
+    let dna_bundle_path = dna_bundle_path(
+        &app_file_tree.app_manifest_path,
+        &dna_workdir_path,
+        dna_name,
+    );
 
     let mut roles = app_file_tree.app_manifest.app_roles();
 
@@ -206,21 +255,7 @@ pub fn scaffold_dna(
         return Err(ScaffoldError::DnaAlreadyExists(dna_name.clone()));
     }
 
-    roles.push(AppRoleManifest {
-        name: dna_name.clone(),
-        dna: AppRoleDnaManifest {
-            location: Some(Location::Bundled(dna_bundle_path)),
-            modifiers: DnaModifiersOpt {
-                network_seed: None,
-                origin_time: None,
-                properties: None,
-                quantum_time: None,
-            },
-            installed_hash: None,
-            clone_limit: 0,
-        },
-        provisioning: Some(CellProvisioning::Create { deferred: false }),
-    });
+    roles.push(new_app_role_manifest(dna_name, dna_bundle_path));
 
     let new_manifest: AppManifest = AppManifestCurrentBuilder::default()
         .name(app_file_tree.app_manifest.app_name().to_string().clone())
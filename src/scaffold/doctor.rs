@@ -0,0 +1,854 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use cargo_metadata::Metadata;
+use convert_case::{Case, Casing};
+use holochain_types::prelude::{AppManifest, DnaManifest, ZomeManifest};
+use mr_bundle::Location;
+use path_clean::PathClean;
+use quote::ToTokens;
+use serde::Serialize;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{
+        dir_content, dir_exists, file_exists, find_files_by_name, find_map_rust_files, insert_file,
+        FileTree,
+    },
+};
+
+use super::{
+    app::{
+        cargo::{add_workspace_member, exec_metadata},
+        find_app_manifests,
+        utils::read_app_manifest,
+    },
+    dna::{
+        coordinator::{add_coordinator_zome_to_manifest, new_coordinator_zome_manifest},
+        dna_bundle_path, find_dna_manifests,
+        integrity::{add_integrity_zome_to_manifest, new_integrity_zome_manifest},
+        new_app_role_manifest, read_dna_manifest, DnaFileTree,
+    },
+    entry_type::integrity::get_all_entry_types,
+    link_type::integrity::get_all_link_types,
+    zome::{utils::get_coordinator_zomes_for_integrity, ZomeFileTree},
+};
+
+/// An inconsistency found in the app by `hc scaffold doctor`
+#[derive(Serialize, Debug, Clone)]
+pub struct Inconsistency {
+    pub description: String,
+    pub suggestion: String,
+    /// The manifest-level change that fixes this inconsistency, if it can be repaired automatically
+    pub repair: Option<Repair>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Repair {
+    SetRoleDnaLocation {
+        app_manifest_path: PathBuf,
+        role_name: String,
+        dna_manifest_path: PathBuf,
+    },
+    AddRole {
+        app_manifest_path: PathBuf,
+        dna_manifest_path: PathBuf,
+    },
+    AddWorkspaceMember {
+        crate_path: PathBuf,
+    },
+    AddIntegrityZome {
+        dna_manifest_path: PathBuf,
+        zome_name: String,
+    },
+    AddCoordinatorZome {
+        dna_manifest_path: PathBuf,
+        zome_name: String,
+        dependencies: Vec<String>,
+    },
+    RemoveZome {
+        dna_manifest_path: PathBuf,
+        zome_name: String,
+    },
+    RemoveZomeDependency {
+        dna_manifest_path: PathBuf,
+        coordinator_zome_name: String,
+        dependency: String,
+    },
+}
+
+impl Repair {
+    /// Whether this repair removes something from the manifests that might only look missing, eg. because of a wrong path,
+    /// so it's only applied if explicitly asked for
+    pub fn is_destructive(&self) -> bool {
+        matches!(self, Repair::RemoveZome { .. })
+    }
+}
+
+/// The result of `hc scaffold doctor`, as printed with `--output json`
+#[derive(Serialize, Debug, Clone)]
+pub struct DoctorReport {
+    pub inconsistencies: Vec<Inconsistency>,
+    pub repaired: Vec<Inconsistency>,
+    pub modified: Vec<PathBuf>,
+}
+
+/// Cross-checks the app manifests, DNA manifests, cargo workspace and zome code in the given project structure
+///
+/// Needs to be run from the root of the project, as it executes `cargo metadata` to get the workspace packages
+pub fn check_app_consistency(file_tree: &FileTree) -> ScaffoldResult<Vec<Inconsistency>> {
+    let app_manifests = find_app_manifests(file_tree)?;
+    let dna_manifests = find_dna_manifests(file_tree)?;
+
+    let mut inconsistencies = check_roles(&app_manifests, &dna_manifests);
+
+    for (dna_manifest_path, dna_manifest) in &dna_manifests {
+        inconsistencies.extend(check_coordinator_dependencies(
+            dna_manifest_path,
+            dna_manifest,
+        ));
+    }
+
+    match exec_metadata(file_tree) {
+        Ok(metadata) => {
+            inconsistencies.extend(check_workspace(file_tree, &dna_manifests, &metadata)?);
+
+            // The zome code can only be found for the zomes that are workspace members
+            let role_names = role_names_by_dna(&app_manifests, &dna_manifests);
+            for dna_manifest_path in dna_manifests.keys() {
+                inconsistencies.extend(check_integrity_zomes(
+                    file_tree,
+                    dna_manifest_path,
+                    role_names.get(dna_manifest_path),
+                )?);
+            }
+        }
+        Err(err) => inconsistencies.push(Inconsistency {
+            description: format!(
+                "Could not read the cargo workspace, so the zome crates were not checked: {}",
+                err
+            ),
+            suggestion: String::from("fix the errors reported by \"cargo metadata\" in the root Cargo.toml and the zome crates"),
+            repair: None,
+        }),
+    }
+
+    Ok(inconsistencies)
+}
+
+/// Returns the path to the dna.yaml that the given role bundles, if it exists
+pub fn role_dna_manifest_path(
+    app_manifest_path: &Path,
+    location: &Option<Location>,
+    dna_manifests: &BTreeMap<PathBuf, DnaManifest>,
+) -> Option<Option<PathBuf>> {
+    match location {
+        Some(Location::Bundled(bundled_location)) => {
+            let mut dna_workdir_path = app_manifest_path.to_path_buf();
+            dna_workdir_path.pop();
+            let mut bundled_location = bundled_location.clone();
+            bundled_location.pop();
+            let dna_workdir_path = dna_workdir_path.join(bundled_location).clean();
+
+            Some(
+                dna_manifests
+                    .keys()
+                    .find(|p| p.parent() == Some(dna_workdir_path.as_path()))
+                    .cloned(),
+            )
+        }
+        // DNAs from other locations are not part of this repository
+        _ => None,
+    }
+}
+
+fn role_names_by_dna(
+    app_manifests: &BTreeMap<PathBuf, AppManifest>,
+    dna_manifests: &BTreeMap<PathBuf, DnaManifest>,
+) -> BTreeMap<PathBuf, String> {
+    let mut role_names = BTreeMap::new();
+
+    for (app_manifest_path, app_manifest) in app_manifests {
+        for role in app_manifest.app_roles() {
+            if let Some(Some(dna_manifest_path)) =
+                role_dna_manifest_path(app_manifest_path, &role.dna.location, dna_manifests)
+            {
+                role_names.insert(dna_manifest_path, role.name.to_string());
+            }
+        }
+    }
+
+    role_names
+}
+
+fn check_roles(
+    app_manifests: &BTreeMap<PathBuf, AppManifest>,
+    dna_manifests: &BTreeMap<PathBuf, DnaManifest>,
+) -> Vec<Inconsistency> {
+    let mut inconsistencies = vec![];
+
+    for (app_manifest_path, app_manifest) in app_manifests {
+        for role in app_manifest.app_roles() {
+            if let Some(None) =
+                role_dna_manifest_path(app_manifest_path, &role.dna.location, dna_manifests)
+            {
+                let description = format!(
+                    "Role \"{}\" in {:?} points to a DNA bundle for which no dna.yaml was found",
+                    role.name, app_manifest_path
+                );
+
+                let dna_with_role_name = dna_manifests
+                    .iter()
+                    .find(|(_, m)| m.name().eq(&role.name.to_string()));
+
+                inconsistencies.push(match dna_with_role_name {
+                    Some((dna_manifest_path, _)) => Inconsistency {
+                        description,
+                        suggestion: format!(
+                            "point the role to the DNA in {:?}",
+                            dna_manifest_path
+                        ),
+                        repair: Some(Repair::SetRoleDnaLocation {
+                            app_manifest_path: app_manifest_path.clone(),
+                            role_name: role.name.to_string(),
+                            dna_manifest_path: dna_manifest_path.clone(),
+                        }),
+                    },
+                    None => Inconsistency {
+                        description,
+                        suggestion: format!(
+                            "fix the \"bundled\" location of the role, or remove the role from {:?} and run \"hc scaffold dna {}\"",
+                            app_manifest_path, role.name
+                        ),
+                        repair: None,
+                    },
+                });
+            }
+        }
+    }
+
+    let role_names = role_names_by_dna(app_manifests, dna_manifests);
+
+    // DNAs that a broken role will be pointed to are not reported on their own
+    let dnas_for_broken_roles: Vec<PathBuf> = inconsistencies
+        .iter()
+        .filter_map(|i| match &i.repair {
+            Some(Repair::SetRoleDnaLocation {
+                dna_manifest_path, ..
+            }) => Some(dna_manifest_path.clone()),
+            _ => None,
+        })
+        .collect();
+
+    for (dna_manifest_path, dna_manifest) in dna_manifests {
+        if role_names.contains_key(dna_manifest_path)
+            || dnas_for_broken_roles.contains(dna_manifest_path)
+        {
+            continue;
+        }
+
+        let name_is_free = |app_manifest: &AppManifest| {
+            !app_manifest
+                .app_roles()
+                .iter()
+                .any(|r| r.name.eq(&dna_manifest.name()))
+        };
+
+        let repair =
+            match app_manifests.len() {
+                1 => app_manifests.iter().find(|(_, m)| name_is_free(m)).map(
+                    |(app_manifest_path, _)| Repair::AddRole {
+                        app_manifest_path: app_manifest_path.clone(),
+                        dna_manifest_path: dna_manifest_path.clone(),
+                    },
+                ),
+                _ => None,
+            };
+
+        inconsistencies.push(Inconsistency {
+            description: format!(
+                "DNA \"{}\" in {:?} is not included in any app",
+                dna_manifest.name(),
+                dna_manifest_path
+            ),
+            suggestion: String::from(
+                "add a role that bundles this DNA to the happ.yaml of your app",
+            ),
+            repair,
+        });
+    }
+
+    inconsistencies
+}
+
+fn check_coordinator_dependencies(
+    dna_manifest_path: &Path,
+    dna_manifest: &DnaManifest,
+) -> Vec<Inconsistency> {
+    let (integrity_zomes, coordinator_zomes) = match dna_manifest.clone() {
+        DnaManifest::V1(m) => (m.integrity.zomes, m.coordinator.zomes),
+    };
+
+    let mut inconsistencies = vec![];
+
+    for coordinator_zome in coordinator_zomes {
+        for dependency in coordinator_zome.dependencies.clone().unwrap_or_default() {
+            if !integrity_zomes.iter().any(|z| z.name.eq(&dependency.name)) {
+                inconsistencies.push(Inconsistency {
+                    description: format!(
+                        "Coordinator zome \"{}\" in DNA \"{}\" depends on the integrity zome \"{}\", which doesn't exist in that DNA",
+                        coordinator_zome.name.0,
+                        dna_manifest.name(),
+                        dependency.name.0
+                    ),
+                    suggestion: format!(
+                        "remove the dependency from {:?}, or scaffold the integrity zome with \"hc scaffold zome {} --integrity <PATH>\"",
+                        dna_manifest_path, dependency.name.0
+                    ),
+                    repair: Some(Repair::RemoveZomeDependency {
+                        dna_manifest_path: dna_manifest_path.to_path_buf(),
+                        coordinator_zome_name: coordinator_zome.name.0.to_string(),
+                        dependency: dependency.name.0.to_string(),
+                    }),
+                });
+            }
+        }
+    }
+
+    inconsistencies
+}
+
+/// The name of the crate that builds the wasm for the given zome
+fn zome_crate_name(zome_manifest: &ZomeManifest) -> Option<String> {
+    match &zome_manifest.location {
+        Location::Bundled(bundled_path) => bundled_path
+            .file_name()
+            .and_then(|f| f.to_str())
+            .and_then(|f| f.split(".wasm").next())
+            .map(|f| f.to_string()),
+        _ => None,
+    }
+}
+
+fn all_zomes(dna_manifest: &DnaManifest) -> Vec<ZomeManifest> {
+    match dna_manifest.clone() {
+        DnaManifest::V1(m) => m
+            .integrity
+            .zomes
+            .into_iter()
+            .chain(m.coordinator.zomes)
+            .collect(),
+    }
+}
+
+fn check_workspace(
+    file_tree: &FileTree,
+    dna_manifests: &BTreeMap<PathBuf, DnaManifest>,
+    metadata: &Metadata,
+) -> ScaffoldResult<Vec<Inconsistency>> {
+    let current_dir = std::env::current_dir()?;
+
+    // Crate name -> path of the crate relative to the workspace root
+    let workspace_packages: BTreeMap<String, PathBuf> = metadata
+        .workspace_packages()
+        .into_iter()
+        .map(|p| {
+            let mut crate_path = p.manifest_path.clone().into_std_path_buf();
+            crate_path.pop();
+            let crate_path = crate_path
+                .strip_prefix(&current_dir)
+                .map(|p| p.to_path_buf())
+                .unwrap_or(crate_path);
+            (p.name.clone(), crate_path)
+        })
+        .collect();
+
+    let mut inconsistencies = vec![];
+
+    for (dna_manifest_path, dna_manifest) in dna_manifests {
+        for zome_manifest in all_zomes(dna_manifest) {
+            let crate_name = match zome_crate_name(&zome_manifest) {
+                Some(crate_name) => crate_name,
+                None => continue,
+            };
+            if workspace_packages.contains_key(&crate_name) {
+                continue;
+            }
+
+            let description = format!(
+                "Zome \"{}\" in DNA \"{}\" is built from the crate \"{}\", which is not a member of the cargo workspace",
+                zome_manifest.name.0,
+                dna_manifest.name(),
+                crate_name
+            );
+
+            inconsistencies.push(match find_crate_path(file_tree, &crate_name) {
+                Some(crate_path) => Inconsistency {
+                    description,
+                    suggestion: format!(
+                        "add {:?} to the \"members\" of the workspace in the root Cargo.toml",
+                        crate_path
+                    ),
+                    repair: Some(Repair::AddWorkspaceMember { crate_path }),
+                },
+                None => Inconsistency {
+                    description: format!("{}, and doesn't exist in this repository", description),
+                    suggestion: format!(
+                        "remove the zome from {:?}, or scaffold it again with \"hc scaffold zome\"",
+                        dna_manifest_path
+                    ),
+                    repair: Some(Repair::RemoveZome {
+                        dna_manifest_path: dna_manifest_path.clone(),
+                        zome_name: zome_manifest.name.0.to_string(),
+                    }),
+                },
+            });
+        }
+    }
+
+    let zome_crates: Vec<String> = dna_manifests
+        .values()
+        .flat_map(all_zomes)
+        .filter_map(|z| zome_crate_name(&z))
+        .collect();
+
+    for package in metadata.workspace_packages() {
+        let depends_on = |name: &str| package.dependencies.iter().any(|d| d.name.eq(name));
+        let is_coordinator = depends_on("hdk");
+
+        if !(is_coordinator || depends_on("hdi")) || zome_crates.contains(&package.name) {
+            continue;
+        }
+
+        let crate_path = workspace_packages[&package.name].clone();
+
+        // The DNA whose folder contains the crate
+        let dna = dna_manifests.iter().find(|(dna_manifest_path, _)| {
+            dna_manifest_path
+                .parent()
+                .and_then(|workdir| workdir.parent())
+                .is_some_and(|dna_path| crate_path.starts_with(dna_path))
+        });
+
+        let repair = dna.map(|(dna_manifest_path, dna_manifest)| match is_coordinator {
+            true => {
+                let integrity_zomes: Vec<String> = match dna_manifest.clone() {
+                    DnaManifest::V1(m) => m.integrity.zomes,
+                }
+                .into_iter()
+                .map(|z| z.name.0.to_string())
+                .collect();
+
+                Repair::AddCoordinatorZome {
+                    dna_manifest_path: dna_manifest_path.clone(),
+                    zome_name: package.name.clone(),
+                    dependencies: package
+                        .dependencies
+                        .iter()
+                        .map(|d| d.name.clone())
+                        .filter(|d| integrity_zomes.contains(d))
+                        .collect(),
+                }
+            }
+            false => Repair::AddIntegrityZome {
+                dna_manifest_path: dna_manifest_path.clone(),
+                zome_name: package.name.clone(),
+            },
+        });
+
+        inconsistencies.push(Inconsistency {
+            description: format!(
+                "The {} zome crate \"{}\" in {:?} is not included in any dna.yaml",
+                match is_coordinator {
+                    true => "coordinator",
+                    false => "integrity",
+                },
+                package.name,
+                crate_path
+            ),
+            suggestion: match dna {
+                Some((dna_manifest_path, _)) => {
+                    format!("add the zome to {:?}", dna_manifest_path)
+                }
+                None => {
+                    String::from("add the zome to the dna.yaml of its DNA, or remove the crate")
+                }
+            },
+            repair,
+        });
+    }
+
+    Ok(inconsistencies)
+}
+
+/// Finds the folder of the crate with the given name, even if it's not a member of the workspace
+fn find_crate_path(file_tree: &FileTree, crate_name: &String) -> Option<PathBuf> {
+    find_files_by_name(file_tree, &PathBuf::from("Cargo.toml"))
+        .into_iter()
+        .find(|(_, contents)| {
+            toml::from_str::<toml::Value>(contents)
+                .ok()
+                .and_then(|v| {
+                    v.get("package")
+                        .and_then(|p| p.get("name"))
+                        .and_then(|n| n.as_str())
+                        .map(|n| n.eq(crate_name))
+                })
+                .unwrap_or(false)
+        })
+        .and_then(|(path, _)| path.parent().map(|p| p.to_path_buf()))
+}
+
+fn check_integrity_zomes(
+    file_tree: &FileTree,
+    dna_manifest_path: &Path,
+    role_name: Option<&String>,
+) -> ScaffoldResult<Vec<Inconsistency>> {
+    let mut dna_file_tree =
+        DnaFileTree::from_dna_manifest_path(file_tree.clone(), &dna_manifest_path.to_path_buf())?;
+    let dna_name = dna_file_tree.dna_manifest.name();
+
+    let integrity_zomes = match dna_file_tree.dna_manifest.clone() {
+        DnaManifest::V1(m) => m.integrity.zomes,
+    };
+
+    let mut inconsistencies = vec![];
+
+    for zome_manifest in integrity_zomes {
+        // Zomes which crates are not in the workspace were already reported
+        let zome_file_tree =
+            match ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest.clone()) {
+                Ok(z) => z,
+                Err(_) => {
+                    dna_file_tree = DnaFileTree::from_dna_manifest_path(
+                        file_tree.clone(),
+                        &dna_manifest_path.to_path_buf(),
+                    )?;
+                    continue;
+                }
+            };
+        let zome_name = zome_manifest.name.0.to_string();
+
+        let entry_types: Vec<String> = match get_all_entry_types(&zome_file_tree) {
+            Ok(entry_types) => entry_types
+                .unwrap_or_default()
+                .into_iter()
+                .map(|e| e.entry_type)
+                .collect(),
+            Err(err @ ScaffoldError::MultipleEntryTypesDefsFoundForIntegrityZome(..)) => {
+                inconsistencies.push(Inconsistency {
+                    description: err.to_string(),
+                    suggestion: String::from(
+                        "merge them into a single #[hdk_entry_defs] enum in the zome",
+                    ),
+                    repair: None,
+                });
+                vec![]
+            }
+            // Coordinator zomes which crates are not in the workspace were already reported
+            Err(_) => vec![],
+        };
+        let link_types = get_all_link_types(&zome_file_tree)?;
+
+        match find_validate_function(&zome_file_tree)? {
+            None if !entry_types.is_empty() || !link_types.is_empty() => {
+                inconsistencies.push(Inconsistency {
+                    description: format!(
+                        "Integrity zome \"{}\" in DNA \"{}\" has no validate() function",
+                        zome_name, dna_name
+                    ),
+                    suggestion: String::from(
+                        "add an #[hdk_extern] validate(op: Op) function that validates all its entry and link types",
+                    ),
+                    repair: None,
+                })
+            }
+            None => {}
+            Some((validate_path, validate_code)) => {
+                let not_validated = entry_types
+                    .iter()
+                    .map(|e| format!("EntryTypes :: {}", e))
+                    .chain(link_types.iter().map(|l| format!("LinkTypes :: {}", l)))
+                    .filter(|variant| !validate_code.contains(variant.as_str()));
+
+                for variant in not_validated {
+                    let variant = variant.replace(' ', "");
+                    inconsistencies.push(Inconsistency {
+                        description: format!(
+                            "{} is not handled by validate() in integrity zome \"{}\" of DNA \"{}\"",
+                            variant, zome_name, dna_name
+                        ),
+                        suggestion: format!(
+                            "add match arms for {} to the validate() function in {:?}",
+                            variant, validate_path
+                        ),
+                        repair: None,
+                    });
+                }
+            }
+        }
+
+        if let Some(role_name) = role_name {
+            let coordinator_zomes = get_coordinator_zomes_for_integrity(
+                &zome_file_tree.dna_file_tree.dna_manifest,
+                &zome_name,
+            );
+
+            for entry_type in &entry_types {
+                for coordinator_zome in &coordinator_zomes {
+                    inconsistencies.extend(check_entry_type_files(
+                        zome_file_tree.dna_file_tree.file_tree_ref(),
+                        role_name,
+                        &coordinator_zome.name.0.to_string(),
+                        entry_type,
+                    ));
+                }
+            }
+        }
+
+        dna_file_tree = zome_file_tree.dna_file_tree;
+    }
+
+    Ok(inconsistencies)
+}
+
+/// Returns the path and the code of the validate() function in the given integrity zome
+fn find_validate_function(
+    zome_file_tree: &ZomeFileTree,
+) -> ScaffoldResult<Option<(PathBuf, String)>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    let validate_functions = find_map_rust_files(
+        zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_path, file| {
+            file.items.iter().find_map(|i| match i {
+                syn::Item::Fn(item_fn) if item_fn.sig.ident.eq("validate") => {
+                    Some(item_fn.to_token_stream().to_string())
+                }
+                _ => None,
+            })
+        },
+    );
+
+    Ok(validate_functions
+        .into_iter()
+        .next()
+        .map(|(path, code)| (crate_src_path.join(path), code)))
+}
+
+/// Checks that the tests and the UI components generated for the given entry type still exist
+fn check_entry_type_files(
+    file_tree: &FileTree,
+    role_name: &String,
+    coordinator_zome_name: &String,
+    entry_type: &String,
+) -> Vec<Inconsistency> {
+    let mut inconsistencies = vec![];
+
+    let entry_type_snake = entry_type.to_case(Case::Snake);
+
+    let tests_path = PathBuf::from("tests")
+        .join("src")
+        .join(role_name)
+        .join(coordinator_zome_name);
+    let test_path = tests_path.join(format!("{}.test.ts", entry_type.to_case(Case::Kebab)));
+
+    if dir_exists(file_tree, &PathBuf::from("tests").join("src"))
+        && !file_exists(file_tree, &test_path)
+    {
+        inconsistencies.push(Inconsistency {
+            description: format!(
+                "Entry type \"{}\" has no tests in {:?}",
+                entry_type_snake, test_path
+            ),
+            suggestion: format!(
+                "write tests for it, or copy them from a fresh \"hc scaffold entry-type {}\" in a scratch app",
+                entry_type_snake
+            ),
+            repair: None,
+        });
+    }
+
+    // Only check the UI if the template generates UI components for this zome
+    let ui_path = PathBuf::from("ui")
+        .join("src")
+        .join(role_name)
+        .join(coordinator_zome_name);
+    if let Ok(ui_files) = dir_content(file_tree, &ui_path) {
+        // Matches both "post-detail.ts" and "PostDetail.svelte"
+        let detail_component = format!("{}detail", entry_type_snake.replace('_', ""));
+        let has_detail_component = ui_files.keys().any(|file_name| {
+            file_name
+                .to_string_lossy()
+                .to_lowercase()
+                .replace('-', "")
+                .starts_with(&detail_component)
+        });
+
+        if !has_detail_component {
+            inconsistencies.push(Inconsistency {
+                description: format!(
+                    "Entry type \"{}\" has no detail component in {:?}",
+                    entry_type_snake, ui_path
+                ),
+                suggestion: format!(
+                    "create the UI components for it, or copy them from a fresh \"hc scaffold entry-type {}\" in a scratch app",
+                    entry_type_snake
+                ),
+                repair: None,
+            });
+        }
+    }
+
+    inconsistencies
+}
+
+/// Applies the given manifest-level repair to the project structure
+pub fn apply_repair(mut file_tree: FileTree, repair: &Repair) -> ScaffoldResult<FileTree> {
+    match repair {
+        Repair::SetRoleDnaLocation {
+            app_manifest_path,
+            role_name,
+            dna_manifest_path,
+        } => {
+            let dna_manifest = read_dna_manifest(&file_tree, dna_manifest_path)?;
+            let bundle_path =
+                dna_bundle_path_for_manifest(app_manifest_path, dna_manifest_path, &dna_manifest);
+
+            let mut app_manifest = read_app_manifest(&file_tree, app_manifest_path)?;
+            match &mut app_manifest {
+                AppManifest::V1(m) => {
+                    for role in m.roles.iter_mut().filter(|r| r.name.eq(role_name)) {
+                        role.dna.location = Some(Location::Bundled(bundle_path.clone()));
+                    }
+                }
+            }
+
+            insert_file(
+                &mut file_tree,
+                app_manifest_path,
+                &serde_yaml::to_string(&app_manifest)?,
+            )?;
+            Ok(file_tree)
+        }
+        Repair::AddRole {
+            app_manifest_path,
+            dna_manifest_path,
+        } => {
+            let dna_manifest = read_dna_manifest(&file_tree, dna_manifest_path)?;
+            let bundle_path =
+                dna_bundle_path_for_manifest(app_manifest_path, dna_manifest_path, &dna_manifest);
+
+            let mut app_manifest = read_app_manifest(&file_tree, app_manifest_path)?;
+            match &mut app_manifest {
+                AppManifest::V1(m) => m
+                    .roles
+                    .push(new_app_role_manifest(&dna_manifest.name(), bundle_path)),
+            }
+
+            insert_file(
+                &mut file_tree,
+                app_manifest_path,
+                &serde_yaml::to_string(&app_manifest)?,
+            )?;
+            Ok(file_tree)
+        }
+        Repair::AddWorkspaceMember { crate_path } => add_workspace_member(file_tree, crate_path),
+        Repair::AddIntegrityZome {
+            dna_manifest_path,
+            zome_name,
+        } => {
+            let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, dna_manifest_path)?;
+            let zome_manifest = new_integrity_zome_manifest(&dna_file_tree, zome_name)?;
+            Ok(add_integrity_zome_to_manifest(dna_file_tree, zome_manifest)?.file_tree())
+        }
+        Repair::AddCoordinatorZome {
+            dna_manifest_path,
+            zome_name,
+            dependencies,
+        } => {
+            let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, dna_manifest_path)?;
+            let dependencies = match dependencies.is_empty() {
+                true => None,
+                false => Some(dependencies.clone()),
+            };
+            let zome_manifest =
+                new_coordinator_zome_manifest(&dna_file_tree, zome_name, &dependencies)?;
+            Ok(add_coordinator_zome_to_manifest(dna_file_tree, zome_manifest)?.file_tree())
+        }
+        Repair::RemoveZome {
+            dna_manifest_path,
+            zome_name,
+        } => {
+            let mut dna_manifest = read_dna_manifest(&file_tree, dna_manifest_path)?;
+            match &mut dna_manifest {
+                DnaManifest::V1(m) => {
+                    m.integrity.zomes.retain(|z| !z.name.0.eq(zome_name));
+                    m.coordinator.zomes.retain(|z| !z.name.0.eq(zome_name));
+
+                    // Coordinator zomes can't depend on a removed integrity zome
+                    for z in m.coordinator.zomes.iter_mut() {
+                        if let Some(dependencies) = &mut z.dependencies {
+                            dependencies.retain(|d| !d.name.0.eq(zome_name));
+                        }
+                    }
+                }
+            }
+
+            insert_file(
+                &mut file_tree,
+                dna_manifest_path,
+                &serde_yaml::to_string(&dna_manifest)?,
+            )?;
+            Ok(file_tree)
+        }
+        Repair::RemoveZomeDependency {
+            dna_manifest_path,
+            coordinator_zome_name,
+            dependency,
+        } => {
+            let mut dna_manifest = read_dna_manifest(&file_tree, dna_manifest_path)?;
+            match &mut dna_manifest {
+                DnaManifest::V1(m) => {
+                    for z in m
+                        .coordinator
+                        .zomes
+                        .iter_mut()
+                        .filter(|z| z.name.0.eq(coordinator_zome_name))
+                    {
+                        if let Some(dependencies) = &mut z.dependencies {
+                            dependencies.retain(|d| !d.name.0.eq(dependency));
+                        }
+                    }
+                }
+            }
+
+            insert_file(
+                &mut file_tree,
+                dna_manifest_path,
+                &serde_yaml::to_string(&dna_manifest)?,
+            )?;
+            Ok(file_tree)
+        }
+    }
+}
+
+fn dna_bundle_path_for_manifest(
+    app_manifest_path: &Path,
+    dna_manifest_path: &Path,
+    dna_manifest: &DnaManifest,
+) -> PathBuf {
+    let mut dna_workdir_path = dna_manifest_path.to_path_buf();
+    dna_workdir_path.pop();
+
+    dna_bundle_path(app_manifest_path, &dna_workdir_path, &dna_manifest.name())
+}
@@ -4,6 +4,7 @@ use crate::{
     file_tree::FileTree,
     reserved_words::check_for_reserved_words,
     templates::{entry_type::scaffold_entry_type_templates, ScaffoldedTemplate},
+    utils::check_can_prompt,
 };
 
 use build_fs_tree::dir;
@@ -15,7 +16,10 @@ use crate::error::{ScaffoldError, ScaffoldResult};
 use self::{
     coordinator::{add_crud_functions_to_coordinator, updates_link_name},
     crud::Crud,
-    definitions::{EntryDefinition, EntryTypeReference, FieldDefinition, Referenceable},
+    definitions::{
+        EntryDefSettings, EntryDefinition, EntryTypeReference, FieldDefinition, FieldType,
+        Referenceable,
+    },
     fields::choose_fields,
     integrity::{add_entry_type_to_integrity_zome, get_all_entry_types},
 };
@@ -23,21 +27,27 @@ use self::{
 use super::{
     app::AppFileTree,
     link_type::{integrity::add_link_type_to_integrity_zome, link_type_name},
-    zome::{utils::get_coordinator_zomes_for_integrity, ZomeFileTree},
+    zome::{utils::get_or_choose_coordinator_zome_for_integrity, ZomeFileTree},
 };
 
+pub mod constraints;
 pub mod coordinator;
 pub mod crud;
 pub mod definitions;
 pub mod fields;
+pub mod from_struct;
 pub mod integrity;
 pub mod utils;
 
-fn check_field_definitions(
+pub fn check_field_definitions(
     entry_type_name: &String,
     zome_file_tree: &ZomeFileTree,
     fields: &Vec<FieldDefinition>,
 ) -> ScaffoldResult<()> {
+    for field_def in fields {
+        check_struct_fields(field_def)?;
+    }
+
     let entry_types = get_all_entry_types(zome_file_tree)?.unwrap_or_else(|| vec![]);
 
     let entry_types_names: Vec<String> = entry_types
@@ -71,6 +81,46 @@ fn check_field_definitions(
     }
 }
 
+pub fn check_struct_fields(field_def: &FieldDefinition) -> ScaffoldResult<()> {
+    let (kind, label, fields): (&str, &String, Vec<&FieldDefinition>) = match &field_def.field_type
+    {
+        FieldType::Struct { label, fields } => ("struct", label, fields.iter().collect()),
+        FieldType::Enum { label, variants } => (
+            "enum",
+            label,
+            variants.iter().flat_map(|v| v.fields.iter()).collect(),
+        ),
+        _ => return Ok(()),
+    };
+
+    if let Some(linked_field) = fields.iter().find(|f| f.linked_from.is_some()) {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "field \"{}\" of {} \"{}\" can't link from other entries, only the fields of the entry type can",
+            linked_field.field_name, kind, label
+        )));
+    }
+
+    if let Some(constrained_field) = fields.iter().find(|f| !f.constraints.is_empty()) {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "field \"{}\" of {} \"{}\" can't have constraints, only the fields of the entry type can",
+            constrained_field.field_name, kind, label
+        )));
+    }
+
+    if field_def.widget.is_some() && !field_def.field_type.can_be_rendered() {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "{} \"{}\" can only be rendered in the UI if all its fields have a widget and none of them are vectors or maps",
+            kind, label
+        )));
+    }
+
+    for f in fields {
+        check_struct_fields(f)?;
+    }
+
+    Ok(())
+}
+
 pub fn scaffold_entry_type(
     zome_file_tree: ZomeFileTree,
     template_file_tree: &FileTree,
@@ -78,7 +128,11 @@ pub fn scaffold_entry_type(
     maybe_crud: &Option<Crud>,
     maybe_reference_entry_hash: &Option<bool>,
     maybe_link_from_original_to_each_update: &Option<bool>,
+    maybe_only_author_can_update_and_delete: &Option<bool>,
     maybe_fields: &Option<Vec<FieldDefinition>>,
+    maybe_coordinator_zome: &Option<String>,
+    settings: &EntryDefSettings,
+    keep_existing_definition: bool,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     check_for_reserved_words(name)?;
 
@@ -101,6 +155,16 @@ pub fn scaffold_entry_type(
         }
     };
 
+    if let (true, Some(linked_field)) = (
+        settings.is_private(),
+        fields.iter().find(|f| f.linked_from.is_some()),
+    ) {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "field \"{}\" can't link from other entries: entry type \"{}\" is private, so other agents can't get its entries",
+            linked_field.field_name, name
+        )));
+    }
+
     let reference_entry_hash = match maybe_reference_entry_hash {
         Some(r) => r.clone(),
         None => {
@@ -122,15 +186,44 @@ pub fn scaffold_entry_type(
         }
     };
 
-    let crud = match maybe_crud {
+    let mut crud = match maybe_crud {
         Some(c) => c.clone(),
-        None => choose_crud(),
+        None => choose_crud()?,
+    };
+
+    crud.author_only = match crud.update || crud.delete {
+        true => match maybe_only_author_can_update_and_delete {
+            Some(a) => *a,
+            None => {
+                check_can_prompt(
+                    "whether only the author of an entry can update and delete it",
+                    "--only-author-can-update-and-delete <true|false>",
+                )?;
+
+                let selection = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt(
+                        "Who should be able to update and delete the entries of this type?",
+                    )
+                    .default(0)
+                    .item("Only their author (recommended)")
+                    .item("Any agent")
+                    .interact()?;
+
+                selection == 0
+            }
+        },
+        false => false,
     };
 
     let link_from_original_to_each_update = match crud.update {
         true => match maybe_link_from_original_to_each_update {
             Some(l) => l.clone(),
             None => {
+                check_can_prompt(
+                    "whether to link the original entry to each update",
+                    "--link-from-original-to-each-update <true|false>",
+                )?;
+
                 let selection = Select::with_theme(&ColorfulTheme::default())
                 .with_prompt("Should a link from the original entry be created when this entry is updated?")
                 .default(0)
@@ -148,11 +241,16 @@ pub fn scaffold_entry_type(
         name: name.clone(),
         fields,
         reference_entry_hash,
+        settings: settings.clone(),
     };
 
     let integrity_zome_name = zome_file_tree.zome_manifest.name.0.to_string();
 
-    let mut zome_file_tree = add_entry_type_to_integrity_zome(zome_file_tree, &entry_def, &crud)?;
+    // Entry types read from their hand-written struct keep their definition and validation
+    let mut zome_file_tree = match keep_existing_definition {
+        true => zome_file_tree,
+        false => add_entry_type_to_integrity_zome(zome_file_tree, &entry_def, &crud)?,
+    };
 
     let linked_from: Vec<Referenceable> = entry_def
         .fields
@@ -167,35 +265,19 @@ pub fn scaffold_entry_type(
             &Some(l),
             &Some(entry_def.referenceable()),
             crud.delete,
+            false,
+            None,
             &PathBuf::from(format!("{}.rs", entry_def.name.to_case(Case::Snake))),
+            &None,
         )?;
     }
 
-    let coordinator_zomes_for_integrity = get_coordinator_zomes_for_integrity(
+    let coordinator_zome = get_or_choose_coordinator_zome_for_integrity(
         &zome_file_tree.dna_file_tree.dna_manifest,
         &zome_file_tree.zome_manifest.name.0.to_string(),
-    );
-
-    let coordinator_zome = match coordinator_zomes_for_integrity.len() {
-        0 => Err(ScaffoldError::NoCoordinatorZomesFoundForIntegrityZome(
-            zome_file_tree.dna_file_tree.dna_manifest.name(),
-            zome_file_tree.zome_manifest.name.0.to_string(),
-        )),
-        1 => Ok(coordinator_zomes_for_integrity[0].clone()),
-        _ => {
-            let names: Vec<String> = coordinator_zomes_for_integrity
-                .iter()
-                .map(|z| z.name.0.to_string())
-                .collect();
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt("Which coordinator zome should the CRUD functions be scaffolded in?")
-                .default(0)
-                .items(&names[..])
-                .interact()?;
-
-            Ok(coordinator_zomes_for_integrity[selection].clone())
-        }
-    }?;
+        maybe_coordinator_zome,
+        "Which coordinator zome should the CRUD functions be scaffolded in?",
+    )?;
 
     if link_from_original_to_each_update {
         zome_file_tree = add_link_type_to_integrity_zome(
@@ -204,7 +286,10 @@ pub fn scaffold_entry_type(
             &Some(entry_def.referenceable()),
             &Some(entry_def.referenceable()),
             false,
+            false,
+            None,
             &PathBuf::from(format!("{}.rs", entry_def.name.to_case(Case::Snake))),
+            &None,
         )?;
     }
 
@@ -238,18 +323,20 @@ pub fn scaffold_entry_type(
     )
 }
 
-fn choose_crud() -> Crud {
+fn choose_crud() -> ScaffoldResult<Crud> {
+    check_can_prompt("CRUD functions", "--crud <CRUD>")?;
+
     let selections = MultiSelect::with_theme(&ColorfulTheme::default())
         .with_prompt("Which CRUD functions should be scaffolded (SPACE to select/unselect, ENTER to continue)?")
         .item_checked("Update", true)
         .item_checked("Delete", true)
-        .interact()
-        .unwrap();
+        .interact()?;
 
     let mut crud = Crud {
         delete: false,
 
         update: false,
+        author_only: false,
     };
 
     for selection in selections {
@@ -261,5 +348,5 @@ fn choose_crud() -> Crud {
         }
     }
 
-    crud
+    Ok(crud)
 }
@@ -0,0 +1,413 @@
+use regex_syntax::hir::{Class, Hir, HirKind};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ScaffoldError, ScaffoldResult};
+
+use super::definitions::{Cardinality, FieldType};
+
+/// Constraints on the values of a field, which get checked both in the validation of its entry type and in the UI
+///
+/// For vectors, the lengths and `non_empty` apply to the vector itself, and the rest of the constraints to each of its elements
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct FieldConstraints {
+    /// Minimum number of characters of a String, or of elements of a vector
+    pub min_length: Option<usize>,
+    /// Maximum number of characters of a String, or of elements of a vector
+    pub max_length: Option<usize>,
+    /// Minimum value of a number, as the literal it gets rendered with
+    pub min: Option<String>,
+    /// Maximum value of a number, as the literal it gets rendered with
+    pub max: Option<String>,
+    /// Regular expression that the whole String has to match
+    pub pattern: Option<String>,
+    /// Whether the String or the vector can't be empty
+    pub non_empty: bool,
+    /// Whether the field keeps the value it was created with when its entry gets updated
+    pub immutable: bool,
+}
+
+/// The usual sample for Strings, that the templates render when no constraints change it
+pub const LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.";
+
+impl FieldConstraints {
+    /// Parses the constraints given after the field type, as in "immutable;min_length=3;max_length=100;non_empty;pattern=[a-z ]+"
+    ///
+    /// The pattern takes the rest of the constraints, so that it can contain ";"
+    pub fn parse(
+        constraints_str: &str,
+        field_name: &str,
+        field_type: &FieldType,
+        cardinality: &Cardinality,
+    ) -> ScaffoldResult<FieldConstraints> {
+        let mut constraints = FieldConstraints::default();
+        let mut rest = constraints_str;
+
+        while !rest.is_empty() {
+            let (constraint, remaining) = match rest.starts_with("pattern=") {
+                true => (rest, ""),
+                false => rest.split_once(';').unwrap_or((rest, "")),
+            };
+            rest = remaining;
+
+            match constraint.split_once('=') {
+                Some(("min_length", value)) => {
+                    constraints.min_length = Some(parse_length(value, field_name)?)
+                }
+                Some(("max_length", value)) => {
+                    constraints.max_length = Some(parse_length(value, field_name)?)
+                }
+                Some(("min", value)) => {
+                    constraints.min = Some(number_literal(value, field_name, field_type)?)
+                }
+                Some(("max", value)) => {
+                    constraints.max = Some(number_literal(value, field_name, field_type)?)
+                }
+                Some(("pattern", value)) => constraints.pattern = Some(value.to_string()),
+                None if constraint.trim() == "non_empty" => constraints.non_empty = true,
+                None if constraint.trim() == "immutable" => constraints.immutable = true,
+                None if constraint.trim().is_empty() => {}
+                _ => Err(ScaffoldError::InvalidArguments(format!(
+                    "invalid constraint \"{}\" for field \"{}\", the supported constraints are \"immutable\", \"min_length=<N>\", \"max_length=<N>\", \"non_empty\", \"min=<NUMBER>\", \"max=<NUMBER>\" and \"pattern=<REGEX>\"",
+                    constraint, field_name
+                )))?,
+            }
+        }
+
+        constraints.check(field_name, field_type, cardinality)?;
+
+        Ok(constraints)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.eq(&FieldConstraints::default())
+    }
+
+    /// Checks that the constraints can be applied to a field of the given type, and that they don't contradict each other
+    pub fn check(
+        &self,
+        field_name: &str,
+        field_type: &FieldType,
+        cardinality: &Cardinality,
+    ) -> ScaffoldResult<()> {
+        let not_applicable = |constraint: &str| {
+            Err(ScaffoldError::InvalidArguments(format!(
+                "the constraint \"{}\" can't be applied to the field \"{}\" of type {}",
+                constraint,
+                field_name,
+                field_type.to_string()
+            )))
+        };
+
+        // Maps can't be validated element by element, but they can still be kept as they were created
+        if let Cardinality::Map { .. } = cardinality {
+            let value_constraints = FieldConstraints {
+                immutable: false,
+                ..self.clone()
+            };
+            if !value_constraints.is_empty() {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "only the \"immutable\" constraint is supported for the map in field \"{}\"",
+                    field_name
+                )));
+            }
+        }
+
+        if !lengths_apply(field_type, cardinality) {
+            if self.min_length.is_some() {
+                return not_applicable("min_length");
+            }
+            if self.max_length.is_some() {
+                return not_applicable("max_length");
+            }
+            if self.non_empty {
+                return not_applicable("non_empty");
+            }
+        }
+        if !field_type.is_number() {
+            if self.min.is_some() {
+                return not_applicable("min");
+            }
+            if self.max.is_some() {
+                return not_applicable("max");
+            }
+        }
+        if !matches!(field_type, FieldType::String) && self.pattern.is_some() {
+            return not_applicable("pattern");
+        }
+
+        if let (Some(min_length), Some(max_length)) = (self.min_length, self.max_length) {
+            if min_length > max_length {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "the min_length of field \"{}\" is greater than its max_length",
+                    field_name
+                )));
+            }
+        }
+        if let (Some(min), Some(max)) = (&self.min, &self.max) {
+            if min.parse::<f64>().ok() > max.parse::<f64>().ok() {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "the min of field \"{}\" is greater than its max",
+                    field_name
+                )));
+            }
+        }
+        if let Some(pattern) = &self.pattern {
+            regex::Regex::new(pattern).map_err(|e| {
+                ScaffoldError::InvalidArguments(format!(
+                    "invalid pattern for field \"{}\": {}",
+                    field_name, e
+                ))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// A String that satisfies the constraints, as a JavaScript literal,
+    /// or None if the usual sample for Strings already satisfies them
+    pub fn string_sample(&self, cardinality: &Cardinality) -> Option<String> {
+        let (min_length, max_length) = match cardinality {
+            Cardinality::Vector => (None, None),
+            _ => (self.min_length, self.max_length),
+        };
+
+        let sample = match &self.pattern {
+            Some(pattern) => {
+                let hir = regex_syntax::parse(pattern).ok()?;
+                let min_length = min_length.unwrap_or_default();
+
+                // Repeats the repetitions of the pattern further until the sample is long enough
+                let mut extra_repetitions = 0;
+                loop {
+                    let mut sample = String::new();
+                    push_matching_sample(&hir, extra_repetitions, &mut sample);
+                    if sample.chars().count() >= min_length || extra_repetitions >= min_length {
+                        break sample;
+                    }
+                    extra_repetitions += 1;
+                }
+            }
+            None => {
+                let lorem_length = LOREM_IPSUM.chars().count();
+                let length = lorem_length
+                    .max(min_length.unwrap_or_default())
+                    .min(max_length.unwrap_or(usize::MAX));
+                if length == lorem_length {
+                    return None;
+                }
+                [LOREM_IPSUM, " "]
+                    .concat()
+                    .chars()
+                    .cycle()
+                    .take(length)
+                    .collect()
+            }
+        };
+
+        serde_json::to_string(&sample).ok()
+    }
+}
+
+/// Whether the lengths and non_empty can be applied to a field with the given type and cardinality
+pub fn lengths_apply(field_type: &FieldType, cardinality: &Cardinality) -> bool {
+    match cardinality {
+        Cardinality::Vector => true,
+        Cardinality::Map { .. } => false,
+        _ => matches!(field_type, FieldType::String),
+    }
+}
+
+pub fn parse_length(value: &str, field_name: &str) -> ScaffoldResult<usize> {
+    value.trim().parse::<usize>().map_err(|_| {
+        ScaffoldError::InvalidArguments(format!(
+            "invalid length \"{}\" for field \"{}\", lengths must be non negative integers",
+            value, field_name
+        ))
+    })
+}
+
+/// The largest integer that javascript numbers can represent exactly, `Number.MAX_SAFE_INTEGER`
+pub const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Checks that the given value fits in the given number type, and returns the literal it gets rendered with
+pub fn number_literal(
+    value: &str,
+    field_name: &str,
+    field_type: &FieldType,
+) -> ScaffoldResult<String> {
+    let value = value.trim();
+    let fits = match field_type {
+        FieldType::U8 => value.parse::<u8>().is_ok(),
+        FieldType::U32 => value.parse::<u32>().is_ok(),
+        FieldType::I32 => value.parse::<i32>().is_ok(),
+        // The UI works with these as javascript numbers, which are only exact up to 2^53 - 1
+        FieldType::U64 => value
+            .parse::<u64>()
+            .is_ok_and(|n| n <= MAX_SAFE_INTEGER as u64),
+        FieldType::I64 => value
+            .parse::<i64>()
+            .is_ok_and(|n| n.unsigned_abs() <= MAX_SAFE_INTEGER as u64),
+        FieldType::F32 | FieldType::F64 => value.parse::<f64>().is_ok_and(|f| f.is_finite()),
+        _ => false,
+    };
+
+    if !fits {
+        let range = match field_type {
+            FieldType::U64 | FieldType::I64 => format!(
+                ", which must be at most {} in absolute value to be exact in the UI",
+                MAX_SAFE_INTEGER
+            ),
+            _ => String::new(),
+        };
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "invalid value \"{}\" for the field \"{}\" of type {}{}",
+            value,
+            field_name,
+            field_type.to_string(),
+            range
+        )));
+    }
+
+    // Floats are compared with float literals in the validation functions
+    match field_type {
+        FieldType::F32 | FieldType::F64 if !value.contains(['.', 'e', 'E']) => {
+            Ok(format!("{}.0", value))
+        }
+        _ => Ok(value.to_string()),
+    }
+}
+
+// Appends a simple string that matches the given regular expression, taking the first branch of alternations
+// and repeating each repetition the fewest times it allows, but at least once, plus the given extra repetitions
+fn push_matching_sample(hir: &Hir, extra_repetitions: usize, sample: &mut String) {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => {}
+        HirKind::Literal(literal) => sample.push_str(&String::from_utf8_lossy(&literal.0)),
+        HirKind::Class(Class::Unicode(class)) => {
+            let ranges: Vec<(char, char)> = class.iter().map(|r| (r.start(), r.end())).collect();
+            if let Some(c) = sample_char(&ranges) {
+                sample.push(c);
+            }
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            let ranges: Vec<(char, char)> = class
+                .iter()
+                .map(|r| (r.start() as char, r.end() as char))
+                .collect();
+            if let Some(c) = sample_char(&ranges) {
+                sample.push(c);
+            }
+        }
+        HirKind::Repetition(repetition) => {
+            let min = repetition.min.max(1) as usize;
+            let max = repetition.max.map(|max| max as usize).unwrap_or(usize::MAX);
+            for _ in 0..(min + extra_repetitions).min(max) {
+                push_matching_sample(&repetition.sub, extra_repetitions, sample);
+            }
+        }
+        HirKind::Capture(capture) => push_matching_sample(&capture.sub, extra_repetitions, sample),
+        HirKind::Concat(hirs) => {
+            for hir in hirs {
+                push_matching_sample(hir, extra_repetitions, sample);
+            }
+        }
+        HirKind::Alternation(hirs) => {
+            if let Some(hir) = hirs.first() {
+                push_matching_sample(hir, extra_repetitions, sample);
+            }
+        }
+    }
+}
+
+// Prefers readable characters when the class contains them
+fn sample_char(ranges: &[(char, char)]) -> Option<char> {
+    ['a', 'A', '0', ' ']
+        .into_iter()
+        .find(|c| ranges.iter().any(|(start, end)| start <= c && c <= end))
+        .or(ranges.first().map(|(start, _)| *start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_constraints() {
+        let constraints = FieldConstraints::parse(
+            "immutable;min_length=3;max_length=100;non_empty;pattern=[a-z;]+",
+            "title",
+            &FieldType::String,
+            &Cardinality::Single,
+        )
+        .unwrap();
+
+        assert_eq!(
+            constraints,
+            FieldConstraints {
+                min_length: Some(3),
+                max_length: Some(100),
+                min: None,
+                max: None,
+                pattern: Some(String::from("[a-z;]+")),
+                non_empty: true,
+                immutable: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_number_constraints() {
+        let constraints = FieldConstraints::parse(
+            "min=1;max=5",
+            "rating",
+            &FieldType::F32,
+            &Cardinality::Single,
+        )
+        .unwrap();
+
+        assert_eq!(constraints.min, Some(String::from("1.0")));
+        assert_eq!(constraints.max, Some(String::from("5.0")));
+
+        assert!(FieldConstraints::parse(
+            &format!("max={}", MAX_SAFE_INTEGER as u64 + 1),
+            "count",
+            &FieldType::U64,
+            &Cardinality::Single
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_constraints() {
+        let parse = |constraints_str: &str, field_type: &FieldType, cardinality: &Cardinality| {
+            FieldConstraints::parse(constraints_str, "field", field_type, cardinality)
+        };
+
+        assert!(parse("unique", &FieldType::String, &Cardinality::Single).is_err());
+        assert!(parse("min_length=-1", &FieldType::String, &Cardinality::Single).is_err());
+        assert!(parse(
+            "min_length=5;max_length=3",
+            &FieldType::String,
+            &Cardinality::Single
+        )
+        .is_err());
+        assert!(parse("min=5;max=3", &FieldType::U32, &Cardinality::Single).is_err());
+        assert!(parse("pattern=[a-z]+", &FieldType::U32, &Cardinality::Single).is_err());
+        assert!(parse("min=1", &FieldType::String, &Cardinality::Single).is_err());
+        assert!(parse("pattern=[a-z", &FieldType::String, &Cardinality::Single).is_err());
+    }
+
+    #[test]
+    fn test_parse_map_constraints() {
+        let map = Cardinality::Map {
+            key: FieldType::String,
+        };
+
+        assert!(
+            FieldConstraints::parse("immutable", "tags", &FieldType::String, &map)
+                .unwrap()
+                .immutable
+        );
+        assert!(FieldConstraints::parse("non_empty", "tags", &FieldType::String, &map).is_err());
+    }
+}
@@ -19,10 +19,56 @@ use super::{
     integrity::find_ending_match_expr_in_block,
 };
 
+/// Private entries are not published to the DHT, so their records can only be read from the source chain of their author
+pub fn source_chain_records_handler(entry_def: &EntryDefinition) -> String {
+    let snake_entry_def_name = entry_def.name.to_case(Case::Snake);
+    let pascal_entry_def_name = entry_def.name.to_case(Case::Pascal);
+
+    format!(
+        r#"fn get_{snake_entry_def_name}_records_from_source_chain() -> ExternResult<Vec<Record>> {{
+    query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::{pascal_entry_def_name}.try_into()?)
+            .include_entries(true),
+    )
+}}
+"#,
+    )
+}
+
+/// Expression that reads the record with the given action hash, as an `Option<Record>`
+fn get_record(entry_def: &EntryDefinition, action_hash: &str) -> String {
+    match entry_def.settings.is_private() {
+        true => format!(
+            "get_{}_records_from_source_chain()?.into_iter().find(|record| record.action_address().eq(&{}))",
+            entry_def.name.to_case(Case::Snake),
+            action_hash
+        ),
+        false => format!("get({}.clone(), GetOptions::default())?", action_hash),
+    }
+}
+
 pub fn no_update_read_handler(entry_def: &EntryDefinition) -> String {
     let hash_type = entry_def.referenceable().hash_type().to_string();
     let snake_entry_def_name = entry_def.name.to_case(Case::Snake);
 
+    if entry_def.settings.is_private() {
+        let find_record = match entry_def.referenceable().hash_type() {
+            FieldType::EntryHash => {
+                format!("record.action().entry_hash() == Some(&{snake_entry_def_name}_hash)")
+            }
+            _ => format!("record.action_address().eq(&{snake_entry_def_name}_hash)"),
+        };
+        return format!(
+            r#"#[hdk_extern]
+pub fn get_{snake_entry_def_name}({snake_entry_def_name}_hash: {hash_type}) -> ExternResult<Option<Record>> {{
+    let records = get_{snake_entry_def_name}_records_from_source_chain()?;
+    Ok(records.into_iter().find(|record| {find_record}))
+}}
+"#,
+        );
+    }
+
     match entry_def.referenceable().hash_type() {
         FieldType::ActionHash => format!(
             r#"#[hdk_extern]
@@ -112,6 +158,57 @@ pub fn get_all_revisions_for_{snake_entry_def_name}(original_{snake_entry_def_na
     )
 }
 
+/// Read handlers for private entries, which follow the updates in the source chain instead of the DHT
+pub fn private_read_handler_with_updates(entry_def: &EntryDefinition) -> String {
+    let snake_entry_def_name = entry_def.name.to_case(Case::Snake);
+
+    format!(
+        r#"#[hdk_extern]
+pub fn get_original_{snake_entry_def_name}(original_{snake_entry_def_name}_hash: ActionHash) -> ExternResult<Option<Record>> {{
+    let records = get_{snake_entry_def_name}_records_from_source_chain()?;
+    Ok(records.into_iter().find(|record| record.action_address().eq(&original_{snake_entry_def_name}_hash)))
+}}
+
+#[hdk_extern]
+pub fn get_latest_{snake_entry_def_name}(original_{snake_entry_def_name}_hash: ActionHash) -> ExternResult<Option<Record>> {{
+    let records = get_{snake_entry_def_name}_records_from_source_chain()?;
+
+    let mut latest_{snake_entry_def_name}_hash = original_{snake_entry_def_name}_hash;
+    while let Some(update) = records.iter().rev().find(|record| matches!(
+        record.action(),
+        Action::Update(update) if update.original_action_address.eq(&latest_{snake_entry_def_name}_hash)
+    )) {{
+        latest_{snake_entry_def_name}_hash = update.action_address().clone();
+    }}
+
+    Ok(records.into_iter().find(|record| record.action_address().eq(&latest_{snake_entry_def_name}_hash)))
+}}
+
+#[hdk_extern]
+pub fn get_all_revisions_for_{snake_entry_def_name}(original_{snake_entry_def_name}_hash: ActionHash) -> ExternResult<Vec<Record>> {{
+    let records = get_{snake_entry_def_name}_records_from_source_chain()?;
+
+    let mut revisions_hashes = vec![original_{snake_entry_def_name}_hash];
+    let revisions = records
+        .into_iter()
+        .filter(|record| {{
+            let is_revision = match record.action() {{
+                Action::Update(update) => revisions_hashes.contains(&update.original_action_address),
+                _ => revisions_hashes.contains(record.action_address()),
+            }};
+            if is_revision {{
+                revisions_hashes.push(record.action_address().clone());
+            }}
+            is_revision
+        }})
+        .collect();
+
+    Ok(revisions)
+}}
+"#,
+    )
+}
+
 pub fn updates_link_name(entry_def_name: &String) -> String {
     format!("{}Updates", entry_def_name.to_case(Case::Pascal))
 }
@@ -208,6 +305,15 @@ pub fn create_link_for_cardinality(
         Cardinality::Vector => format!(
             r#"  for base in {}.{}.clone() {{
     create_link(base, {}.clone(), LinkTypes::{}, ())?;
+  }}"#,
+            entry_def.name.to_case(Case::Snake),
+            field_name,
+            link_target,
+            link_type_name
+        ),
+        Cardinality::Map { .. } => format!(
+            r#"  for base in {}.{}.values().cloned() {{
+    create_link(base, {}.clone(), LinkTypes::{}, ())?;
   }}"#,
             entry_def.name.to_case(Case::Snake),
             field_name,
@@ -251,7 +357,7 @@ pub fn create_{}({}: {}) -> ExternResult<Record> {{
   let {}_hash = create_entry(&EntryTypes::{}({}.clone()))?;
 {}
     
-  let record = get({}_hash.clone(), GetOptions::default())?
+  let record = {}
         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Could not find the newly created {}"))))?;
 
   Ok(record)
@@ -264,19 +370,26 @@ pub fn create_{}({}: {}) -> ExternResult<Record> {{
         entry_def.name.to_case(Case::Pascal),
         entry_def.name.to_case(Case::Snake),
         create_links_str,
-        entry_def.name.to_case(Case::Snake),
+        get_record(
+            entry_def,
+            &format!("{}_hash", entry_def.name.to_case(Case::Snake))
+        ),
         entry_def.name.to_case(Case::Pascal)
     )
 }
 
-pub fn update_handler(entry_def_name: &String, link_from_original_to_each_update: bool) -> String {
+pub fn update_handler(
+    entry_def: &EntryDefinition,
+    link_from_original_to_each_update: bool,
+) -> String {
     match link_from_original_to_each_update {
-        true => update_handler_linking_on_each_update(entry_def_name),
-        false => update_handler_without_linking_on_each_update(entry_def_name),
+        true => update_handler_linking_on_each_update(entry_def),
+        false => update_handler_without_linking_on_each_update(entry_def),
     }
 }
 
-pub fn update_handler_without_linking_on_each_update(entry_def_name: &String) -> String {
+pub fn update_handler_without_linking_on_each_update(entry_def: &EntryDefinition) -> String {
+    let entry_def_name = &entry_def.name;
     format!(
         r#"#[derive(Serialize, Deserialize, Debug)]
 pub struct Update{}Input {{
@@ -288,7 +401,7 @@ pub struct Update{}Input {{
 pub fn update_{}(input: Update{}Input) -> ExternResult<Record> {{
   let updated_{}_hash = update_entry(input.previous_{}_hash, &input.updated_{})?;
 
-  let record = get(updated_{}_hash.clone(), GetOptions::default())?
+  let record = {}
         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Could not find the newly updated {}"))))?;
     
   Ok(record)
@@ -303,12 +416,16 @@ pub fn update_{}(input: Update{}Input) -> ExternResult<Record> {{
         entry_def_name.to_case(Case::Snake),
         entry_def_name.to_case(Case::Snake),
         entry_def_name.to_case(Case::Snake),
-        entry_def_name.to_case(Case::Snake),
+        get_record(
+            entry_def,
+            &format!("updated_{}_hash", entry_def_name.to_case(Case::Snake))
+        ),
         entry_def_name.to_case(Case::Pascal)
     )
 }
 
-pub fn update_handler_linking_on_each_update(entry_def_name: &String) -> String {
+pub fn update_handler_linking_on_each_update(entry_def: &EntryDefinition) -> String {
+    let entry_def_name = &entry_def.name;
     format!(
         r#"#[derive(Serialize, Deserialize, Debug)]
 pub struct Update{}Input {{
@@ -323,7 +440,7 @@ pub fn update_{}(input: Update{}Input) -> ExternResult<Record> {{
         
   create_link(input.original_{}_hash.clone(), updated_{}_hash.clone(), LinkTypes::{}, ())?;
 
-  let record = get(updated_{}_hash.clone(), GetOptions::default())?
+  let record = {}
         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Could not find the newly updated {}"))))?;
     
   Ok(record)
@@ -342,7 +459,10 @@ pub fn update_{}(input: Update{}Input) -> ExternResult<Record> {{
         entry_def_name.to_case(Case::Snake),
         entry_def_name.to_case(Case::Snake),
         updates_link_name(entry_def_name),
-        entry_def_name.to_case(Case::Snake),
+        get_record(
+            entry_def,
+            &format!("updated_{}_hash", entry_def_name.to_case(Case::Snake))
+        ),
         entry_def_name.to_case(Case::Pascal)
     )
 }
@@ -358,9 +478,16 @@ pub fn delete_handler(entry_def: &EntryDefinition) -> String {
         .filter(|field| field.linked_from.is_some())
         .collect();
 
-    let delete_depending_links = match linked_from_fields.is_empty() {
+    let get_record = match entry_def.settings.is_private() {
         true => format!(
             r#"
+    let record = get_{snake_entry_def_name}_records_from_source_chain()?
+        .into_iter()
+        .find(|record| record.action_address().eq(&original_{snake_entry_def_name}_hash))
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("{pascal_entry_def_name} not found"))))?;"#
+        ),
+        false => format!(
+            r#"
     let details = get_details(original_{snake_entry_def_name}_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("{{pascal_entry_def_name}} not found"))))?;
     let record = match details {{
@@ -368,20 +495,16 @@ pub fn delete_handler(entry_def: &EntryDefinition) -> String {
         _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
             "Malformed get details response"
         )))),
-    }}?;
-            "#
+    }}?;"#
         ),
+    };
+
+    let delete_depending_links = match linked_from_fields.is_empty() {
+        true if entry_def.settings.is_private() => String::new(),
+        true => get_record.clone(),
         false => {
             let mut delete_links = format!(
-                r#"
-    let details = get_details(original_{snake_entry_def_name}_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("{{pascal_entry_def_name}} not found"))))?;
-    let record = match details {{
-        Details::Record(details) => Ok(details.record),
-        _ => Err(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Malformed get details response"
-        )))),
-    }}?;
+                r#"{get_record}
     let entry = record.entry().as_option().ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
             "{pascal_entry_def_name} record has no entry"
         ))))?;
@@ -433,6 +556,20 @@ pub fn delete_handler(entry_def: &EntryDefinition) -> String {
                 }}
             }}
         }}
+    }} 
+                                    "#
+                    ),
+                    Cardinality::Map { .. } => format!(
+                        r#"
+    for base_address in {snake_entry_def_name}.{field_name}.into_values() {{
+        let links = get_links(base_address.clone(), LinkTypes::{link_type}, None)?;
+        for link in links {{
+            if let Some(action_hash) = link.target.into_action_hash() {{
+                if action_hash.eq(&original_{snake_entry_def_name}_hash) {{
+                    delete_link(link.create_link_hash)?;
+                }}
+            }}
+        }}
     }} 
                                     "#
                     ),
@@ -442,14 +579,26 @@ pub fn delete_handler(entry_def: &EntryDefinition) -> String {
             delete_links
         }
     };
-    format!(
-        r#"#[hdk_extern]
-pub fn delete_{snake_entry_def_name}(original_{snake_entry_def_name}_hash: ActionHash) -> ExternResult<ActionHash> {{
-  {delete_depending_links}
-  delete_entry(original_{snake_entry_def_name}_hash)
-}}
+    let get_all_deletes = match entry_def.settings.is_private() {
+        true => format!(
+            r#"#[hdk_extern]
+pub fn get_all_deletes_for_{snake_entry_def_name}(
+    original_{snake_entry_def_name}_hash: ActionHash,
+) -> ExternResult<Option<Vec<SignedActionHashed>>> {{
+    let deletes = query(ChainQueryFilter::new().action_type(ActionType::Delete))?
+        .into_iter()
+        .filter(|record| matches!(
+            record.action(),
+            Action::Delete(delete) if delete.deletes_address.eq(&original_{snake_entry_def_name}_hash)
+        ))
+        .map(|record| record.signed_action)
+        .collect();
 
-#[hdk_extern]
+    Ok(Some(deletes))
+}}"#
+        ),
+        false => format!(
+            r#"#[hdk_extern]
 pub fn get_all_deletes_for_{snake_entry_def_name}(
     original_{snake_entry_def_name}_hash: ActionHash,
 ) -> ExternResult<Option<Vec<SignedActionHashed>>> {{
@@ -463,8 +612,19 @@ pub fn get_all_deletes_for_{snake_entry_def_name}(
         ))),
         Details::Record(record_details) => Ok(Some(record_details.deletes)),
     }}
+}}"#
+        ),
+    };
+
+    format!(
+        r#"#[hdk_extern]
+pub fn delete_{snake_entry_def_name}(original_{snake_entry_def_name}_hash: ActionHash) -> ExternResult<ActionHash> {{
+  {delete_depending_links}
+  delete_entry(original_{snake_entry_def_name}_hash)
 }}
 
+{get_all_deletes}
+
 #[hdk_extern]
 pub fn get_oldest_delete_for_{snake_entry_def_name}(
     original_{snake_entry_def_name}_hash: ActionHash,
@@ -497,8 +657,15 @@ use {}::*;
         create_handler(entry_def)
     );
 
+    if entry_def.settings.is_private() {
+        initial.push_str(source_chain_records_handler(entry_def).as_str());
+    }
+
     if !crud.update {
         initial.push_str(no_update_read_handler(entry_def).as_str());
+    } else if entry_def.settings.is_private() {
+        // The updates of private entries are also in the source chain, so there is no need to follow the links to them
+        initial.push_str(private_read_handler_with_updates(entry_def).as_str());
     } else {
         if link_from_original_to_each_update {
             initial.push_str(read_handler_with_linking_to_updates(&entry_def.name).as_str());
@@ -507,8 +674,7 @@ use {}::*;
         }
     }
     if crud.update {
-        initial
-            .push_str(update_handler(&entry_def.name, link_from_original_to_each_update).as_str());
+        initial.push_str(update_handler(entry_def, link_from_original_to_each_update).as_str());
     }
     if crud.delete {
         initial.push_str(delete_handler(&entry_def).as_str());
@@ -517,7 +683,8 @@ use {}::*;
     for f in &entry_def.fields {
         if let Some(linked_from) = &f.linked_from {
             initial.push_str(
-                get_links_handler(&linked_from, &entry_def.referenceable(), crud.delete).as_str(),
+                get_links_handler(linked_from, &entry_def.referenceable(), crud.delete, &None)
+                    .as_str(),
             );
         }
     }
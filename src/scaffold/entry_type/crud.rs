@@ -5,6 +5,8 @@ pub struct Crud {
     // We don't include create and read because they must always exist
     pub update: bool,
     pub delete: bool,
+    /// Whether only the author of an entry can update and delete it
+    pub author_only: bool,
 }
 
 pub fn parse_crud(crud_str: &str) -> Result<Crud, String> {
@@ -18,6 +20,7 @@ pub fn parse_crud(crud_str: &str) -> Result<Crud, String> {
     let mut crud = Crud {
         update: false,
         delete: false,
+        author_only: false,
     };
 
     for c in crud_str.chars() {
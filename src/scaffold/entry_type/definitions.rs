@@ -8,6 +8,8 @@ use crate::{
     utils::check_case,
 };
 
+use super::constraints::FieldConstraints;
+
 #[derive(Deserialize, Debug, Clone, Serialize)]
 #[serde(tag = "type")]
 pub enum FieldType {
@@ -20,6 +22,15 @@ pub enum FieldType {
     I32,
     #[serde(rename = "f32")]
     F32,
+    #[serde(rename = "u64")]
+    U64,
+    #[serde(rename = "i64")]
+    I64,
+    #[serde(rename = "f64")]
+    F64,
+    #[serde(rename = "u8")]
+    U8,
+    Bytes,
     Timestamp,
     AgentPubKey,
     ActionHash,
@@ -27,10 +38,23 @@ pub enum FieldType {
     DnaHash,
     Enum {
         label: String,
-        variants: Vec<String>,
+        variants: Vec<EnumVariant>,
+    },
+    Struct {
+        label: String,
+        #[serde(skip_deserializing)]
+        fields: Vec<FieldDefinition>,
     },
 }
 
+/// A variant of an enum field type, which can carry named fields like a struct
+#[derive(Deserialize, Debug, Clone, Serialize)]
+pub struct EnumVariant {
+    pub name: String,
+    #[serde(skip_deserializing)]
+    pub fields: Vec<FieldDefinition>,
+}
+
 impl TryFrom<String> for FieldType {
     type Error = ScaffoldError;
     fn try_from(value: String) -> Result<Self, Self::Error> {
@@ -61,12 +85,18 @@ impl ToString for FieldType {
             U32 => "u32",
             I32 => "i32",
             F32 => "f32",
+            U64 => "u64",
+            I64 => "i64",
+            F64 => "f64",
+            U8 => "u8",
+            Bytes => "Bytes",
             Timestamp => "Timestamp",
             ActionHash => "ActionHash",
             EntryHash => "EntryHash",
             DnaHash => "DnaHash",
             AgentPubKey => "AgentPubKey",
             Enum { .. } => "Enum",
+            Struct { .. } => "Struct",
         }
         .into()
     }
@@ -80,6 +110,11 @@ impl FieldType {
             FieldType::U32,
             FieldType::I32,
             FieldType::F32,
+            FieldType::U64,
+            FieldType::I64,
+            FieldType::F64,
+            FieldType::U8,
+            FieldType::Bytes,
             FieldType::Timestamp,
             FieldType::ActionHash,
             FieldType::EntryHash,
@@ -89,6 +124,10 @@ impl FieldType {
                 label: String::from(""),
                 variants: vec![],
             },
+            FieldType::Struct {
+                label: String::from(""),
+                fields: vec![],
+            },
         ]
     }
 
@@ -100,12 +139,17 @@ impl FieldType {
             U32 => quote!(u32),
             I32 => quote!(i32),
             F32 => quote!(f32),
+            U64 => quote!(u64),
+            I64 => quote!(i64),
+            F64 => quote!(f64),
+            U8 => quote!(u8),
+            Bytes => quote!(SerializedBytes),
             Timestamp => quote!(Timestamp),
             ActionHash => quote!(ActionHash),
             DnaHash => quote!(DnaHash),
             EntryHash => quote!(EntryHash),
             AgentPubKey => quote!(AgentPubKey),
-            Enum { label, .. } => {
+            Enum { label, .. } | Struct { label, .. } => {
                 let ident = format_ident!("{}", label);
                 quote!(#ident)
             }
@@ -116,32 +160,132 @@ impl FieldType {
     pub fn rust_type_definition(&self) -> Option<TokenStream> {
         match self {
             FieldType::Enum { label, variants } => {
-                let variants_expressions: Vec<syn::Expr> = variants
+                let nested_definitions: Vec<TokenStream> = variants
+                    .iter()
+                    .flat_map(|variant| variant.fields.iter())
+                    .filter_map(|field_def| field_def.field_type.rust_type_definition())
+                    .collect();
+                let variants_definitions: Vec<TokenStream> = variants
                     .iter()
-                    .cloned()
                     .map(|variant| {
-                        let e: syn::Expr = syn::parse_str(variant.to_case(Case::Pascal).as_str())
-                            .expect("Unable to parse");
-                        e
+                        let variant_ident = format_ident!("{}", variant.name.to_case(Case::Pascal));
+                        if variant.fields.is_empty() {
+                            return quote!(#variant_ident);
+                        }
+                        let field_names: Vec<syn::Ident> = variant
+                            .fields
+                            .iter()
+                            .map(|field_def| format_ident!("{}", field_def.field_name))
+                            .collect();
+                        let field_types: Vec<TokenStream> = variant
+                            .fields
+                            .iter()
+                            .map(|field_def| field_def.rust_type())
+                            .collect();
+                        quote! {
+                            #variant_ident {
+                              #(#field_names: #field_types),*
+                            }
+                        }
                     })
                     .collect();
 
                 let label_ident = format_ident!("{}", label);
                 let enum_definition = quote! {
+                    #(#nested_definitions)*
+
                     #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
                     #[serde(tag = "type")]
                     pub enum #label_ident {
-                      #(#variants_expressions),*
+                      #(#variants_definitions),*
                     }
                 };
                 Some(enum_definition)
             }
+            FieldType::Struct { label, fields } => {
+                let nested_definitions: Vec<TokenStream> = fields
+                    .iter()
+                    .filter_map(|field_def| field_def.field_type.rust_type_definition())
+                    .collect();
+                let field_names: Vec<syn::Ident> = fields
+                    .iter()
+                    .map(|field_def| format_ident!("{}", field_def.field_name))
+                    .collect();
+                let field_types: Vec<TokenStream> = fields
+                    .iter()
+                    .map(|field_def| field_def.rust_type())
+                    .collect();
+
+                let label_ident = format_ident!("{}", label);
+                let struct_definition = quote! {
+                    #(#nested_definitions)*
+
+                    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+                    pub struct #label_ident {
+                      #(pub #field_names: #field_types),*
+                    }
+                };
+                Some(struct_definition)
+            }
             _ => None,
         }
     }
+
+    /// The names of the types that this field type defines, starting with its own and followed by the ones nested in it
+    pub fn defined_types(&self) -> Vec<String> {
+        match self {
+            FieldType::Enum { label, variants } => {
+                let mut defined_types = vec![label.clone()];
+                for field_def in variants.iter().flat_map(|variant| variant.fields.iter()) {
+                    defined_types.extend(field_def.field_type.defined_types());
+                }
+                defined_types
+            }
+            FieldType::Struct { label, fields } => {
+                let mut defined_types = vec![label.clone()];
+                for field_def in fields {
+                    defined_types.extend(field_def.field_type.defined_types());
+                }
+                defined_types
+            }
+            _ => vec![],
+        }
+    }
+
+    /// A struct, or an enum with fields in its variants, can only be rendered in the UI if all their fields can,
+    /// and none of them are vectors or maps
+    pub fn can_be_rendered(&self) -> bool {
+        let fields: Vec<&FieldDefinition> = match self {
+            FieldType::Struct { fields, .. } => fields.iter().collect(),
+            FieldType::Enum { variants, .. } => {
+                variants.iter().flat_map(|v| v.fields.iter()).collect()
+            }
+            _ => vec![],
+        };
+
+        fields.into_iter().all(|field_def| {
+            field_def.widget.is_some()
+                && matches!(
+                    field_def.cardinality,
+                    Cardinality::Single | Cardinality::Option
+                )
+                && field_def.field_type.can_be_rendered()
+        })
+    }
+
+    /// The keys of maps end up as the keys of plain objects in the UI and the tests,
+    /// which javascript can only represent as strings
+    pub fn can_be_map_key(&self) -> bool {
+        matches!(self, FieldType::String)
+    }
+
+    pub fn is_number(&self) -> bool {
+        use FieldType::*;
+        matches!(self, U8 | U32 | I32 | F32 | U64 | I64 | F64)
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone)]
 pub enum Cardinality {
     #[serde(rename = "single")]
     Single,
@@ -149,20 +293,66 @@ pub enum Cardinality {
     Vector,
     #[serde(rename = "option")]
     Option,
+    #[serde(rename = "map")]
+    Map { key: FieldType },
 }
 
-#[derive(Serialize, Debug, Clone)]
+// Templates compare the cardinality with plain strings, the key type of maps is serialized with the field definition
+impl Serialize for Cardinality {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(match self {
+            Cardinality::Single => "single",
+            Cardinality::Vector => "vector",
+            Cardinality::Option => "option",
+            Cardinality::Map { .. } => "map",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct FieldDefinition {
     pub field_name: String,
     pub field_type: FieldType,
     pub widget: Option<String>,
     pub cardinality: Cardinality,
     pub linked_from: Option<Referenceable>,
+    pub constraints: FieldConstraints,
+}
+
+impl Serialize for FieldDefinition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let map_key_type = match &self.cardinality {
+            Cardinality::Map { key } => Some(key),
+            _ => None,
+        };
+
+        let string_sample = match &self.field_type {
+            FieldType::String => self.constraints.string_sample(&self.cardinality),
+            _ => None,
+        };
+
+        let mut state = serializer.serialize_struct("FieldDefinition", 8)?;
+        state.serialize_field("field_name", &self.field_name)?;
+        state.serialize_field("field_type", &self.field_type)?;
+        state.serialize_field("widget", &self.widget)?;
+        state.serialize_field("cardinality", &self.cardinality)?;
+        state.serialize_field("map_key_type", &map_key_type)?;
+        state.serialize_field("linked_from", &self.linked_from)?;
+        state.serialize_field("constraints", &self.constraints)?;
+        state.serialize_field("string_sample", &string_sample)?;
+        state.end()
+    }
 }
 
 impl FieldDefinition {
     pub fn rust_type(&self) -> TokenStream {
-        match self.cardinality {
+        match &self.cardinality {
             Cardinality::Single => self.field_type.rust_type(),
             Cardinality::Option => {
                 let rust_representation_type = self.field_type.rust_type();
@@ -174,6 +364,24 @@ impl FieldDefinition {
 
                 quote! {Vec<#rust_representation_type>}
             }
+            Cardinality::Map { key } => {
+                let rust_key_type = key.rust_type();
+                let rust_representation_type = self.field_type.rust_type();
+
+                quote! {BTreeMap<#rust_key_type, #rust_representation_type>}
+            }
+        }
+    }
+
+    /// Whether this field, or any of the fields of the structs and enum variants nested in it, is a map
+    pub fn uses_map(&self) -> bool {
+        match (&self.cardinality, &self.field_type) {
+            (Cardinality::Map { .. }, _) => true,
+            (_, FieldType::Struct { fields, .. }) => fields.iter().any(|f| f.uses_map()),
+            (_, FieldType::Enum { variants, .. }) => variants
+                .iter()
+                .any(|variant| variant.fields.iter().any(|f| f.uses_map())),
+            _ => false,
         }
     }
 }
@@ -192,7 +400,7 @@ impl EntryTypeReference {
     }
     pub fn field_name(&self, cardinality: &Cardinality) -> String {
         match cardinality {
-            Cardinality::Vector => format!(
+            Cardinality::Vector | Cardinality::Map { .. } => format!(
                 "{}_hashes",
                 pluralizer::pluralize(self.entry_type.as_str(), 2, false).to_case(Case::Snake)
             ),
@@ -201,7 +409,9 @@ impl EntryTypeReference {
     }
     pub fn to_string(&self, c: &Cardinality) -> String {
         match c {
-            Cardinality::Vector => pluralizer::pluralize(self.entry_type.as_str(), 2, false),
+            Cardinality::Vector | Cardinality::Map { .. } => {
+                pluralizer::pluralize(self.entry_type.as_str(), 2, false)
+            }
             _ => pluralizer::pluralize(self.entry_type.as_str(), 1, false),
         }
     }
@@ -288,7 +498,9 @@ impl Referenceable {
         };
 
         match c {
-            Cardinality::Vector => pluralizer::pluralize(singular.as_str(), 2, false),
+            Cardinality::Vector | Cardinality::Map { .. } => {
+                pluralizer::pluralize(singular.as_str(), 2, false)
+            }
             _ => singular,
         }
     }
@@ -299,6 +511,94 @@ pub struct EntryDefinition {
     pub name: String,
     pub fields: Vec<FieldDefinition>,
     pub reference_entry_hash: bool,
+    #[serde(flatten)]
+    pub settings: EntryDefSettings,
+}
+
+/// Whether the entries of an entry type are published to the DHT or only kept in the source chain of their author
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryVisibility {
+    #[default]
+    Public,
+    Private,
+}
+
+pub fn parse_visibility(visibility_str: &str) -> Result<EntryVisibility, String> {
+    match visibility_str {
+        "public" => Ok(EntryVisibility::Public),
+        "private" => Ok(EntryVisibility::Private),
+        _ => Err(String::from(
+            "Only \"public\" and \"private\" are allowed in the visibility argument",
+        )),
+    }
+}
+
+/// The settings of an entry type that go in the `#[entry_def]` attribute of its variant in the `EntryTypes` enum
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct EntryDefSettings {
+    pub visibility: EntryVisibility,
+    /// Number of validation receipts that the author waits for, the default of holochain if None
+    pub required_validations: Option<u8>,
+    pub cache_at_agent_activity: bool,
+}
+
+impl EntryDefSettings {
+    pub fn is_private(&self) -> bool {
+        self.visibility == EntryVisibility::Private
+    }
+
+    /// The `#[entry_def(...)]` attribute for these settings, or None if they are all the defaults
+    pub fn entry_def_attribute(&self) -> Option<String> {
+        let mut args: Vec<String> = vec![];
+
+        if self.is_private() {
+            args.push(String::from("visibility = \"private\""));
+        }
+        if let Some(required_validations) = self.required_validations {
+            args.push(format!("required_validations = {}", required_validations));
+        }
+        if self.cache_at_agent_activity {
+            args.push(String::from("cache_at_agent_activity = true"));
+        }
+
+        match args.is_empty() {
+            true => None,
+            false => Some(format!("#[entry_def({})]", args.join(", "))),
+        }
+    }
+
+    /// Reads the settings back from the attributes of the variant of an entry type
+    pub fn from_variant(variant: &syn::Variant) -> EntryDefSettings {
+        let mut settings = EntryDefSettings::default();
+
+        for attr in &variant.attrs {
+            if !attr.path().is_ident("entry_def") {
+                continue;
+            }
+            let _ = attr.parse_nested_meta(|meta| {
+                let value: syn::Lit = meta.value()?.parse()?;
+                match (meta.path.get_ident().map(|i| i.to_string()), value) {
+                    (Some(key), syn::Lit::Str(s))
+                        if key == "visibility" && s.value() == "private" =>
+                    {
+                        settings.visibility = EntryVisibility::Private
+                    }
+                    (Some(key), syn::Lit::Int(i)) if key == "required_validations" => {
+                        settings.required_validations = i.base10_parse().ok()
+                    }
+                    (Some(key), syn::Lit::Bool(b)) if key == "cache_at_agent_activity" => {
+                        settings.cache_at_agent_activity = b.value
+                    }
+                    _ => {}
+                }
+                Ok(())
+            });
+        }
+
+        settings
+    }
 }
 
 impl EntryDefinition {
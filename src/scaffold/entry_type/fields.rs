@@ -1,38 +1,162 @@
 use std::path::PathBuf;
 
 use convert_case::{Case, Casing};
-use dialoguer::{theme::ColorfulTheme, Confirm, Select};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
 use regex::Regex;
 
 use crate::{
-    error::ScaffoldResult,
+    error::{ScaffoldError, ScaffoldResult},
     file_tree::{dir_content, FileTree},
     scaffold::zome::ZomeFileTree,
-    utils::{check_case, input_with_case, input_with_case_and_initial_text},
+    utils::{
+        check_can_prompt, check_case, input_with_case, input_with_case_and_initial_text,
+        prompts_disabled,
+    },
 };
 
 use super::{
-    definitions::{Cardinality, EntryTypeReference, FieldDefinition, FieldType, Referenceable},
+    constraints::{lengths_apply, number_literal, parse_length, FieldConstraints},
+    definitions::{
+        Cardinality, EntryTypeReference, EnumVariant, FieldDefinition, FieldType, Referenceable,
+    },
     integrity::get_all_entry_types,
 };
 
 fn parse_enum(fields_str: &str) -> ScaffoldResult<FieldType> {
-    let sp: Vec<&str> = fields_str.split(":").collect();
+    // The variants may contain ":" in their fields, so only the first four separators are split
+    let sp: Vec<&str> = fields_str.splitn(5, ':').collect();
 
-    let label = sp[3].to_string().to_case(Case::Pascal);
+    let (label, variants_str) = match (sp.get(3), sp.get(4)) {
+        (Some(label), Some(variants_str)) if !label.is_empty() && !variants_str.is_empty() => {
+            (label.to_string().to_case(Case::Pascal), *variants_str)
+        }
+        _ => Err(ScaffoldError::InvalidArguments(format!(
+            "the enum in field \"{}\" has no name or variants, enum fields must be given as \"<FIELD_NAME>:Enum:<WIDGET>:<ENUM_NAME>:<VARIANT>.<VARIANT>{{<FIELD>;<FIELD>...}}...\"",
+            sp[0]
+        )))?,
+    };
 
-    let variants = sp[4]
-        .to_string()
-        .split(".")
-        .map(|v| v.to_case(Case::Pascal))
-        .collect();
+    let variants = split_top_level(variants_str, '.')
+        .into_iter()
+        .map(parse_enum_variant)
+        .collect::<ScaffoldResult<Vec<EnumVariant>>>()?;
 
     Ok(FieldType::Enum { label, variants })
 }
 
-pub fn parse_fields(fields_str: &str) -> ScaffoldResult<FieldDefinition> {
+// Parses a variant as "Rejected" or, if it carries fields, as "Rejected{reason:String:TextField}"
+fn parse_enum_variant(variant_str: &str) -> ScaffoldResult<EnumVariant> {
+    let (name, fields_str) = split_braces(variant_str)?;
+
+    let fields = split_top_level(fields_str.unwrap_or_default(), ';')
+        .into_iter()
+        .filter(|f| !f.is_empty())
+        .map(parse_fields)
+        .collect::<ScaffoldResult<Vec<FieldDefinition>>>()?;
+
+    Ok(EnumVariant {
+        name: name.to_case(Case::Pascal),
+        fields,
+    })
+}
+
+fn parse_struct(fields_str: &str) -> ScaffoldResult<FieldType> {
+    let (fields_str, struct_fields_str) = split_braces(fields_str)?;
     let sp: Vec<&str> = fields_str.split(":").collect();
 
+    let label = match sp.get(3) {
+        Some(label) if !label.is_empty() => label.to_string().to_case(Case::Pascal),
+        _ => Err(ScaffoldError::InvalidArguments(format!(
+            "the struct in field \"{}\" has no name, struct fields must be given as \"<FIELD_NAME>:Struct:<WIDGET>:<STRUCT_NAME>{{<FIELD>;<FIELD>...}}\"",
+            sp[0]
+        )))?,
+    };
+
+    let fields = split_top_level(struct_fields_str.unwrap_or_default(), ';')
+        .into_iter()
+        .filter(|f| !f.is_empty())
+        .map(parse_fields)
+        .collect::<ScaffoldResult<Vec<FieldDefinition>>>()?;
+
+    if fields.is_empty() {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "struct \"{}\" must have at least one field",
+            label
+        )));
+    }
+
+    Ok(FieldType::Struct { label, fields })
+}
+
+// Splits the fields given between braces at the end, as in "address:Struct:Fieldset:Address{street:String:TextField}"
+fn split_braces(s: &str) -> ScaffoldResult<(&str, Option<&str>)> {
+    match s.find('{') {
+        Some(i) if s.ends_with('}') => Ok((&s[..i], Some(&s[i + 1..s.len() - 1]))),
+        Some(_) => Err(ScaffoldError::InvalidArguments(format!(
+            "missing closing brace for the fields in \"{}\"",
+            s
+        ))),
+        None => Ok((s, None)),
+    }
+}
+
+// Splits by the given separator, leaving the fields of nested structs and enum variants and the types of maps together
+fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' | '<' => depth += 1,
+            '}' | '>' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+
+    parts
+}
+
+// Splits the constraints given between brackets at the end, as in "title:String:TextField[min_length=3]"
+fn split_constraints(s: &str) -> ScaffoldResult<(&str, Option<&str>)> {
+    let mut depth = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' | '<' => depth += 1,
+            '}' | '>' => depth -= 1,
+            '[' if depth == 0 => {
+                return match s.ends_with(']') {
+                    true => Ok((&s[..i], Some(&s[i + 1..s.len() - 1]))),
+                    false => Err(ScaffoldError::InvalidArguments(format!(
+                        "missing closing bracket for the constraints in \"{}\"",
+                        s
+                    ))),
+                };
+            }
+            _ => {}
+        }
+    }
+
+    Ok((s, None))
+}
+
+pub fn parse_fields(fields_str: &str) -> ScaffoldResult<FieldDefinition> {
+    let (fields_str, constraints_str) = split_constraints(fields_str)?;
+
+    // The fields of structs and enum variants are given between braces, and parsed together with their type
+    let head = match fields_str.find('{') {
+        Some(i) => &fields_str[..i],
+        None => fields_str,
+    };
+
+    let sp: Vec<&str> = head.split(":").collect();
+
     let field_name = sp[0].to_string();
 
     check_case(&field_name, "field_name", Case::Snake)?;
@@ -41,38 +165,32 @@ pub fn parse_fields(fields_str: &str) -> ScaffoldResult<FieldDefinition> {
 
     let vec_regex = Regex::new(r"Vec<(?P<a>(.)*)>\z").unwrap();
     let option_regex = Regex::new(r"Option<(?P<a>(.)*)>\z").unwrap();
-
-    let (field_type, cardinality) = if vec_regex.is_match(field_type_str.as_str()) {
-        let field_type = vec_regex.replace(field_type_str.as_str(), "${a}");
-
-        if field_type == "Enum" {
-            (parse_enum(fields_str)?, Cardinality::Vector)
-        } else {
-            (
-                FieldType::try_from(field_type.to_string())?,
-                Cardinality::Vector,
-            )
-        }
-    } else if option_regex.is_match(field_type_str.as_str()) {
-        let field_type = option_regex.replace(field_type_str.as_str(), "${a}");
-
-        if field_type == "Enum" {
-            (parse_enum(fields_str)?, Cardinality::Option)
-        } else {
-            (
-                FieldType::try_from(field_type.to_string())?,
-                Cardinality::Option,
-            )
+    // The key and the value of maps can also be separated by ";", since "--fields" is split by ","
+    let map_regex = Regex::new(r"(BTreeMap|HashMap)<(?P<k>[^;,]*)[;,](?P<a>(.)*)>\z").unwrap();
+
+    let (value_type_str, cardinality) = if let Some(captures) = vec_regex.captures(&field_type_str)
+    {
+        (captures["a"].to_string(), Cardinality::Vector)
+    } else if let Some(captures) = option_regex.captures(&field_type_str) {
+        (captures["a"].to_string(), Cardinality::Option)
+    } else if let Some(captures) = map_regex.captures(&field_type_str) {
+        let key = FieldType::try_from(captures["k"].trim().to_string())?;
+        if !key.can_be_map_key() {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "invalid key type \"{}\" for the map in field \"{}\": only String keys are supported",
+                key.to_string(),
+                field_name
+            )));
         }
+        (captures["a"].trim().to_string(), Cardinality::Map { key })
     } else {
-        if field_type_str == "Enum" {
-            (parse_enum(fields_str)?, Cardinality::Single)
-        } else {
-            (
-                FieldType::try_from(field_type_str.to_string())?,
-                Cardinality::Single,
-            )
-        }
+        (field_type_str.clone(), Cardinality::Single)
+    };
+
+    let field_type = match value_type_str.as_str() {
+        "Enum" => parse_enum(fields_str)?,
+        "Struct" => parse_struct(fields_str)?,
+        _ => FieldType::try_from(value_type_str)?,
     };
 
     let widget = if sp.len() > 2 {
@@ -104,61 +222,77 @@ pub fn parse_fields(fields_str: &str) -> ScaffoldResult<FieldDefinition> {
         _ => None,
     };
 
+    let constraints = match constraints_str {
+        Some(constraints_str) => {
+            FieldConstraints::parse(constraints_str, &field_name, &field_type, &cardinality)?
+        }
+        None => FieldConstraints::default(),
+    };
+
     Ok(FieldDefinition {
         field_name,
         field_type,
         widget,
         cardinality,
         linked_from,
+        constraints,
     })
 }
 
-pub fn choose_widget(
+/// The widgets of the template that can render the given field type
+pub fn widgets_that_can_render(
     field_type: &FieldType,
     field_types_templates: &FileTree,
-) -> ScaffoldResult<Option<String>> {
+) -> Vec<String> {
     let path = PathBuf::new().join(field_type.to_string());
 
     match dir_content(field_types_templates, &path) {
-        Err(_) => Ok(None),
-        Ok(folders) => {
-            let widgets_that_can_render_this_type: Vec<String> = folders
-                .into_iter()
-                .filter(|(_key, value)| value.dir_content().is_some())
-                .map(|(key, _value)| key)
-                .map(|s| s.to_str().unwrap().to_string())
-                .collect();
-
-            if widgets_that_can_render_this_type.len() == 0 {
-                return Ok(None);
-            }
-
-            let visible = Confirm::with_theme(&ColorfulTheme::default())
-                .with_prompt("Should this field be visible in the UI?")
-                .interact()?;
+        Err(_) => vec![],
+        Ok(folders) => folders
+            .into_iter()
+            .filter(|(_key, value)| value.dir_content().is_some())
+            .map(|(key, _value)| key)
+            .map(|s| s.to_str().unwrap().to_string())
+            .collect(),
+    }
+}
 
-            if !visible {
-                return Ok(None);
-            }
+pub fn choose_widget(
+    field_name: &str,
+    field_type: &FieldType,
+    field_types_templates: &FileTree,
+) -> ScaffoldResult<Option<String>> {
+    let widgets_that_can_render_this_type =
+        widgets_that_can_render(field_type, field_types_templates);
 
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt("Choose widget to render this field:")
-                .default(0)
-                .items(&widgets_that_can_render_this_type[..])
-                .interact()?;
+    // Without prompts the field is rendered with the first widget that can render it
+    if widgets_that_can_render_this_type.is_empty() || prompts_disabled() {
+        return Ok(widgets_that_can_render_this_type.into_iter().next());
+    }
 
-            let widget_name = widgets_that_can_render_this_type[selection].clone();
+    let visible = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Should the field \"{}\" be visible in the UI?",
+            field_name
+        ))
+        .interact()?;
 
-            Ok(Some(widget_name))
-        }
+    if !visible {
+        return Ok(None);
     }
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Choose widget to render field \"{}\":", field_name))
+        .default(0)
+        .items(&widgets_that_can_render_this_type[..])
+        .interact()?;
+
+    let widget_name = widgets_that_can_render_this_type[selection].clone();
+
+    Ok(Some(widget_name))
 }
 
-pub fn choose_field(
-    entry_type_name: &String,
-    zome_file_tree: &ZomeFileTree,
-    field_types_templates: &FileTree,
-) -> ScaffoldResult<FieldDefinition> {
+fn choose_field_type(field_types_templates: &FileTree) -> ScaffoldResult<(Cardinality, FieldType)> {
     let field_types = FieldType::list();
     let field_type_names: Vec<String> = field_types
         .clone()
@@ -172,6 +306,7 @@ pub fn choose_field(
         .items(&field_type_names[..])
         .item("Option of...")
         .item("Vector of...")
+        .item("Map of...")
         .interact()?;
 
     // If user selected vector
@@ -191,6 +326,19 @@ pub fn choose_field(
             .interact()?;
 
         (Cardinality::Vector, field_types[selection].clone())
+    } else if selection == field_type_names.len() + 2 {
+        let selection = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt("Map from String keys to which field type?")
+            .default(0)
+            .items(&field_type_names[..])
+            .interact()?;
+
+        (
+            Cardinality::Map {
+                key: FieldType::String,
+            },
+            field_types[selection].clone(),
+        )
     } else {
         (Cardinality::Single, field_types[selection].clone())
     };
@@ -198,16 +346,32 @@ pub fn choose_field(
     if let FieldType::Enum { .. } = field_type {
         let label = input_with_case(&String::from("Enter the name of the enum:"), Case::Pascal)?;
 
-        let mut variants: Vec<String> = Vec::new();
+        let mut variants: Vec<EnumVariant> = Vec::new();
 
         let mut another_field = true;
 
         while another_field {
-            let variant = input_with_case(
+            let name = input_with_case(
                 &String::from("Enter the name of the next variant:"),
                 Case::Pascal,
             )?;
-            variants.push(variant);
+
+            let mut fields: Vec<FieldDefinition> = Vec::new();
+            let mut another_variant_field = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Does the variant {} carry any fields?", name))
+                .default(false)
+                .report(false)
+                .interact()?;
+
+            while another_variant_field {
+                fields.push(choose_struct_field(field_types_templates)?);
+                another_variant_field = Confirm::with_theme(&ColorfulTheme::default())
+                    .with_prompt(format!("Add another field to the variant {}?", name))
+                    .report(false)
+                    .interact()?;
+            }
+
+            variants.push(EnumVariant { name, fields });
             another_field = Confirm::with_theme(&ColorfulTheme::default())
                 .with_prompt("Add another variant to the enum?")
                 .report(false)
@@ -217,6 +381,56 @@ pub fn choose_field(
         field_type = FieldType::Enum { label, variants };
     }
 
+    if let FieldType::Struct { .. } = field_type {
+        let label = input_with_case(&String::from("Enter the name of the struct:"), Case::Pascal)?;
+        let mut fields: Vec<FieldDefinition> = Vec::new();
+
+        let mut another_field = true;
+
+        while another_field {
+            fields.push(choose_struct_field(field_types_templates)?);
+            another_field = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Add another field to the struct?")
+                .report(false)
+                .interact()?;
+        }
+
+        field_type = FieldType::Struct { label, fields };
+    }
+
+    Ok((cardinality, field_type))
+}
+
+// Fields of structs and enum variants can't be linked from, so this only asks for their type, name and widget
+fn choose_struct_field(field_types_templates: &FileTree) -> ScaffoldResult<FieldDefinition> {
+    let (cardinality, field_type) = choose_field_type(field_types_templates)?;
+
+    let field_name: String = input_with_case(&String::from("Field name:"), Case::Snake)?;
+
+    let widget = match (&cardinality, field_type.can_be_rendered()) {
+        (Cardinality::Vector | Cardinality::Map { .. }, _) | (_, false) => None,
+        _ => choose_widget(&field_name, &field_type, field_types_templates)?,
+    };
+
+    Ok(FieldDefinition {
+        widget,
+        field_name,
+        cardinality,
+        field_type,
+        linked_from: None,
+        constraints: FieldConstraints::default(),
+    })
+}
+
+pub fn choose_field(
+    entry_type_name: &String,
+    zome_file_tree: &ZomeFileTree,
+    field_types_templates: &FileTree,
+) -> ScaffoldResult<FieldDefinition> {
+    check_can_prompt("field", "<FIELD>")?;
+
+    let (cardinality, field_type) = choose_field_type(field_types_templates)?;
+
     let maybe_linked_from = match &field_type {
         FieldType::AgentPubKey => {
             let link_from = Confirm::with_theme(&ColorfulTheme::default())
@@ -254,7 +468,9 @@ pub fn choose_field(
                         .map(|r| r.entry_type)
                         .collect();
 
-                    if let Cardinality::Option | Cardinality::Vector = cardinality {
+                    if let Cardinality::Option | Cardinality::Vector | Cardinality::Map { .. } =
+                        cardinality
+                    {
                         all_options.push(format!(
                             "{} (itself)",
                             entry_type_name.to_case(Case::Pascal)
@@ -296,7 +512,12 @@ pub fn choose_field(
     let field_name: String =
         input_with_case_and_initial_text(&String::from("Field name:"), Case::Snake, &initial_text)?;
 
-    let widget = choose_widget(&field_type, field_types_templates)?;
+    let widget = match field_type.can_be_rendered() {
+        true => choose_widget(&field_name, &field_type, field_types_templates)?,
+        false => None,
+    };
+
+    let constraints = choose_constraints(&field_name, &field_type, &cardinality)?;
 
     Ok(FieldDefinition {
         widget,
@@ -304,14 +525,120 @@ pub fn choose_field(
         cardinality,
         field_type,
         linked_from: maybe_linked_from,
+        constraints,
     })
 }
 
+fn choose_constraints(
+    field_name: &str,
+    field_type: &FieldType,
+    cardinality: &Cardinality,
+) -> ScaffoldResult<FieldConstraints> {
+    let lengths_apply = lengths_apply(field_type, cardinality);
+    let is_string = matches!(field_type, FieldType::String);
+    let choose_immutable = || {
+        Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(
+                "Should the field keep the value it was created with when its entry is updated?",
+            )
+            .default(false)
+            .interact()
+    };
+
+    let mut constraints = FieldConstraints::default();
+
+    // Maps and the field types that have no value constraints can only be immutable
+    let only_immutable = matches!(cardinality, Cardinality::Map { .. })
+        || (!lengths_apply && !is_string && !field_type.is_number());
+    if only_immutable {
+        constraints.immutable = choose_immutable()?;
+        return Ok(constraints);
+    }
+
+    let add_constraints = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Add validation constraints to this field?")
+        .default(false)
+        .interact()?;
+
+    if !add_constraints {
+        return Ok(constraints);
+    }
+
+    constraints.immutable = choose_immutable()?;
+
+    if lengths_apply {
+        let unit = match cardinality {
+            Cardinality::Vector => "elements",
+            _ => "characters",
+        };
+
+        constraints.non_empty = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Should the field be required to not be empty?")
+            .default(false)
+            .interact()?;
+        constraints.min_length = input_constraint(
+            &format!("Minimum number of {} (leave empty for no minimum):", unit),
+            |value| parse_length(value, field_name),
+        )?;
+        constraints.max_length = input_constraint(
+            &format!("Maximum number of {} (leave empty for no maximum):", unit),
+            |value| parse_length(value, field_name),
+        )?;
+    }
+
+    if field_type.is_number() {
+        constraints.min =
+            input_constraint("Minimum value (leave empty for no minimum):", |value| {
+                number_literal(value, field_name, field_type)
+            })?;
+        constraints.max =
+            input_constraint("Maximum value (leave empty for no maximum):", |value| {
+                number_literal(value, field_name, field_type)
+            })?;
+    }
+
+    if is_string {
+        constraints.pattern = input_constraint(
+            "Regular expression that the whole value must match (leave empty for none):",
+            |value| {
+                Regex::new(value).map_err(|e| ScaffoldError::InvalidArguments(e.to_string()))?;
+                Ok(value.to_string())
+            },
+        )?;
+    }
+
+    constraints.check(field_name, field_type, cardinality)?;
+
+    Ok(constraints)
+}
+
+// Asks for the value of an optional constraint, until it's either empty or valid
+fn input_constraint<T>(
+    prompt: &str,
+    parse: impl Fn(&str) -> ScaffoldResult<T>,
+) -> ScaffoldResult<Option<T>> {
+    let value: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .validate_with(|value: &String| match value.is_empty() {
+            true => Ok(()),
+            false => parse(value).map(|_| ()).map_err(|e| e.to_string()),
+        })
+        .interact_text()?;
+
+    match value.is_empty() {
+        true => Ok(None),
+        false => parse(&value).map(Some),
+    }
+}
+
 pub fn choose_fields(
     entry_type_name: &String,
     zome_file_tree: &ZomeFileTree,
     field_types_templates: &FileTree,
 ) -> ScaffoldResult<Vec<FieldDefinition>> {
+    check_can_prompt("fields", "--fields <FIELDS>")?;
+
     let mut finished = false;
     let mut fields: Vec<FieldDefinition> = Vec::new();
     println!("\nWhich fields should the entry contain?\n");
@@ -339,3 +666,44 @@ pub fn choose_fields(
 
     Ok(fields)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fields_with_constraints() {
+        let field = parse_fields("title:String:TextField[min_length=3;pattern=[a-z]+]").unwrap();
+
+        assert_eq!(field.field_name, "title");
+        assert!(matches!(field.field_type, FieldType::String));
+        assert!(matches!(field.cardinality, Cardinality::Single));
+        assert_eq!(field.widget, Some(String::from("TextField")));
+        assert_eq!(field.constraints.min_length, Some(3));
+        assert_eq!(field.constraints.pattern, Some(String::from("[a-z]+")));
+    }
+
+    #[test]
+    fn test_parse_fields_with_cardinality() {
+        let field = parse_fields("tags:Vec<String>:TextField[non_empty]").unwrap();
+        assert!(matches!(field.cardinality, Cardinality::Vector));
+        assert!(field.constraints.non_empty);
+
+        let field = parse_fields("scores:BTreeMap<String;u32>").unwrap();
+        assert!(matches!(field.field_type, FieldType::U32));
+        assert!(matches!(
+            field.cardinality,
+            Cardinality::Map {
+                key: FieldType::String
+            }
+        ));
+        assert_eq!(field.widget, None);
+    }
+
+    #[test]
+    fn test_parse_invalid_fields() {
+        assert!(parse_fields("title:String:TextField[min_length=3").is_err());
+        assert!(parse_fields("Title:String:TextField").is_err());
+        assert!(parse_fields("title:String:TextField[min=3]").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use build_fs_tree::dir;
+use convert_case::{Case, Casing};
+use quote::ToTokens;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::FileTree,
+    scaffold::{
+        field::{find_entry_def_settings, find_struct, parse_field_type},
+        remove::rust_files,
+        zome::{utils::get_coordinator_zomes_for_integrity, zome_crate_path, ZomeFileTree},
+    },
+    utils::find_fn_in_files,
+};
+
+use super::{
+    constraints::FieldConstraints,
+    definitions::{Cardinality, EntryDefSettings, FieldDefinition, FieldType},
+    fields::choose_widget,
+    integrity::get_all_entry_types,
+};
+
+/// A hand-written struct, given as "<PATH>::<STRUCT_NAME>"
+#[derive(Debug, Clone)]
+pub struct StructReference {
+    pub path: PathBuf,
+    pub struct_name: String,
+}
+
+pub fn parse_struct_reference(struct_reference_str: &str) -> Result<StructReference, String> {
+    match struct_reference_str.rsplit_once("::") {
+        Some((path, struct_name)) if !path.is_empty() && !struct_name.is_empty() => {
+            Ok(StructReference {
+                path: PathBuf::from(path),
+                struct_name: struct_name.to_string(),
+            })
+        }
+        _ => Err(String::from(
+            "The struct must be given as \"<PATH>::<STRUCT_NAME>\", eg. \"src/post.rs::Post\"",
+        )),
+    }
+}
+
+/// The entry type read from a hand-written struct
+pub struct StructEntryType {
+    pub fields: Vec<FieldDefinition>,
+    /// The settings of the entry type, if the struct already is an entry type of the integrity zome
+    /// In that case its definition and its validation are kept as they are
+    pub existing_settings: Option<EntryDefSettings>,
+}
+
+/// Reads the fields of the given struct, mapping their types back to the field types and cardinalities
+/// that would have scaffolded it, and chooses the widgets that render them
+pub fn read_struct_entry_type(
+    zome_file_tree: &ZomeFileTree,
+    template_file_tree: &FileTree,
+    entry_type_name: &str,
+    struct_reference: &StructReference,
+) -> ScaffoldResult<StructEntryType> {
+    let StructReference { path, struct_name } = struct_reference;
+    let pascal_entry_type = entry_type_name.to_case(Case::Pascal);
+    let snake_entry_type = entry_type_name.to_case(Case::Snake);
+    let dna_file_tree = &zome_file_tree.dna_file_tree;
+    let integrity_zome_name = zome_file_tree.zome_manifest.name.0.to_string();
+
+    let file_content = std::fs::read_to_string(path)?;
+    let file = syn::parse_str::<syn::File>(&file_content)
+        .map_err(|e| ScaffoldError::MalformedFile(path.clone(), e.to_string()))?;
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let mut files = rust_files(dna_file_tree.file_tree_ref(), &crate_src_path)?;
+
+    let entry_type_exists = get_all_entry_types(zome_file_tree)?
+        .unwrap_or_default()
+        .into_iter()
+        .any(|et| et.entry_type.eq(&pascal_entry_type));
+
+    let existing_settings = match find_struct(&files, struct_name) {
+        None => None,
+        Some(_) if entry_type_exists && struct_name.eq(&pascal_entry_type) => {
+            for coordinator_zome in
+                get_coordinator_zomes_for_integrity(&dna_file_tree.dna_manifest, &integrity_zome_name)
+            {
+                let coordinator_src_path =
+                    zome_crate_path(dna_file_tree, &coordinator_zome)?.join("src");
+                let coordinator_files =
+                    rust_files(dna_file_tree.file_tree_ref(), &coordinator_src_path)?;
+
+                if find_fn_in_files(&coordinator_files, &format!("create_{}", snake_entry_type)).is_some() {
+                    return Err(ScaffoldError::InvalidArguments(format!(
+                        "entry type \"{}\" already has its zome functions in the coordinator zome \"{}\"",
+                        entry_type_name, coordinator_zome.name.0
+                    )));
+                }
+            }
+
+            Some(find_entry_def_settings(&files, &pascal_entry_type))
+        }
+        Some(_) => {
+            return Err(ScaffoldError::InvalidArguments(format!(
+                "struct \"{}\" is already defined in the integrity zome \"{}\", but it isn't the struct of entry type \"{}\"",
+                struct_name, integrity_zome_name, entry_type_name
+            )))
+        }
+    };
+
+    // Enums and structs used by the fields can be defined either next to the struct or in the integrity zome
+    files.insert(path.clone(), file);
+
+    let item_struct = files
+        .get(path)
+        .and_then(|file| {
+            file.items.iter().find_map(|item| match item {
+                syn::Item::Struct(item_struct) if item_struct.ident.eq(struct_name) => {
+                    Some(item_struct)
+                }
+                _ => None,
+            })
+        })
+        .ok_or(ScaffoldError::MalformedFile(
+            path.clone(),
+            format!("struct \"{}\" was not found", struct_name),
+        ))?;
+
+    let mut fields = item_struct
+        .fields
+        .iter()
+        .map(|field| {
+            let field_name = field
+                .ident
+                .as_ref()
+                .ok_or(ScaffoldError::InvalidArguments(format!(
+                    "struct \"{}\" must have named fields",
+                    struct_name
+                )))?
+                .to_string();
+            let (field_type, cardinality) = parse_field_type(&field.ty, &files).ok_or(
+                ScaffoldError::InvalidArguments(format!(
+                    "the type \"{}\" of field \"{}\" can't be scaffolded",
+                    field.ty.to_token_stream().to_string().replace(' ', ""),
+                    field_name
+                )),
+            )?;
+
+            Ok(FieldDefinition {
+                field_name,
+                field_type,
+                widget: None,
+                cardinality,
+                linked_from: None,
+                constraints: FieldConstraints::default(),
+            })
+        })
+        .collect::<ScaffoldResult<Vec<FieldDefinition>>>()?;
+
+    let v: Vec<OsString> = PathBuf::from("field-types")
+        .iter()
+        .map(|s| s.to_os_string())
+        .collect();
+    let empty_dir = dir! {};
+    choose_widgets(
+        &mut fields,
+        template_file_tree.path(&mut v.iter()).unwrap_or(&empty_dir),
+        false,
+    )?;
+
+    Ok(StructEntryType {
+        fields,
+        existing_settings,
+    })
+}
+
+/// Chooses the widgets of the fields, and of the fields nested in their structs and enum variants
+/// If prompts are disabled, the first widget that can render each field is used
+fn choose_widgets(
+    fields: &mut Vec<FieldDefinition>,
+    field_types_templates: &FileTree,
+    nested: bool,
+) -> ScaffoldResult<()> {
+    for field_def in fields {
+        match &mut field_def.field_type {
+            FieldType::Struct { fields, .. } => {
+                choose_widgets(fields, field_types_templates, true)?;
+            }
+            FieldType::Enum { variants, .. } => {
+                for variant in variants {
+                    choose_widgets(&mut variant.fields, field_types_templates, true)?;
+                }
+            }
+            _ => {}
+        }
+
+        // The fields of structs and enum variants can't be rendered if they are vectors or maps
+        let nested_collection = nested
+            && matches!(
+                field_def.cardinality,
+                Cardinality::Vector | Cardinality::Map { .. }
+            );
+
+        field_def.widget = match nested_collection || !field_def.field_type.can_be_rendered() {
+            true => None,
+            false => choose_widget(
+                &field_def.field_name,
+                &field_def.field_type,
+                field_types_templates,
+            )?,
+        };
+    }
+
+    Ok(())
+}
@@ -1,12 +1,15 @@
 use convert_case::{Case, Casing};
 use itertools::Itertools;
-use proc_macro2::TokenStream;
+use proc_macro2::{Literal, TokenStream};
 use quote::{format_ident, quote};
 use std::{ffi::OsString, path::PathBuf};
 
 use crate::error::{ScaffoldError, ScaffoldResult};
 use crate::file_tree::insert_file;
+use crate::scaffold::app::cargo::{add_workspace_external_dependency, get_workspace_cargo_toml};
 use crate::scaffold::dna::DnaFileTree;
+use crate::scaffold::field::find_entry_def_settings;
+use crate::scaffold::remove::rust_files;
 use crate::scaffold::zome::coordinator::find_extern_function_in_zomes;
 use crate::scaffold::zome::utils::get_coordinator_zomes_for_integrity;
 use crate::utils::unparse;
@@ -16,7 +19,7 @@ use crate::{
 };
 
 use super::crud::Crud;
-use super::definitions::{Cardinality, EntryDefinition, EntryTypeReference, FieldDefinition, Referenceable};
+use super::definitions::{Cardinality, EntryDefinition, EntryTypeReference, FieldDefinition, FieldType, Referenceable};
 
 pub fn render_entry_definition_struct(entry_def: &EntryDefinition) -> ScaffoldResult<TokenStream> {
     let name: syn::Expr = syn::parse_str(entry_def.name.to_case(Case::Pascal).as_str())?;
@@ -55,15 +58,46 @@ pub fn render_entry_definition_file(
         .filter_map(|field_def| field_def.field_type.rust_type_definition())
         .collect();
 
+    let entry_arg = format_ident!("{}", entry_def.name.to_case(Case::Snake));
+    let constraint_checks: Vec<TokenStream> = entry_def
+        .fields
+        .iter()
+        .map(|field_def| render_constraint_checks(field_def, &entry_arg))
+        .collect::<ScaffoldResult<Vec<Vec<TokenStream>>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
     let validate_update_fn =
         format_ident!("validate_update_{}", entry_def.name.to_case(Case::Snake));
-    let new_entry_arg = format_ident!("_{}", entry_def.name.to_case(Case::Snake));
-    let original_entry_arg = format_ident!("_original_{}", entry_def.name.to_case(Case::Snake));
+    let original_entry_arg = format_ident!("original_{}", entry_def.name.to_case(Case::Snake));
+    let immutable_checks = render_immutable_checks(entry_def, &entry_arg, &original_entry_arg);
+    let new_entry_arg =
+        match crud.update && !(constraint_checks.is_empty() && immutable_checks.is_empty()) {
+            true => entry_arg.clone(),
+            false => format_ident!("_{}", entry_def.name.to_case(Case::Snake)),
+        };
+    let original_entry_arg = match crud.update && !immutable_checks.is_empty() {
+        true => original_entry_arg,
+        false => format_ident!("_{}", original_entry_arg),
+    };
+
+    // Only the author of the original entry can update or delete it
+    let (update_action_arg, update_original_action_arg, update_author_check) =
+        author_check(crud.update && crud.author_only, entry_def, "update");
+    let (delete_action_arg, delete_original_action_arg, delete_author_check) =
+        author_check(crud.delete && crud.author_only, entry_def, "delete");
 
     let updated_invalid_reason = format!("{} cannot be updated", plural_name_title);
 
     let validate_update_result: TokenStream = match crud.update {
         true => quote! {
+            #update_author_check
+
+            #(#immutable_checks)*
+
+            #(#constraint_checks)*
+
             /// TODO: add the appropriate validation rules
             Ok(ValidateCallbackResult::Valid)
         },
@@ -73,9 +107,9 @@ pub fn render_entry_definition_file(
     };
     let validate_update: TokenStream = quote! {
         pub fn #validate_update_fn(
-            _action: Update, 
+            #update_action_arg: Update, 
             #new_entry_arg: #name_pascal, 
-            _original_action: EntryCreationAction, 
+            #update_original_action_arg: EntryCreationAction, 
             #original_entry_arg: #name_pascal
         ) -> ExternResult<ValidateCallbackResult> {
             #validate_update_result
@@ -90,6 +124,8 @@ pub fn render_entry_definition_file(
 
     let validate_delete_result: TokenStream = match crud.delete {
         true => quote! {
+            #delete_author_check
+
             /// TODO: add the appropriate validation rules
             Ok(ValidateCallbackResult::Valid)
         },
@@ -99,8 +135,8 @@ pub fn render_entry_definition_file(
     };
     let validate_delete: TokenStream = quote! {
         pub fn #validate_delete_fn(
-            _action: Delete, 
-            _original_action: EntryCreationAction, 
+            #delete_action_arg: Delete, 
+            #delete_original_action_arg: EntryCreationAction, 
             #deleted_post_arg: #name_pascal
         ) -> ExternResult<ValidateCallbackResult> {
             #validate_delete_result
@@ -119,9 +155,9 @@ pub fn render_entry_definition_file(
         })
         .collect();
 
-    let create_new_entry_arg = match deps.len() {
-        0 => format_ident!("_{}", entry_def.name.to_case(Case::Snake)),
-        _ => format_ident!("{}", entry_def.name.to_case(Case::Snake)),
+    let create_new_entry_arg = match deps.is_empty() && constraint_checks.is_empty() {
+        true => format_ident!("_{}", entry_def.name.to_case(Case::Snake)),
+        false => entry_arg.clone(),
     };
     let deps_validation: Vec<TokenStream> = deps
         .into_iter()
@@ -156,6 +192,15 @@ pub fn render_entry_definition_file(
                             .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Dependant action must be accompanied by an entry"))))?;
                     }
                 },
+                (Cardinality::Map { .. }, false) => quote! {
+                    for action_hash in #create_new_entry_arg.#field_name.values().cloned() {
+                        let record = must_get_valid_record(action_hash)?;
+
+                        let #dependant_entry_type_snake: crate::#dependant_entry_type_pascal = record.entry().to_app_option()
+                            .map_err(|e| wasm_error!(e))?
+                            .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Dependant action must be accompanied by an entry"))))?;
+                    }
+                },
                 (Cardinality::Single,true) => quote! {
                     let entry = must_get_entry(#create_new_entry_arg.#field_name.clone())?;
 
@@ -172,6 +217,13 @@ pub fn render_entry_definition_file(
                     for entry_hash in #create_new_entry_arg.#field_name.clone() {
                         let entry = must_get_entry(entry_hash)?;
 
+                        let #dependant_entry_type_snake = crate::#dependant_entry_type_pascal::try_from(entry)?;
+                    }
+                },
+                (Cardinality::Map { .. }, true) => quote! {
+                    for entry_hash in #create_new_entry_arg.#field_name.values().cloned() {
+                        let entry = must_get_entry(entry_hash)?;
+
                         let #dependant_entry_type_snake = crate::#dependant_entry_type_pascal::try_from(entry)?;
                     }
                 },
@@ -179,8 +231,15 @@ pub fn render_entry_definition_file(
         })
         .collect();
 
+    // The prelude of hdi doesn't include BTreeMap
+    let map_import = match entry_def.fields.iter().any(|f| f.uses_map()) {
+        true => quote! { use std::collections::BTreeMap; },
+        false => quote! {},
+    };
+
     let token_stream = quote! {
       use hdi::prelude::*;
+      #map_import
 
       #(#type_definitions)*
 
@@ -194,6 +253,8 @@ pub fn render_entry_definition_file(
       ) -> ExternResult<ValidateCallbackResult> {
           #(#deps_validation)*
 
+          #(#constraint_checks)*
+
           /// TODO: add the appropriate validation rules
           Ok(ValidateCallbackResult::Valid)
       }
@@ -208,6 +269,227 @@ pub fn render_entry_definition_file(
     Ok(file)
 }
 
+/// The names of the action arguments of a validation function for updates or deletes, together with the check
+/// that rejects the actions whose author is not the author of the original entry, if it applies
+fn author_check(
+    applies: bool,
+    entry_def: &EntryDefinition,
+    operation: &str,
+) -> (syn::Ident, syn::Ident, TokenStream) {
+    if !applies {
+        return (
+            format_ident!("_action"),
+            format_ident!("_original_action"),
+            quote! {},
+        );
+    }
+
+    let invalid_reason = format!(
+        "Only the author of a {} can {} it",
+        entry_def.name.to_case(Case::Lower),
+        operation
+    );
+
+    (
+        format_ident!("action"),
+        format_ident!("original_action"),
+        quote! {
+            if action.author.ne(original_action.author()) {
+                return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_reason)));
+            }
+        },
+    )
+}
+
+/// Renders the statements that return an invalid result if an update changes the value of an immutable field
+///
+/// The original entry is the one that validate() got with must_get_valid_record, so the checks only need to compare with it
+fn render_immutable_checks(
+    entry_def: &EntryDefinition,
+    entry_arg: &syn::Ident,
+    original_entry_arg: &syn::Ident,
+) -> Vec<TokenStream> {
+    entry_def
+        .fields
+        .iter()
+        .filter(|field_def| field_def.constraints.immutable)
+        .map(|field_def| {
+            let field_name = format_ident!("{}", field_def.field_name);
+            let invalid_reason = format!(
+                "{} can't be updated",
+                field_def.field_name.to_case(Case::Title)
+            );
+
+            quote! {
+                if #entry_arg.#field_name.ne(&#original_entry_arg.#field_name) {
+                    return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_reason)));
+                }
+            }
+        })
+        .collect()
+}
+
+/// Renders the statements that return an invalid result if the value of the given field doesn't satisfy its constraints
+fn render_constraint_checks(
+    field_def: &FieldDefinition,
+    entry_arg: &syn::Ident,
+) -> ScaffoldResult<Vec<TokenStream>> {
+    let constraints = &field_def.constraints;
+    let field_name = format_ident!("{}", field_def.field_name);
+    let field_title = field_def.field_name.to_case(Case::Title);
+    let is_string = matches!(field_def.field_type, FieldType::String);
+
+    let invalid = |reason: String| {
+        quote! {
+            return Ok(ValidateCallbackResult::Invalid(String::from(#reason)));
+        }
+    };
+
+    // Checks for a single value, given as an expression of its type or, if by_ref is true, as a reference to it
+    let value_checks = |value: TokenStream, by_ref: bool, subject: &str, lengths: bool| {
+        let (number, string_ref) = match by_ref {
+            true => (quote! { *#value }, value.clone()),
+            false => (value.clone(), quote! { &#value }),
+        };
+        let mut checks: Vec<TokenStream> = vec![];
+
+        if lengths && is_string {
+            if constraints.non_empty {
+                let reason = invalid(format!("{} can't be empty", subject));
+                checks.push(quote! { if #value.is_empty() { #reason } });
+            }
+            if let Some(min_length) = constraints.min_length {
+                let reason = invalid(format!(
+                    "{} must have at least {} characters",
+                    subject, min_length
+                ));
+                let min_length = Literal::usize_unsuffixed(min_length);
+                checks.push(quote! { if #value.chars().count() < #min_length { #reason } });
+            }
+            if let Some(max_length) = constraints.max_length {
+                let reason = invalid(format!(
+                    "{} must have at most {} characters",
+                    subject, max_length
+                ));
+                let max_length = Literal::usize_unsuffixed(max_length);
+                checks.push(quote! { if #value.chars().count() > #max_length { #reason } });
+            }
+        }
+        if let Some(min) = &constraints.min {
+            let reason = invalid(format!("{} must be at least {}", subject, min));
+            let min: syn::Expr = syn::parse_str(min)?;
+            checks.push(quote! { if #number < #min { #reason } });
+        }
+        if let Some(max) = &constraints.max {
+            let reason = invalid(format!("{} must be at most {}", subject, max));
+            let max: syn::Expr = syn::parse_str(max)?;
+            checks.push(quote! { if #number > #max { #reason } });
+        }
+        if let Some(pattern) = &constraints.pattern {
+            let reason = invalid(format!("{} must match the pattern {}", subject, pattern));
+            // Anchored so that the whole value has to match, like the pattern attribute of HTML inputs
+            let anchored_pattern = format!("^(?:{})$", pattern);
+            checks.push(quote! {
+                if !regex::Regex::new(#anchored_pattern)
+                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+                    .is_match(#string_ref) {
+                    #reason
+                }
+            });
+        }
+
+        ScaffoldResult::Ok(checks)
+    };
+
+    match field_def.cardinality {
+        Cardinality::Single => value_checks(quote! { #entry_arg.#field_name }, false, &field_title, true),
+        Cardinality::Option => {
+            let checks = value_checks(quote! { #field_name }, true, &field_title, true)?;
+            match checks.is_empty() {
+                true => Ok(vec![]),
+                false => Ok(vec![quote! {
+                    if let Some(#field_name) = &#entry_arg.#field_name {
+                        #(#checks)*
+                    }
+                }]),
+            }
+        }
+        Cardinality::Vector => {
+            let mut checks: Vec<TokenStream> = vec![];
+            if constraints.non_empty {
+                let reason = invalid(format!("{} can't be empty", field_title));
+                checks.push(quote! { if #entry_arg.#field_name.is_empty() { #reason } });
+            }
+            if let Some(min_length) = constraints.min_length {
+                let reason = invalid(format!("{} must have at least {} elements", field_title, min_length));
+                let min_length = Literal::usize_unsuffixed(min_length);
+                checks.push(quote! { if #entry_arg.#field_name.len() < #min_length { #reason } });
+            }
+            if let Some(max_length) = constraints.max_length {
+                let reason = invalid(format!("{} must have at most {} elements", field_title, max_length));
+                let max_length = Literal::usize_unsuffixed(max_length);
+                checks.push(quote! { if #entry_arg.#field_name.len() > #max_length { #reason } });
+            }
+
+            let element = format_ident!("{}", pluralizer::pluralize(&field_def.field_name, 1, false));
+            let element_checks = value_checks(
+                quote! { #element },
+                true,
+                &format!("Every element of {}", field_title),
+                false,
+            )?;
+            if !element_checks.is_empty() {
+                checks.push(quote! {
+                    for #element in #entry_arg.#field_name.iter() {
+                        #(#element_checks)*
+                    }
+                });
+            }
+
+            Ok(checks)
+        }
+        Cardinality::Map { .. } => Ok(vec![]),
+    }
+}
+
+/// Adds the regex crate to the dependencies of the integrity zome, which the validation of patterns needs
+pub fn add_regex_dependency(zome_file_tree: ZomeFileTree) -> ScaffoldResult<ZomeFileTree> {
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+    let zome_crate_path = zome_file_tree.zome_crate_path.clone();
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    let workspace_cargo_toml = get_workspace_cargo_toml(&file_tree)?;
+    if workspace_cargo_toml
+        .get("workspace")
+        .and_then(|workspace| workspace.get("dependencies"))
+        .and_then(|dependencies| dependencies.get("regex"))
+        .is_none()
+    {
+        file_tree =
+            add_workspace_external_dependency(file_tree, &"regex".to_string(), &"1".to_string())?;
+    }
+
+    map_file(&mut file_tree, &zome_crate_path.join("Cargo.toml"), |cargo_toml| {
+        match cargo_toml.lines().any(|line| line.starts_with("regex ")) {
+            true => cargo_toml,
+            false => cargo_toml.replacen(
+                "[dependencies]\n",
+                "[dependencies]\nregex = { workspace = true }\n",
+                1,
+            ),
+        }
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+
+    Ok(ZomeFileTree {
+        dna_file_tree,
+        zome_manifest,
+        zome_crate_path,
+    })
+}
+
 fn is_create_entry(pat: &syn::Pat) -> bool {
     if let syn::Pat::Struct(pat_struct) = pat {
         if let Some(ps) = pat_struct.path.segments.last() {
@@ -396,8 +678,16 @@ pub use {}::*;
 
                                 found = true;
                                 let new_variant = syn::parse_str::<syn::Variant>(
-                                    format!("{}({})", pascal_entry_def_name, pascal_entry_def_name)
-                                        .as_str(),
+                                    format!(
+                                        "{} {}({})",
+                                        entry_def
+                                            .settings
+                                            .entry_def_attribute()
+                                            .unwrap_or_default(),
+                                        pascal_entry_def_name,
+                                        pascal_entry_def_name
+                                    )
+                                    .as_str(),
                                 )
                                 .unwrap();
                                 item_enum.variants.push(new_variant);
@@ -430,7 +720,10 @@ pub use {}::*;
     let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
     let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
 
-    Ok(zome_file_tree)
+    match entry_def.fields.iter().any(|f| f.constraints.pattern.is_some()) {
+        true => add_regex_dependency(zome_file_tree),
+        false => Ok(zome_file_tree),
+    }
 }
 
 pub fn get_all_entry_types(
@@ -517,6 +810,29 @@ pub fn get_all_entry_types(
     }
 }
 
+/// Returns an error if the given entry type is private, since other agents can't get the entries that a link points to
+/// if they are only kept in the source chain of their author
+pub fn check_entry_type_is_public(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: &str,
+    use_case: &str,
+) -> ScaffoldResult<()> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let integrity_files = rust_files(
+        zome_file_tree.dna_file_tree.file_tree_ref(),
+        &crate_src_path,
+    )?;
+    let settings = find_entry_def_settings(&integrity_files, &entry_type.to_case(Case::Pascal));
+
+    match settings.is_private() {
+        true => Err(ScaffoldError::InvalidArguments(format!(
+            "entry type \"{}\" is private, so it can't be {}: other agents can't get its entries",
+            entry_type, use_case
+        ))),
+        false => Ok(()),
+    }
+}
+
 fn add_entry_type_to_validation_arms(
     item: &mut syn::Item,
     entry_def: &EntryDefinition,
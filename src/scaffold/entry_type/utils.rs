@@ -5,7 +5,7 @@ use crate::{
     error::{ScaffoldError, ScaffoldResult},
     reserved_words::check_for_reserved_words,
     scaffold::zome::ZomeFileTree,
-    utils::input_with_case,
+    utils::{check_can_prompt, input_with_case},
 };
 
 use super::{
@@ -13,7 +13,13 @@ use super::{
     integrity::get_all_entry_types,
 };
 
-pub fn choose_reference_entry_hash(prompt: &String, recommended: bool) -> ScaffoldResult<bool> {
+pub fn choose_reference_entry_hash(
+    prompt: &String,
+    supplied_by: &str,
+    recommended: bool,
+) -> ScaffoldResult<bool> {
+    check_can_prompt(prompt, supplied_by)?;
+
     match recommended {
         true => {
             let selection = Select::with_theme(&ColorfulTheme::default())
@@ -47,8 +53,11 @@ pub fn choose_reference_entry_hash(prompt: &String, recommended: bool) -> Scaffo
 fn inner_choose_referenceable(
     all_entries: &Vec<EntryTypeReference>,
     prompt: &String,
+    supplied_by: &str,
     optional: bool,
 ) -> ScaffoldResult<Option<Referenceable>> {
+    check_can_prompt(prompt, supplied_by)?;
+
     let mut all_options: Vec<String> = all_entries
         .clone()
         .into_iter()
@@ -80,6 +89,7 @@ fn inner_choose_referenceable(
             entry_type: all_options[selection].clone(),
             reference_entry_hash: choose_reference_entry_hash(
                 &String::from("Reference this entry type with its entry hash or its action hash?"),
+                supplied_by,
                 all_entries[selection].reference_entry_hash,
             )?,
         })))
@@ -89,8 +99,9 @@ fn inner_choose_referenceable(
 pub fn choose_referenceable(
     all_entries: &Vec<EntryTypeReference>,
     prompt: &String,
+    supplied_by: &str,
 ) -> ScaffoldResult<Referenceable> {
-    let maybe_reference_type = inner_choose_referenceable(all_entries, prompt, false)?;
+    let maybe_reference_type = inner_choose_referenceable(all_entries, prompt, supplied_by, false)?;
 
     Ok(maybe_reference_type.expect("reference type should not be None"))
 }
@@ -98,14 +109,18 @@ pub fn choose_referenceable(
 pub fn choose_optional_referenceable(
     all_entries: &Vec<EntryTypeReference>,
     prompt: &String,
+    supplied_by: &str,
 ) -> ScaffoldResult<Option<Referenceable>> {
-    inner_choose_referenceable(all_entries, prompt, true)
+    inner_choose_referenceable(all_entries, prompt, supplied_by, true)
 }
 
 pub fn choose_entry_type_reference(
     all_entries: &Vec<EntryTypeReference>,
     prompt: &String,
+    supplied_by: &str,
 ) -> ScaffoldResult<EntryTypeReference> {
+    check_can_prompt(prompt, supplied_by)?;
+
     let all_options: Vec<String> = all_entries
         .clone()
         .into_iter()
@@ -125,11 +140,12 @@ pub fn get_or_choose_referenceable(
     zome_file_tree: &ZomeFileTree,
     entry_type: &Option<Referenceable>,
     prompt: &String,
+    supplied_by: &str,
 ) -> ScaffoldResult<Referenceable> {
     let all_entries = get_all_entry_types(&zome_file_tree)?.unwrap_or_else(|| vec![]);
 
     match &entry_type {
-        None => choose_referenceable(&all_entries, prompt),
+        None => choose_referenceable(&all_entries, prompt, supplied_by),
         Some(Referenceable::Agent { role }) => {
             check_for_reserved_words(&role)?;
             Ok(Referenceable::Agent { role: role.clone() })
@@ -155,11 +171,12 @@ pub fn get_or_choose_optional_reference_type(
     zome_file_tree: &ZomeFileTree,
     entry_type: &Option<Referenceable>,
     prompt: &String,
+    supplied_by: &str,
 ) -> ScaffoldResult<Option<Referenceable>> {
     let all_entries = get_all_entry_types(&zome_file_tree)?.unwrap_or_else(|| vec![]);
 
     match entry_type {
-        None => choose_optional_referenceable(&all_entries, prompt),
+        None => choose_optional_referenceable(&all_entries, prompt, supplied_by),
         Some(Referenceable::Agent { .. }) => Ok(entry_type.clone()),
         Some(Referenceable::EntryType(app_entry_reference)) => {
             let all_entries: Vec<String> = all_entries.into_iter().map(|e| e.entry_type).collect();
@@ -0,0 +1,845 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::FileTree,
+};
+
+use super::{
+    entry_type::integrity::{find_ending_match_expr_in_block, get_all_entry_types},
+    remove::{map_zome_files, mentions_ident},
+    zome::{utils::get_coordinator_zomes_for_integrity, ZomeFileTree},
+};
+
+/// Snapshots the current struct of the given entry type as its next version, "<ENTRY_TYPE>V<N>",
+/// with a conversion from it to the current struct, and makes the validation of the integrity zome and the read handlers
+/// of the coordinator zomes convert the entries created with any of its versions to the current struct
+///
+/// Returns the name of the new version together with the resulting file tree
+pub fn version_entry_type(
+    zome_file_tree: ZomeFileTree,
+    entry_type: &str,
+) -> ScaffoldResult<(String, FileTree)> {
+    let pascal_entry_type = entry_type.to_case(Case::Pascal);
+    let snake_entry_type = entry_type.to_case(Case::Snake);
+    let dna_name = zome_file_tree.dna_file_tree.dna_manifest.name();
+    let integrity_zome_name = zome_file_tree.zome_manifest.name.0.to_string();
+
+    if !get_all_entry_types(&zome_file_tree)?
+        .unwrap_or_default()
+        .iter()
+        .any(|et| et.entry_type.eq(&pascal_entry_type))
+    {
+        return Err(ScaffoldError::EntryTypeNotFound(
+            entry_type.to_string(),
+            dna_name,
+            integrity_zome_name,
+        ));
+    }
+
+    let mut version_name = String::new();
+    let zome_file_tree = map_zome_files(zome_file_tree, |files, _file_tree, crate_src_path| {
+        let version = versions_of(files, &pascal_entry_type)
+            .into_iter()
+            .max()
+            .unwrap_or_default()
+            + 1;
+        version_name = format!("{}V{}", pascal_entry_type, version);
+
+        let (entry_def_path, position, entry_struct) = files
+            .iter()
+            .find_map(|(path, file)| {
+                file.items
+                    .iter()
+                    .enumerate()
+                    .find_map(|(position, item)| match item {
+                        syn::Item::Struct(s) if s.ident.eq(&pascal_entry_type) => {
+                            Some((path.clone(), position, s.clone()))
+                        }
+                        _ => None,
+                    })
+            })
+            .ok_or(ScaffoldError::MalformedFile(
+                crate_src_path.join(format!("{}.rs", snake_entry_type)),
+                format!("the struct for entry type \"{}\" was not found", entry_type),
+            ))?;
+
+        // The conversion from the previous version now converts to the version being snapshotted
+        if version > 1 {
+            let previous_version_name = format!("{}V{}", pascal_entry_type, version - 1);
+            for file in files.values_mut() {
+                for item in &mut file.items {
+                    if let syn::Item::Impl(item_impl) = item {
+                        if is_conversion(item_impl, &previous_version_name, &pascal_entry_type) {
+                            *item_impl =
+                                retarget_conversion(item_impl, &pascal_entry_type, &version_name)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        let from_entry_fn = format!("{}_from_entry", snake_entry_type);
+        for file in files.values_mut() {
+            file.items.retain(|item| match item {
+                syn::Item::Fn(item_fn) => !item_fn.sig.ident.eq(&from_entry_fn),
+                _ => true,
+            });
+        }
+
+        let file = files
+            .get_mut(&entry_def_path)
+            .ok_or(ScaffoldError::PathNotFound(entry_def_path.clone()))?;
+        let version_items = render_version_items(&entry_struct, version)?;
+        file.items
+            .splice(position + 1..position + 1, version_items.items);
+
+        for file in files.values_mut() {
+            for item in &mut file.items {
+                if let syn::Item::Fn(item_fn) = item {
+                    if item_fn.sig.ident.eq(&from_entry_fn) {
+                        continue;
+                    }
+                    convert_entry_deserializations_in_block(&mut item_fn.block, &pascal_entry_type);
+                    if item_fn.sig.ident.eq("validate") {
+                        convert_deleted_entry_validation(&mut item_fn.block, &pascal_entry_type)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    })?;
+
+    let dna_manifest = zome_file_tree.dna_file_tree.dna_manifest.clone();
+    let mut dna_file_tree = zome_file_tree.dna_file_tree;
+
+    for coordinator_zome in get_coordinator_zomes_for_integrity(&dna_manifest, &integrity_zome_name)
+    {
+        let coordinator_file_tree =
+            ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome)?;
+        dna_file_tree = map_zome_files(
+            coordinator_file_tree,
+            |files, _file_tree, _crate_src_path| convert_read_handlers(files, &snake_entry_type),
+        )?
+        .dna_file_tree;
+    }
+
+    Ok((version_name, dna_file_tree.file_tree()))
+}
+
+/// The versions of the given entry type that were already snapshotted, from the structs named "<ENTRY_TYPE>V<N>"
+fn versions_of(files: &BTreeMap<PathBuf, syn::File>, pascal_entry_type: &str) -> Vec<usize> {
+    files
+        .values()
+        .flat_map(|file| file.items.iter())
+        .filter_map(|item| match item {
+            syn::Item::Struct(item_struct) => item_struct
+                .ident
+                .to_string()
+                .strip_prefix(&format!("{}V", pascal_entry_type))
+                .and_then(|version| version.parse::<usize>().ok()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the given impl is "impl From<FROM> for TO"
+fn is_conversion(item_impl: &syn::ItemImpl, from: &str, to: &str) -> bool {
+    let implements_from = match &item_impl.trait_ {
+        Some((_, path, _)) => path.segments.last().is_some_and(|segment| {
+            segment.ident.eq("From") && mentions_ident(segment.arguments.to_token_stream(), from)
+        }),
+        None => false,
+    };
+
+    implements_from
+        && matches!(&*item_impl.self_ty, syn::Type::Path(type_path) if type_path.path.is_ident(to))
+}
+
+/// Replaces the type that the given conversion converts to, also where its body names it
+fn retarget_conversion(
+    item_impl: &syn::ItemImpl,
+    from: &str,
+    to: &str,
+) -> ScaffoldResult<syn::ItemImpl> {
+    let tokens = replace_ident(item_impl.to_token_stream(), from, to);
+    Ok(syn::parse2(tokens)?)
+}
+
+fn replace_ident(tokens: TokenStream, from: &str, to: &str) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) if ident.eq(from) => {
+                proc_macro2::TokenTree::Ident(proc_macro2::Ident::new(to, ident.span()))
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut new_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    replace_ident(group.stream(), from, to),
+                );
+                new_group.set_span(group.span());
+                proc_macro2::TokenTree::Group(new_group)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+/// Renders the snapshot of the given entry struct as the given version, the conversion from it to the entry struct,
+/// and the function that deserializes an entry created with any of the versions of the entry type
+fn render_version_items(
+    entry_struct: &syn::ItemStruct,
+    version: usize,
+) -> ScaffoldResult<syn::File> {
+    let pascal_entry_type = entry_struct.ident.to_string();
+    let snake_entry_type = pascal_entry_type.to_case(Case::Snake);
+    let entry_type_ident = &entry_struct.ident;
+    let version_ident = format_ident!("{}V{}", pascal_entry_type, version);
+    let version_arg = format_ident!("{}_v{}", snake_entry_type, version);
+
+    let mut version_struct = entry_struct.clone();
+    version_struct.ident = version_ident.clone();
+    let version_doc = format!(
+        " Version {} of the {} entry type, kept so that the entries created with it can still be read",
+        version,
+        snake_entry_type.to_case(Case::Lower)
+    );
+    version_struct
+        .attrs
+        .insert(0, syn::parse_quote! { #[doc = #version_doc] });
+
+    let field_names: Vec<&syn::Ident> = entry_struct
+        .fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+
+    let from_entry_fn = format_ident!("{}_from_entry", snake_entry_type);
+    let entry_arg = format_ident!("{}", snake_entry_type);
+    // Newer versions are tried first, and each version is converted through the ones after it
+    let version_checks: Vec<TokenStream> = (1..=version)
+        .rev()
+        .map(|v| {
+            let v_ident = format_ident!("{}V{}", pascal_entry_type, v);
+            let v_arg = format_ident!("{}_v{}", snake_entry_type, v);
+            let conversion = ((v + 1)..=version).fold(quote! { #v_arg }, |converted, next| {
+                let next_ident = format_ident!("{}V{}", pascal_entry_type, next);
+                quote! { #next_ident::from(#converted) }
+            });
+            quote! {
+                if let Ok(#v_arg) = #v_ident::try_from(entry) {
+                    return Ok(#entry_type_ident::from(#conversion));
+                }
+            }
+        })
+        .collect();
+    let invalid_reason = format!(
+        "The entry is not any of the versions of {}",
+        pascal_entry_type
+    );
+
+    let items = quote! {
+        #version_struct
+
+        impl From<#version_ident> for #entry_type_ident {
+            fn from(#version_arg: #version_ident) -> Self {
+                Self {
+                    #(#field_names: #version_arg.#field_names),*
+                }
+            }
+        }
+
+        /// Deserializes the given entry as the current version of the entry type, or converts it from the version it was created with
+        pub fn #from_entry_fn(entry: &Entry) -> ExternResult<#entry_type_ident> {
+            if let Ok(#entry_arg) = #entry_type_ident::try_from(entry) {
+                return Ok(#entry_arg);
+            }
+            #(#version_checks)*
+
+            Err(wasm_error!(WasmErrorInner::Guest(String::from(#invalid_reason))))
+        }
+    };
+
+    Ok(syn::parse2(items)?)
+}
+
+/// Makes the statements of the given block, and the ones nested in it, deserialize the entries of the given entry type
+/// with "<ENTRY_TYPE>_from_entry" instead of deserializing them directly as its current struct
+fn convert_entry_deserializations_in_block(block: &mut syn::Block, pascal_entry_type: &str) {
+    for stmt in &mut block.stmts {
+        match stmt {
+            syn::Stmt::Local(local) => {
+                if let Some(init) = &mut local.init {
+                    // "let post: Post = record.entry().to_app_option()..." declares the type it deserializes
+                    if let syn::Pat::Type(pat_type) = &local.pat {
+                        if let Some(from_entry_path) =
+                            from_entry_path_for_type(&pat_type.ty, pascal_entry_type)
+                        {
+                            convert_to_app_option(&mut init.expr, &from_entry_path);
+                        }
+                    }
+                    convert_entry_deserializations(&mut init.expr, pascal_entry_type);
+                }
+            }
+            syn::Stmt::Expr(expr, _) => convert_entry_deserializations(expr, pascal_entry_type),
+            _ => {}
+        }
+    }
+}
+
+fn convert_entry_deserializations(expr: &mut syn::Expr, pascal_entry_type: &str) {
+    match expr {
+        syn::Expr::Call(call) => {
+            // "Post::try_from(entry)" becomes "post_from_entry(&entry)"
+            let from_entry_path = match &*call.func {
+                syn::Expr::Path(expr_path) if call.args.len() == 1 => {
+                    from_entry_path_for_try_from(&expr_path.path, pascal_entry_type)
+                }
+                _ => None,
+            };
+            match from_entry_path {
+                Some(from_entry_path) => {
+                    let entry = &call.args[0];
+                    *expr = syn::parse_quote! { #from_entry_path(&#entry) };
+                }
+                None => {
+                    for arg in &mut call.args {
+                        convert_entry_deserializations(arg, pascal_entry_type);
+                    }
+                }
+            }
+        }
+        syn::Expr::Try(expr_try) => {
+            convert_entry_deserializations(&mut expr_try.expr, pascal_entry_type)
+        }
+        syn::Expr::Block(expr_block) => {
+            convert_entry_deserializations_in_block(&mut expr_block.block, pascal_entry_type)
+        }
+        syn::Expr::If(expr_if) => {
+            convert_entry_deserializations_in_block(&mut expr_if.then_branch, pascal_entry_type);
+            if let Some((_, else_branch)) = &mut expr_if.else_branch {
+                convert_entry_deserializations(else_branch, pascal_entry_type);
+            }
+        }
+        syn::Expr::ForLoop(expr_for) => {
+            convert_entry_deserializations_in_block(&mut expr_for.body, pascal_entry_type)
+        }
+        syn::Expr::Match(expr_match) => {
+            for arm in &mut expr_match.arms {
+                convert_entry_deserializations(&mut arm.body, pascal_entry_type);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replaces "<RECORD>.entry().to_app_option().map_err(...)?" in the given method chain
+/// with "<RECORD>.entry().as_option().map(<FROM_ENTRY_PATH>).transpose()?"
+fn convert_to_app_option(expr: &mut syn::Expr, from_entry_path: &syn::Path) {
+    match expr {
+        syn::Expr::Try(expr_try) => {
+            let record_entry = match &*expr_try.expr {
+                syn::Expr::MethodCall(map_err) if map_err.method.eq("map_err") => {
+                    match &*map_err.receiver {
+                        syn::Expr::MethodCall(to_app_option)
+                            if to_app_option.method.eq("to_app_option") =>
+                        {
+                            Some(to_app_option.receiver.clone())
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match record_entry {
+                Some(record_entry) => {
+                    *expr = syn::parse_quote! {
+                        #record_entry.as_option().map(#from_entry_path).transpose()?
+                    };
+                }
+                None => convert_to_app_option(&mut expr_try.expr, from_entry_path),
+            }
+        }
+        syn::Expr::MethodCall(method_call) => {
+            convert_to_app_option(&mut method_call.receiver, from_entry_path)
+        }
+        _ => {}
+    }
+}
+
+/// The path to "<ENTRY_TYPE>_from_entry" if the given type is the struct of the entry type or an option of it,
+/// next to the struct, like "crate::post_from_entry" for "crate::Post"
+fn from_entry_path_for_type(ty: &syn::Type, pascal_entry_type: &str) -> Option<syn::Path> {
+    match type_name_and_arguments(ty)? {
+        (name, arguments) if name == "Option" && arguments.len() == 1 => {
+            from_entry_path_for_type(arguments[0], pascal_entry_type)
+        }
+        (name, arguments) if name == pascal_entry_type && arguments.is_empty() => {
+            let mut path = match ty {
+                syn::Type::Path(type_path) => type_path.path.clone(),
+                _ => return None,
+            };
+            let last_segment = path.segments.last_mut()?;
+            last_segment.ident =
+                format_ident!("{}_from_entry", pascal_entry_type.to_case(Case::Snake));
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
+/// The path to "<ENTRY_TYPE>_from_entry" if the given path is "<ENTRY_TYPE>::try_from", keeping the prefix of the struct
+fn from_entry_path_for_try_from(path: &syn::Path, pascal_entry_type: &str) -> Option<syn::Path> {
+    let segments: Vec<&syn::PathSegment> = path.segments.iter().collect();
+    match segments.as_slice() {
+        [prefix @ .., entry_type, try_from]
+            if entry_type.ident.eq(pascal_entry_type) && try_from.ident.eq("try_from") =>
+        {
+            let from_entry_fn =
+                format_ident!("{}_from_entry", pascal_entry_type.to_case(Case::Snake));
+            let mut from_entry_path = syn::Path {
+                leading_colon: path.leading_colon,
+                segments: prefix.iter().map(|segment| (*segment).clone()).collect(),
+            };
+            from_entry_path.segments.push(from_entry_fn.into());
+            Some(from_entry_path)
+        }
+        _ => None,
+    }
+}
+
+/// Makes the validation of the deletes of the given entry type deserialize the deleted entry with "<ENTRY_TYPE>_from_entry",
+/// before the deleted entry is deserialized as one of the current structs of "EntryTypes"
+fn convert_deleted_entry_validation(
+    validate_block: &mut syn::Block,
+    pascal_entry_type: &str,
+) -> ScaffoldResult<()> {
+    let from_entry_fn = format_ident!("{}_from_entry", pascal_entry_type.to_case(Case::Snake));
+    let delete_block = match validate_block.stmts.last_mut() {
+        Some(syn::Stmt::Expr(expr, None)) => find_delete_entry_block(expr),
+        _ => None,
+    };
+    let delete_block = match delete_block {
+        Some(delete_block) => delete_block,
+        None => return Ok(()),
+    };
+    if mentions_ident(delete_block.to_token_stream(), &from_entry_fn.to_string()) {
+        return Ok(());
+    }
+    let deserialization_position = delete_block.stmts.iter().position(|stmt| {
+        matches!(stmt, syn::Stmt::Local(_))
+            && mentions_ident(stmt.to_token_stream(), "deserialize_from_type")
+    });
+
+    // Reuses the arm that validates the delete once the entry is deserialized
+    let validation_arm = find_ending_match_expr_in_block(delete_block).and_then(|expr_match| {
+        expr_match.arms.iter().find_map(|arm| match &arm.pat {
+            syn::Pat::TupleStruct(pat)
+                if pat
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident.eq(pascal_entry_type)) =>
+            {
+                let validation = match &*arm.body {
+                    syn::Expr::Block(expr_block) => match expr_block.block.stmts.as_slice() {
+                        [syn::Stmt::Expr(validation, None)] => validation.clone(),
+                        _ => *arm.body.clone(),
+                    },
+                    _ => *arm.body.clone(),
+                };
+                match pat.elems.first() {
+                    Some(syn::Pat::Ident(binding)) => Some((binding.ident.clone(), validation)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+    });
+
+    if let (Some(position), Some((binding, validation))) =
+        (deserialization_position, validation_arm)
+    {
+        let unit_entry_type = format_ident!("{}", pascal_entry_type);
+        delete_block.stmts.insert(
+            position,
+            syn::parse_quote! {
+                if let Some(UnitEntryTypes::#unit_entry_type) = zome_info()?.zome_types.entries.find(
+                    UnitEntryTypes::iter(),
+                    ScopedEntryDefIndex {
+                        zome_index: app_entry_type.zome_index.clone(),
+                        zome_type: app_entry_type.entry_index.clone(),
+                    },
+                ) {
+                    let #binding = #from_entry_fn(entry)?;
+                    return #validation;
+                }
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// The block of the arm of "validate" that validates the deletes of entries, which deserializes the deleted entry
+fn find_delete_entry_block(expr: &mut syn::Expr) -> Option<&mut syn::Block> {
+    match expr {
+        syn::Expr::Match(expr_match) => expr_match.arms.iter_mut().find_map(|arm| {
+            let deletes_entry = mentions_ident(arm.pat.to_token_stream(), "DeleteEntry")
+                && mentions_ident(arm.body.to_token_stream(), "deserialize_from_type");
+            match (&mut *arm.body, deletes_entry) {
+                (syn::Expr::Block(expr_block), true) => Some(&mut expr_block.block),
+                (_, true) => None,
+                (body, false) => find_delete_entry_block(body),
+            }
+        }),
+        syn::Expr::Block(expr_block) => match expr_block.block.stmts.last_mut() {
+            Some(syn::Stmt::Expr(expr, None)) => find_delete_entry_block(expr),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Makes the read handlers of the given entry type return its records with their entry converted to its current version
+fn convert_read_handlers(
+    files: &mut BTreeMap<PathBuf, syn::File>,
+    snake_entry_type: &str,
+) -> ScaffoldResult<()> {
+    let read_handlers = [
+        format!("get_{}", snake_entry_type),
+        format!("get_original_{}", snake_entry_type),
+        format!("get_latest_{}", snake_entry_type),
+        format!("get_all_revisions_for_{}", snake_entry_type),
+    ];
+    let convert_fn = format_ident!("with_latest_{}_version", snake_entry_type);
+    let from_entry_fn = format_ident!("{}_from_entry", snake_entry_type);
+
+    for file in files.values_mut() {
+        let mut converted_handlers = false;
+
+        for item in &mut file.items {
+            if let syn::Item::Fn(item_fn) = item {
+                if read_handlers.iter().any(|h| item_fn.sig.ident.eq(h))
+                    && !mentions_ident(item_fn.block.to_token_stream(), &convert_fn.to_string())
+                {
+                    converted_handlers |= convert_returned_records(item_fn, &convert_fn);
+                }
+            }
+        }
+
+        let already_defined = file.items.iter().any(
+            |item| matches!(item, syn::Item::Fn(item_fn) if item_fn.sig.ident.eq(&convert_fn)),
+        );
+        if converted_handlers && !already_defined {
+            let entry_arg = format_ident!("{}", snake_entry_type);
+            file.items.push(syn::parse_quote! {
+                /// Converts the entry of the given record to the current version of its entry type,
+                /// in case it was created with one of its previous versions
+                fn #convert_fn(mut record: Record) -> ExternResult<Record> {
+                    if let Some(entry) = record.entry().as_option() {
+                        let #entry_arg = #from_entry_fn(entry)?;
+                        record.entry = RecordEntry::Present(Entry::try_from(#entry_arg)?);
+                    }
+                    Ok(record)
+                }
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces the last expression of the given function, which returns "ExternResult<Option<Record>>" or "ExternResult<Vec<Record>>",
+/// with one that converts the records that it returns
+fn convert_returned_records(item_fn: &mut syn::ItemFn, convert_fn: &syn::Ident) -> bool {
+    let returned_type = match &item_fn.sig.output {
+        syn::ReturnType::Type(_, ty) => ty,
+        syn::ReturnType::Default => return false,
+    };
+    let records_type = match type_name_and_arguments(returned_type) {
+        Some((name, arguments)) if name == "ExternResult" && arguments.len() == 1 => arguments[0],
+        _ => return false,
+    };
+    let returns_option = match type_name_and_arguments(records_type) {
+        Some((name, arguments)) if arguments.len() == 1 && is_record(arguments[0]) => {
+            match name.as_str() {
+                "Option" => true,
+                "Vec" => false,
+                _ => return false,
+            }
+        }
+        _ => return false,
+    };
+
+    let tail = match item_fn.block.stmts.pop() {
+        Some(syn::Stmt::Expr(tail, None)) => tail,
+        Some(stmt) => {
+            item_fn.block.stmts.push(stmt);
+            return false;
+        }
+        None => return false,
+    };
+
+    // "Ok(records)" is unwrapped, and a plain variable is converted directly
+    let value = match tail {
+        syn::Expr::Call(call)
+            if call.args.len() == 1
+                && matches!(&*call.func, syn::Expr::Path(p) if p.path.is_ident("Ok")) =>
+        {
+            call.args[0].clone()
+        }
+        tail => syn::parse_quote! { #tail? },
+    };
+    let binding = match &value {
+        syn::Expr::Path(path) if path.path.get_ident().is_some() => {
+            path.path.segments[0].ident.clone()
+        }
+        _ => {
+            let (binding, binding_type) = match returns_option {
+                true => (format_ident!("maybe_record"), quote! { Option<Record> }),
+                false => (format_ident!("records"), quote! { Vec<Record> }),
+            };
+            item_fn
+                .block
+                .stmts
+                .push(syn::parse_quote! { let #binding: #binding_type = #value; });
+            binding
+        }
+    };
+
+    let conversion: syn::Expr = match returns_option {
+        true => syn::parse_quote! { #binding.map(#convert_fn).transpose() },
+        false => syn::parse_quote! { #binding.into_iter().map(#convert_fn).collect() },
+    };
+    item_fn.block.stmts.push(syn::Stmt::Expr(conversion, None));
+
+    true
+}
+
+/// The name of the given type together with its type arguments, like "Option" and "Record" for "Option<Record>"
+fn type_name_and_arguments(ty: &syn::Type) -> Option<(String, Vec<&syn::Type>)> {
+    let segment = match ty {
+        syn::Type::Path(type_path) => type_path.path.segments.last()?,
+        _ => return None,
+    };
+
+    let type_arguments = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(inner_ty) => Some(inner_ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    Some((segment.ident.to_string(), type_arguments))
+}
+
+fn is_record(ty: &syn::Type) -> bool {
+    matches!(type_name_and_arguments(ty), Some((name, arguments)) if name == "Record" && arguments.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converted(mut item_fn: syn::ItemFn) -> Option<String> {
+        match convert_returned_records(&mut item_fn, &format_ident!("with_latest_post_version")) {
+            true => Some(item_fn.block.to_token_stream().to_string()),
+            false => None,
+        }
+    }
+
+    #[test]
+    fn test_convert_returned_option() {
+        let item_fn: syn::ItemFn = syn::parse_quote! {
+            pub fn get_post(post_hash: ActionHash) -> ExternResult<Option<Record>> {
+                get(post_hash, GetOptions::default())
+            }
+        };
+        let expected: syn::Block = syn::parse_quote! {{
+            let maybe_record: Option<Record> = get(post_hash, GetOptions::default())?;
+            maybe_record.map(with_latest_post_version).transpose()
+        }};
+
+        assert_eq!(
+            converted(item_fn),
+            Some(expected.to_token_stream().to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_returned_vec() {
+        let item_fn: syn::ItemFn = syn::parse_quote! {
+            pub fn get_all_posts(_: ()) -> ExternResult<Vec<Record>> {
+                let records = get_records()?;
+                Ok(records)
+            }
+        };
+        let expected: syn::Block = syn::parse_quote! {{
+            let records = get_records()?;
+            records.into_iter().map(with_latest_post_version).collect()
+        }};
+
+        assert_eq!(
+            converted(item_fn),
+            Some(expected.to_token_stream().to_string())
+        );
+    }
+
+    #[test]
+    fn test_skip_functions_that_dont_return_records() {
+        let item_fn: syn::ItemFn = syn::parse_quote! {
+            pub fn get_post_revisions(post_hash: ActionHash) -> ExternResult<Option<Vec<Record>>> {
+                get_revisions(post_hash)
+            }
+        };
+        assert_eq!(converted(item_fn), None);
+
+        let item_fn: syn::ItemFn = syn::parse_quote! {
+            pub fn get_post_links(post_hash: ActionHash) -> ExternResult<Vec<Link>> {
+                get_links(post_hash)
+            }
+        };
+        assert_eq!(converted(item_fn), None);
+    }
+
+    #[test]
+    fn test_convert_dependency_deserializations() {
+        let mut block: syn::Block = syn::parse_quote! {{
+            for action_hash in comment.post_hashes.clone() {
+                let record = must_get_valid_record(action_hash)?;
+                let _post: crate::Post = record.entry().to_app_option()
+                    .map_err(|e| wasm_error!(e))?
+                    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Dependant action must be accompanied by an entry"))))?;
+            }
+            if let Some(entry_hash) = comment.post_entry_hash.clone() {
+                let entry = must_get_entry(entry_hash)?;
+                let _post = crate::Post::try_from(entry)?;
+            }
+            let _comment = crate::Comment::try_from(entry)?;
+        }};
+        let expected: syn::Block = syn::parse_quote! {{
+            for action_hash in comment.post_hashes.clone() {
+                let record = must_get_valid_record(action_hash)?;
+                let _post: crate::Post = record.entry().as_option().map(crate::post_from_entry).transpose()?
+                    .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Dependant action must be accompanied by an entry"))))?;
+            }
+            if let Some(entry_hash) = comment.post_entry_hash.clone() {
+                let entry = must_get_entry(entry_hash)?;
+                let _post = crate::post_from_entry(&entry)?;
+            }
+            let _comment = crate::Comment::try_from(entry)?;
+        }};
+
+        convert_entry_deserializations_in_block(&mut block, "Post");
+
+        assert_eq!(
+            block.to_token_stream().to_string(),
+            expected.to_token_stream().to_string()
+        );
+    }
+
+    #[test]
+    fn test_convert_original_entry_of_updates() {
+        let mut block: syn::Block = syn::parse_quote! {{
+            match app_entry {
+                EntryTypes::Post(post) => {
+                    let original_post: Option<Post> = original_record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+                    validate_update_post(action, post, original_action, original_post)
+                }
+                EntryTypes::Comment(comment) => {
+                    let original_comment: Option<Comment> = original_record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+                    validate_update_comment(action, comment, original_action, original_comment)
+                }
+            }
+        }};
+        let expected: syn::Block = syn::parse_quote! {{
+            match app_entry {
+                EntryTypes::Post(post) => {
+                    let original_post: Option<Post> = original_record.entry().as_option().map(post_from_entry).transpose()?;
+                    validate_update_post(action, post, original_action, original_post)
+                }
+                EntryTypes::Comment(comment) => {
+                    let original_comment: Option<Comment> = original_record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+                    validate_update_comment(action, comment, original_action, original_comment)
+                }
+            }
+        }};
+
+        convert_entry_deserializations_in_block(&mut block, "Post");
+
+        assert_eq!(
+            block.to_token_stream().to_string(),
+            expected.to_token_stream().to_string()
+        );
+    }
+
+    #[test]
+    fn test_convert_deleted_entry_validation() {
+        let mut block: syn::Block = syn::parse_quote! {{
+            match op.flattened::<EntryTypes, LinkTypes>()? {
+                FlatOp::StoreRecord(store_record) => match store_record {
+                    OpRecord::DeleteEntry { original_action_hash, action, .. } => {
+                        let entry = must_get_entry(original_entry_hash)?;
+                        let original_app_entry = match EntryTypes::deserialize_from_type(
+                            app_entry_type.zome_index.clone(),
+                            app_entry_type.entry_index.clone(),
+                            &entry,
+                        )? {
+                            Some(app_entry) => app_entry,
+                            None => return Ok(ValidateCallbackResult::Invalid("Unknown entry type".to_string())),
+                        };
+                        match original_app_entry {
+                            EntryTypes::Post(original_post) => {
+                                validate_delete_post(action, original_action, original_post)
+                            }
+                        }
+                    }
+                    _ => Ok(ValidateCallbackResult::Valid),
+                },
+                _ => Ok(ValidateCallbackResult::Valid),
+            }
+        }};
+        let expected: syn::Stmt = syn::parse_quote! {
+            if let Some(UnitEntryTypes::Post) = zome_info()?.zome_types.entries.find(
+                UnitEntryTypes::iter(),
+                ScopedEntryDefIndex {
+                    zome_index: app_entry_type.zome_index.clone(),
+                    zome_type: app_entry_type.entry_index.clone(),
+                },
+            ) {
+                let original_post = post_from_entry(entry)?;
+                return validate_delete_post(action, original_action, original_post);
+            }
+        };
+
+        convert_deleted_entry_validation(&mut block, "Post").unwrap();
+        let converted_block = block.clone();
+        convert_deleted_entry_validation(&mut block, "Post").unwrap();
+        assert_eq!(
+            block.to_token_stream().to_string(),
+            converted_block.to_token_stream().to_string()
+        );
+
+        let delete_block = match block.stmts.last_mut() {
+            Some(syn::Stmt::Expr(expr, None)) => find_delete_entry_block(expr),
+            _ => None,
+        }
+        .unwrap();
+        assert_eq!(
+            delete_block.stmts[1].to_token_stream().to_string(),
+            expected.to_token_stream().to_string()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{ScaffoldError, ScaffoldResult};
+use crate::utils::check_can_prompt;
 use dialoguer::{theme::ColorfulTheme, Select};
 use serde::Serialize;
 use std::str::FromStr;
@@ -34,6 +35,8 @@ impl FromStr for Example {
 }
 
 pub fn choose_example() -> ScaffoldResult<Example> {
+    check_can_prompt("example", "<EXAMPLE>")?;
+
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Choose example:")
         .item("hello-world")
@@ -0,0 +1,1347 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use convert_case::{Case, Casing};
+use holochain_types::prelude::ZomeManifest;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use regex::Regex;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{dir_content, file_content, insert_file, FileTree},
+    templates::{entry_type::scaffold_entry_type_templates, ScaffoldedTemplate},
+    utils::{find_fn, find_fn_in_files, unparse},
+};
+
+use super::{
+    app::AppFileTree,
+    dna::DnaFileTree,
+    entry_type::{
+        check_field_definitions,
+        constraints::FieldConstraints,
+        coordinator::{create_handler, delete_handler, updates_link_name},
+        crud::Crud,
+        definitions::{
+            Cardinality, EntryDefSettings, EntryDefinition, EntryTypeReference, EnumVariant,
+            FieldDefinition, FieldType, Referenceable,
+        },
+        integrity::{add_regex_dependency, get_all_entry_types, render_entry_definition_file},
+    },
+    link_type::{
+        coordinator::get_links_handler,
+        integrity::{add_link_type_to_integrity_zome, get_all_link_types},
+        link_type_name,
+    },
+    remove::{
+        coordinator::remove_statements, link_type_ui_names, map_zome_files, mentions_ident,
+        normalized_file_name, remove_entry_type_from_common_ts, remove_entry_type_from_types_ts,
+        remove_from_zomes, remove_interface_from_types_ts, rust_files,
+    },
+    zome::{utils::get_coordinator_zomes_for_integrity, zome_crate_path, ZomeFileTree},
+};
+
+/// An entry type as it was scaffolded, read back from its code and its UI components
+pub struct ScaffoldedEntryType {
+    pub entry_def: EntryDefinition,
+    pub crud: Crud,
+    pub link_from_original_to_each_update: bool,
+    /// The coordinator zome that has the zome functions for the entry type
+    pub coordinator_zome: ZomeManifest,
+    /// The file of the integrity zome that defines the entry type, relative to its "src" folder
+    pub entry_def_path: PathBuf,
+}
+
+/// Adds the given field to an existing entry type: to its struct, to the validation of the entries it references,
+/// to the links that get created from it, and to the UI components and tests of the entry type
+pub fn add_field(
+    integrity_zome_file_tree: ZomeFileTree,
+    template_file_tree: &FileTree,
+    entry_type: &str,
+    field_def: &FieldDefinition,
+) -> ScaffoldResult<FileTree> {
+    let mut scaffolded =
+        read_scaffolded_entry_type(&integrity_zome_file_tree, template_file_tree, entry_type)?;
+    let previous_entry_def = scaffolded.entry_def.clone();
+
+    if previous_entry_def
+        .fields
+        .iter()
+        .any(|f| f.field_name.eq(&field_def.field_name))
+    {
+        return Err(ScaffoldError::FieldAlreadyExists(
+            field_def.field_name.clone(),
+            entry_type.to_string(),
+        ));
+    }
+
+    check_field_definitions(
+        &previous_entry_def.name,
+        &integrity_zome_file_tree,
+        &vec![field_def.clone()],
+    )?;
+
+    // The code for the new field is rendered as if the entry type only had that field
+    let field_entry_def = EntryDefinition {
+        name: previous_entry_def.name.clone(),
+        fields: vec![field_def.clone()],
+        reference_entry_hash: previous_entry_def.reference_entry_hash,
+        settings: previous_entry_def.settings.clone(),
+    };
+
+    let mut zome_file_tree = add_field_to_integrity_zome(
+        integrity_zome_file_tree,
+        &scaffolded.entry_def_path,
+        &field_entry_def,
+        &scaffolded.crud,
+    )?;
+
+    if let Some(linked_from) = &field_def.linked_from {
+        zome_file_tree = add_link_type_to_integrity_zome(
+            zome_file_tree,
+            &link_type_name(linked_from, &field_entry_def.referenceable()),
+            &Some(linked_from.clone()),
+            &Some(field_entry_def.referenceable()),
+            scaffolded.crud.delete,
+            false,
+            None,
+            &scaffolded.entry_def_path,
+            &None,
+        )?;
+    }
+
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(
+        zome_file_tree.dna_file_tree,
+        scaffolded.coordinator_zome.clone(),
+    )?;
+    let zome_file_tree =
+        add_field_to_coordinator(zome_file_tree, &field_entry_def, &scaffolded.crud)?;
+
+    scaffolded.entry_def.fields.push(field_def.clone());
+
+    rescaffold_entry_type_templates(
+        zome_file_tree.dna_file_tree,
+        template_file_tree,
+        &previous_entry_def,
+        &scaffolded,
+    )
+}
+
+/// Removes the given field from an existing entry type: from its struct, from the validation of the entries it references,
+/// together with the link type that was created from it, and from the UI components and tests of the entry type
+pub fn remove_field(
+    integrity_zome_file_tree: ZomeFileTree,
+    template_file_tree: &FileTree,
+    entry_type: &str,
+    field_name: &str,
+) -> ScaffoldResult<FileTree> {
+    let mut scaffolded =
+        read_scaffolded_entry_type(&integrity_zome_file_tree, template_file_tree, entry_type)?;
+    let previous_entry_def = scaffolded.entry_def.clone();
+
+    let field_def = match scaffolded
+        .entry_def
+        .fields
+        .iter()
+        .position(|f| f.field_name.eq(field_name))
+    {
+        Some(i) => scaffolded.entry_def.fields.remove(i),
+        None => {
+            return Err(ScaffoldError::FieldNotFound(
+                field_name.to_string(),
+                entry_type.to_string(),
+            ))
+        }
+    };
+
+    let integrity_zome_manifest = integrity_zome_file_tree.zome_manifest.clone();
+
+    let dna_file_tree = match &field_def.linked_from {
+        Some(linked_from) => {
+            let link_type = link_type_name(linked_from, &previous_entry_def.referenceable());
+            let ui_names = link_type_ui_names(&link_type);
+
+            remove_from_zomes(integrity_zome_file_tree, None, &[link_type], &ui_names)?
+        }
+        None => integrity_zome_file_tree.dna_file_tree,
+    };
+
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, integrity_zome_manifest)?;
+    let zome_file_tree =
+        remove_field_from_integrity_zome(zome_file_tree, &previous_entry_def, &field_def)?;
+
+    rescaffold_entry_type_templates(
+        zome_file_tree.dna_file_tree,
+        template_file_tree,
+        &previous_entry_def,
+        &scaffolded,
+    )
+}
+
+/// Returns the names of the fields of the given entry type
+pub fn entry_type_field_names(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: &str,
+) -> ScaffoldResult<Vec<String>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let files = rust_files(
+        zome_file_tree.dna_file_tree.file_tree_ref(),
+        &crate_src_path,
+    )?;
+
+    Ok(
+        match find_struct(&files, &entry_type.to_case(Case::Pascal)) {
+            Some((_path, item_struct)) => item_struct
+                .fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()))
+                .collect(),
+            None => vec![],
+        },
+    )
+}
+
+/// Returns the field type and cardinality of the given field of the entry type, if it exists
+pub fn entry_type_field(
+    zome_file_tree: &ZomeFileTree,
+    entry_type: &str,
+    field_name: &str,
+) -> ScaffoldResult<Option<(FieldType, Cardinality)>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let files = rust_files(
+        zome_file_tree.dna_file_tree.file_tree_ref(),
+        &crate_src_path,
+    )?;
+
+    Ok(
+        find_struct(&files, &entry_type.to_case(Case::Pascal)).and_then(|(_path, item_struct)| {
+            item_struct
+                .fields
+                .iter()
+                .find(|field| {
+                    field
+                        .ident
+                        .as_ref()
+                        .is_some_and(|ident| ident.eq(field_name))
+                })
+                .and_then(|field| parse_field_type(&field.ty, &files))
+        }),
+    )
+}
+
+/// Reads the definition of the given entry type back from the integrity zome, with the constraints of its fields,
+/// together with the zome functions that were scaffolded for it in the coordinator zome that creates its entries
+pub fn read_scaffolded_entry_type(
+    zome_file_tree: &ZomeFileTree,
+    template_file_tree: &FileTree,
+    entry_type: &str,
+) -> ScaffoldResult<ScaffoldedEntryType> {
+    let pascal_entry_type = entry_type.to_case(Case::Pascal);
+    let snake_entry_type = entry_type.to_case(Case::Snake);
+    let dna_file_tree = &zome_file_tree.dna_file_tree;
+    let dna_name = dna_file_tree.dna_manifest.name();
+    let integrity_zome_name = zome_file_tree.zome_manifest.name.0.to_string();
+
+    let entry_type_reference = get_all_entry_types(zome_file_tree)?
+        .unwrap_or_default()
+        .into_iter()
+        .find(|et| et.entry_type.eq(&pascal_entry_type))
+        .ok_or(ScaffoldError::EntryTypeNotFound(
+            entry_type.to_string(),
+            dna_name.clone(),
+            integrity_zome_name.clone(),
+        ))?;
+
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let integrity_files = rust_files(dna_file_tree.file_tree_ref(), &crate_src_path)?;
+
+    let (entry_def_path, entry_struct) =
+        find_struct(&integrity_files, &pascal_entry_type).ok_or(ScaffoldError::MalformedFile(
+            crate_src_path.join(format!("{}.rs", snake_entry_type)),
+            format!("the struct for entry type \"{}\" was not found", entry_type),
+        ))?;
+
+    let validate_create_fn = find_fn_in_files(
+        &integrity_files,
+        &format!("validate_create_{}", snake_entry_type),
+    );
+    let validate_update_fn = find_fn_in_files(
+        &integrity_files,
+        &format!("validate_update_{}", snake_entry_type),
+    );
+
+    let create_fn_name = format!("create_{}", snake_entry_type);
+    let mut coordinator = None;
+    for coordinator_zome in
+        get_coordinator_zomes_for_integrity(&dna_file_tree.dna_manifest, &integrity_zome_name)
+    {
+        let coordinator_src_path = zome_crate_path(dna_file_tree, &coordinator_zome)?.join("src");
+        let files = rust_files(dna_file_tree.file_tree_ref(), &coordinator_src_path)?;
+
+        if let Some(create_fn) = find_fn_in_files(&files, &create_fn_name) {
+            let create_fn = create_fn.clone();
+            coordinator = Some((coordinator_zome, files, create_fn));
+            break;
+        }
+    }
+    let (coordinator_zome, coordinator_files, create_fn) =
+        coordinator.ok_or(ScaffoldError::NoCoordinatorZomesFoundForIntegrityZome(
+            dna_name.clone(),
+            integrity_zome_name,
+        ))?;
+
+    let crud = Crud {
+        update: find_fn_in_files(&coordinator_files, &format!("update_{}", snake_entry_type))
+            .is_some(),
+        delete: find_fn_in_files(&coordinator_files, &format!("delete_{}", snake_entry_type))
+            .is_some(),
+        author_only: ["update", "delete"].iter().any(|operation| {
+            find_fn_in_files(
+                &integrity_files,
+                &format!("validate_{}_{}", operation, snake_entry_type),
+            )
+            .is_some_and(|validate_fn| {
+                mentions_field(
+                    validate_fn.block.to_token_stream(),
+                    "original_action",
+                    "author",
+                )
+            })
+        }),
+    };
+    let link_from_original_to_each_update =
+        get_all_link_types(zome_file_tree)?.contains(&updates_link_name(&snake_entry_type));
+
+    let create_component = read_create_component(
+        dna_file_tree.file_tree_ref(),
+        &dna_name,
+        &coordinator_zome.name.0,
+        &pascal_entry_type,
+    );
+
+    let referenceable = Referenceable::EntryType(EntryTypeReference {
+        entry_type: snake_entry_type.clone(),
+        reference_entry_hash: entry_type_reference.reference_entry_hash,
+    });
+
+    let fields = entry_struct
+        .fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref().map(|ident| (ident.to_string(), &field.ty)))
+        .map(|(field_name, ty)| {
+            let (mut field_type, cardinality) = parse_field_type(ty, &integrity_files).ok_or(
+                ScaffoldError::MalformedFile(
+                    crate_src_path.join(&entry_def_path),
+                    format!(
+                        "the type of the field \"{}\" is not one of the field types that can be scaffolded",
+                        field_name
+                    ),
+                ),
+            )?;
+            let linked_from = find_linked_from(
+                &create_fn,
+                &snake_entry_type,
+                &field_name,
+                &field_type,
+                &referenceable,
+            );
+            if let Some(create_component) = &create_component {
+                find_struct_widgets(&mut field_type, template_file_tree, create_component);
+            }
+            let widget = create_component.as_ref().and_then(|create_component| {
+                find_widget(template_file_tree, create_component, &field_name, &field_type)
+            });
+            let mut constraints = validate_create_fn
+                .map(|validate_create_fn| find_constraints(validate_create_fn, &field_name))
+                .unwrap_or_default();
+            // Immutable fields are compared with the original entry when it gets updated
+            constraints.immutable = validate_update_fn.is_some_and(|validate_update_fn| {
+                mentions_field(
+                    validate_update_fn.block.to_token_stream(),
+                    &format!("original_{}", snake_entry_type),
+                    &field_name,
+                )
+            });
+
+            Ok(FieldDefinition {
+                field_name,
+                field_type,
+                widget,
+                cardinality,
+                linked_from,
+                constraints,
+            })
+        })
+        .collect::<ScaffoldResult<Vec<FieldDefinition>>>()?;
+
+    Ok(ScaffoldedEntryType {
+        entry_def: EntryDefinition {
+            name: snake_entry_type,
+            fields,
+            reference_entry_hash: entry_type_reference.reference_entry_hash,
+            settings: find_entry_def_settings(&integrity_files, &pascal_entry_type),
+        },
+        crud,
+        link_from_original_to_each_update,
+        coordinator_zome,
+        entry_def_path,
+    })
+}
+
+/// Adds the field of the given entry definition to the entry struct, together with the definition of its type
+/// and the validation of the entry that it references, if any
+fn add_field_to_integrity_zome(
+    zome_file_tree: ZomeFileTree,
+    entry_def_path: &Path,
+    field_entry_def: &EntryDefinition,
+    crud: &Crud,
+) -> ScaffoldResult<ZomeFileTree> {
+    let rendered_file = render_entry_definition_file(field_entry_def, crud)?;
+    let pascal_entry_type = field_entry_def.name.to_case(Case::Pascal);
+    let validate_fns = [
+        format!(
+            "validate_create_{}",
+            field_entry_def.name.to_case(Case::Snake)
+        ),
+        format!(
+            "validate_update_{}",
+            field_entry_def.name.to_case(Case::Snake)
+        ),
+    ];
+
+    let zome_file_tree = map_zome_files(zome_file_tree, |files, _file_tree, crate_src_path| {
+        let defined_types: Vec<String> = files
+            .values()
+            .flat_map(|file| file.items.iter())
+            .filter_map(|item| match item {
+                syn::Item::Struct(item_struct) => Some(item_struct.ident.to_string()),
+                syn::Item::Enum(item_enum) => Some(item_enum.ident.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        let file = files
+            .get_mut(entry_def_path)
+            .ok_or(ScaffoldError::PathNotFound(
+                crate_src_path.join(entry_def_path),
+            ))?;
+
+        for rendered_item in &rendered_file.items {
+            match rendered_item {
+                syn::Item::Struct(rendered_struct)
+                    if rendered_struct.ident.eq(&pascal_entry_type) =>
+                {
+                    for item in &mut file.items {
+                        if let syn::Item::Struct(item_struct) = item {
+                            if item_struct.ident.eq(&pascal_entry_type) {
+                                if let syn::Fields::Named(fields) = &mut item_struct.fields {
+                                    fields.named.extend(rendered_struct.fields.iter().cloned());
+                                }
+                            }
+                        }
+                    }
+                }
+                // Imports needed by the new field, like the one for BTreeMap
+                syn::Item::Use(_) if !file.items.contains(rendered_item) => {
+                    let position = file
+                        .items
+                        .iter()
+                        .rposition(|item| matches!(item, syn::Item::Use(_)))
+                        .map(|i| i + 1)
+                        .unwrap_or(0);
+                    file.items.insert(position, rendered_item.clone());
+                }
+                syn::Item::Enum(syn::ItemEnum { ident, .. })
+                | syn::Item::Struct(syn::ItemStruct { ident, .. })
+                    if !defined_types.contains(&ident.to_string()) =>
+                {
+                    let position = file
+                        .items
+                        .iter()
+                        .position(|item| {
+                            matches!(item, syn::Item::Struct(s) if s.ident.eq(&pascal_entry_type))
+                        })
+                        .unwrap_or(file.items.len());
+                    file.items.insert(position, rendered_item.clone());
+                }
+                _ => {}
+            }
+        }
+
+        for validate_fn in &validate_fns {
+            if let Some(rendered_fn) = find_fn(&rendered_file, validate_fn) {
+                merge_into_fns(files, rendered_fn);
+            }
+        }
+
+        Ok(())
+    })?;
+
+    match field_entry_def
+        .fields
+        .iter()
+        .any(|f| f.constraints.pattern.is_some())
+    {
+        true => add_regex_dependency(zome_file_tree),
+        false => Ok(zome_file_tree),
+    }
+}
+
+/// Adds the link from the value of the field of the given entry definition to the create and delete functions,
+/// together with the function to get those links
+fn add_field_to_coordinator(
+    zome_file_tree: ZomeFileTree,
+    field_entry_def: &EntryDefinition,
+    crud: &Crud,
+) -> ScaffoldResult<ZomeFileTree> {
+    let linked_from = match field_entry_def
+        .fields
+        .iter()
+        .find_map(|f| f.linked_from.clone())
+    {
+        Some(linked_from) => linked_from,
+        None => return Ok(zome_file_tree),
+    };
+
+    let mut rendered_fns = vec![syn::parse_str::<syn::File>(&create_handler(
+        field_entry_def,
+    ))?];
+    if crud.delete {
+        rendered_fns.push(syn::parse_str::<syn::File>(&delete_handler(
+            field_entry_def,
+        ))?);
+    }
+    let get_links_file = syn::parse_str::<syn::File>(&get_links_handler(
+        &linked_from,
+        &field_entry_def.referenceable(),
+        crud.delete,
+        &None,
+    ))?;
+
+    let create_fn_name = format!("create_{}", field_entry_def.name.to_case(Case::Snake));
+
+    map_zome_files(zome_file_tree, |files, _file_tree, _crate_src_path| {
+        for rendered_file in &rendered_fns {
+            for item in &rendered_file.items {
+                if let syn::Item::Fn(rendered_fn) = item {
+                    merge_into_fns(files, rendered_fn);
+                }
+            }
+        }
+
+        let existing_fns: Vec<String> = files
+            .values()
+            .flat_map(|file| file.items.iter())
+            .filter_map(|item| match item {
+                syn::Item::Fn(item_fn) => Some(item_fn.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        for file in files.values_mut() {
+            if find_fn(file, &create_fn_name).is_some() {
+                file.items.extend(
+                    get_links_file
+                        .items
+                        .iter()
+                        .filter(|item| {
+                            !matches!(item, syn::Item::Fn(item_fn) if existing_fns.contains(&item_fn.sig.ident.to_string()))
+                        })
+                        .cloned(),
+                );
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Removes the given field from the entry struct, together with the validation of the entry that it references
+/// and the definition of its type, if no other field uses it
+fn remove_field_from_integrity_zome(
+    zome_file_tree: ZomeFileTree,
+    entry_def: &EntryDefinition,
+    field_def: &FieldDefinition,
+) -> ScaffoldResult<ZomeFileTree> {
+    let pascal_entry_type = entry_def.name.to_case(Case::Pascal);
+    let validate_fns = [
+        format!("validate_create_{}", entry_def.name.to_case(Case::Snake)),
+        format!("validate_update_{}", entry_def.name.to_case(Case::Snake)),
+    ];
+
+    map_zome_files(zome_file_tree, |files, _file_tree, _crate_src_path| {
+        for file in files.values_mut() {
+            for item in &mut file.items {
+                match item {
+                    syn::Item::Struct(item_struct) if item_struct.ident.eq(&pascal_entry_type) => {
+                        if let syn::Fields::Named(fields) = &mut item_struct.fields {
+                            fields.named = fields
+                                .named
+                                .clone()
+                                .into_iter()
+                                .filter(|f| {
+                                    !f.ident
+                                        .as_ref()
+                                        .is_some_and(|i| i.eq(&field_def.field_name))
+                                })
+                                .collect();
+                        }
+                    }
+                    syn::Item::Fn(item_fn)
+                        if validate_fns.iter().any(|f| item_fn.sig.ident.eq(f)) =>
+                    {
+                        remove_field_validation(item_fn, &field_def.field_name);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Nested types come after the ones that use them, so they are only removed once those are
+        for defined_type in field_def.field_type.defined_types() {
+            let is_definition = |item: &syn::Item| match item {
+                syn::Item::Enum(item_enum) => item_enum.ident.eq(&defined_type),
+                syn::Item::Struct(item_struct) => item_struct.ident.eq(&defined_type),
+                _ => false,
+            };
+            let still_used = files
+                .values()
+                .flat_map(|file| file.items.iter())
+                .any(|item| {
+                    !is_definition(item) && mentions_ident(item.to_token_stream(), &defined_type)
+                });
+
+            if !still_used {
+                for file in files.values_mut() {
+                    file.items.retain(|item| !is_definition(item));
+                }
+            }
+        }
+
+        if field_def.uses_map() {
+            let map_import: syn::Item = syn::parse_quote! { use std::collections::BTreeMap; };
+            for file in files.values_mut() {
+                let still_used = file.items.iter().any(|item| {
+                    !matches!(item, syn::Item::Use(_))
+                        && mentions_ident(item.to_token_stream(), "BTreeMap")
+                });
+                if !still_used {
+                    file.items.retain(|item| item.ne(&map_import));
+                }
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Removes the statements that validate the given field, like the ones for the entry it references or for its constraints,
+/// and prefixes the arguments with an underscore if they are no longer used
+fn remove_field_validation(validate_fn: &mut syn::ItemFn, field_name: &str) {
+    // The actions have fields of their own, like "author", so only the entries are checked
+    let args: Vec<String> = validate_fn
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => match (&*pat_type.pat, &*pat_type.ty) {
+                (syn::Pat::Ident(pat_ident), syn::Type::Path(type_path))
+                    if !["Create", "Update", "Delete", "EntryCreationAction"]
+                        .iter()
+                        .any(|action_type| type_path.path.is_ident(action_type)) =>
+                {
+                    Some(pat_ident.ident.to_string())
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    remove_statements(&mut validate_fn.block, &|tokens| {
+        args.iter()
+            .any(|arg| mentions_field(tokens.clone(), arg, field_name))
+    });
+
+    let block_tokens = validate_fn.block.to_token_stream();
+    for pat_ident in typed_args_mut(validate_fn) {
+        let arg = pat_ident.ident.to_string();
+        if !arg.starts_with('_') && !mentions_ident(block_tokens.clone(), &arg) {
+            pat_ident.ident = quote::format_ident!("_{}", arg);
+        }
+    }
+}
+
+/// Renders the templates for the entry type again, so that its UI components and tests include its current fields
+fn rescaffold_entry_type_templates(
+    dna_file_tree: DnaFileTree,
+    template_file_tree: &FileTree,
+    previous_entry_def: &EntryDefinition,
+    scaffolded: &ScaffoldedEntryType,
+) -> ScaffoldResult<FileTree> {
+    let dna_name = dna_file_tree.dna_manifest.name();
+    let zome_name = scaffolded.coordinator_zome.name.0.to_string();
+    let pascal_entry_type = previous_entry_def.name.to_case(Case::Pascal);
+
+    let mut file_tree = dna_file_tree.file_tree();
+
+    // The templates append the entry type to these files, so it's removed from them first
+    let types_ts_path = PathBuf::from("ui/src")
+        .join(&dna_name)
+        .join(&zome_name)
+        .join("types.ts");
+    if let Ok(types_ts) = file_content(&file_tree, &types_ts_path) {
+        let mut types_ts = remove_entry_type_from_types_ts(&types_ts, &pascal_entry_type);
+        for field in &previous_entry_def.fields {
+            for defined_type in field.field_type.defined_types() {
+                types_ts = remove_interface_from_types_ts(&types_ts, &defined_type);
+            }
+        }
+        insert_file(
+            &mut file_tree,
+            &types_ts_path,
+            &format!("{}\n", types_ts.trim_end()),
+        )?;
+    }
+
+    let common_ts_path = PathBuf::from("tests/src")
+        .join(&dna_name)
+        .join(&zome_name)
+        .join("common.ts");
+    if let Ok(common_ts) = file_content(&file_tree, &common_ts_path) {
+        let common_ts = remove_entry_type_from_common_ts(&common_ts, &pascal_entry_type);
+        insert_file(
+            &mut file_tree,
+            &common_ts_path,
+            &format!("{}\n", common_ts.trim_end()),
+        )?;
+    }
+
+    let app_file_tree = AppFileTree::get_or_choose(file_tree, &None)?;
+    let app_name = app_file_tree.app_manifest.app_name().to_string();
+
+    let ScaffoldedTemplate { file_tree, .. } = scaffold_entry_type_templates(
+        app_file_tree.file_tree(),
+        template_file_tree,
+        &app_name,
+        &dna_name,
+        &scaffolded.coordinator_zome,
+        &scaffolded.entry_def,
+        &scaffolded.crud,
+        scaffolded.link_from_original_to_each_update,
+    )?;
+
+    Ok(file_tree)
+}
+
+/// Adds the statements of the given function that are missing from the function with the same name, if it exists
+///
+/// Each missing statement is inserted before the next statement that both functions share,
+/// or before the return value if there is none
+fn merge_into_fns(files: &mut BTreeMap<PathBuf, syn::File>, rendered_fn: &syn::ItemFn) {
+    for file in files.values_mut() {
+        for item in &mut file.items {
+            if let syn::Item::Fn(item_fn) = item {
+                if item_fn.sig.ident.eq(&rendered_fn.sig.ident) {
+                    merge_statements(&mut item_fn.block, &rendered_fn.block);
+
+                    // The arguments are prefixed with an underscore while they are unused
+                    for (pat_ident, rendered_pat_ident) in typed_args_mut(item_fn)
+                        .into_iter()
+                        .zip(typed_args(rendered_fn))
+                    {
+                        if !rendered_pat_ident.ident.to_string().starts_with('_') {
+                            pat_ident.ident = rendered_pat_ident.ident.clone();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn merge_statements(block: &mut syn::Block, rendered_block: &syn::Block) {
+    let rendered_stmts = match rendered_block.stmts.split_last() {
+        Some((syn::Stmt::Expr(_, None), stmts)) => stmts,
+        _ => &rendered_block.stmts[..],
+    };
+
+    let mut missing: Vec<syn::Stmt> = vec![];
+
+    for rendered_stmt in rendered_stmts {
+        let rendered_tokens = formatted_tokens(rendered_stmt);
+
+        match block
+            .stmts
+            .iter()
+            .position(|stmt| formatted_tokens(stmt).eq(&rendered_tokens))
+        {
+            Some(i) => {
+                block.stmts.splice(i..i, missing.drain(..));
+            }
+            None => missing.push(rendered_stmt.clone()),
+        }
+    }
+
+    let end = match block.stmts.last() {
+        Some(syn::Stmt::Expr(_, None)) => block.stmts.len() - 1,
+        _ => block.stmts.len(),
+    };
+    block.stmts.splice(end..end, missing);
+}
+
+/// The tokens of the given statement after formatting it, so that statements can be compared
+/// regardless of whether they were formatted before being written
+fn formatted_tokens(stmt: &syn::Stmt) -> String {
+    let file: syn::File = syn::parse_quote! {
+        fn f() {
+            #stmt
+        }
+    };
+
+    match syn::parse_file(&unparse(&file)) {
+        Ok(formatted_file) => formatted_file.to_token_stream().to_string(),
+        Err(_) => stmt.to_token_stream().to_string(),
+    }
+}
+
+/// The arguments of the given function that are bound to a name, like the entry in the scaffolded validation functions
+fn typed_args(item_fn: &syn::ItemFn) -> Vec<&syn::PatIdent> {
+    item_fn
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => match &*pat_type.pat {
+                syn::Pat::Ident(pat_ident) => Some(pat_ident),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn typed_args_mut(item_fn: &mut syn::ItemFn) -> Vec<&mut syn::PatIdent> {
+    item_fn
+        .sig
+        .inputs
+        .iter_mut()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pat_type) => match &mut *pat_type.pat {
+                syn::Pat::Ident(pat_ident) => Some(pat_ident),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn find_struct<'a>(
+    files: &'a BTreeMap<PathBuf, syn::File>,
+    struct_name: &str,
+) -> Option<(PathBuf, &'a syn::ItemStruct)> {
+    files.iter().find_map(|(path, file)| {
+        file.items.iter().find_map(|item| match item {
+            syn::Item::Struct(item_struct) if item_struct.ident.eq(struct_name) => {
+                Some((path.clone(), item_struct))
+            }
+            _ => None,
+        })
+    })
+}
+
+/// Reads the settings of the entry type from the `#[entry_def]` attribute of its variant in the `EntryTypes` enum
+pub fn find_entry_def_settings(
+    files: &BTreeMap<PathBuf, syn::File>,
+    pascal_entry_type: &str,
+) -> EntryDefSettings {
+    files
+        .values()
+        .flat_map(|file| file.items.iter())
+        .find_map(|item| match item {
+            syn::Item::Enum(item_enum)
+                if item_enum
+                    .attrs
+                    .iter()
+                    .any(|a| a.path().is_ident("hdk_entry_defs")) =>
+            {
+                item_enum
+                    .variants
+                    .iter()
+                    .find(|v| v.ident.eq(pascal_entry_type))
+            }
+            _ => None,
+        })
+        .map(EntryDefSettings::from_variant)
+        .unwrap_or_default()
+}
+
+/// Parses the field type and cardinality of a field of the entry struct,
+/// returning None if it's not one that can be scaffolded
+pub fn parse_field_type(
+    ty: &syn::Type,
+    files: &BTreeMap<PathBuf, syn::File>,
+) -> Option<(FieldType, Cardinality)> {
+    let segment = match ty {
+        syn::Type::Path(type_path) => type_path.path.segments.last()?,
+        _ => return None,
+    };
+
+    let type_arguments: Vec<&syn::Type> = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) => arguments
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(inner_ty) => Some(inner_ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    match (segment.ident.to_string().as_str(), &type_arguments[..]) {
+        ("Vec", [inner_ty]) => Some((
+            parse_single_field_type(inner_ty, files)?,
+            Cardinality::Vector,
+        )),
+        ("Option", [inner_ty]) => Some((
+            parse_single_field_type(inner_ty, files)?,
+            Cardinality::Option,
+        )),
+        ("BTreeMap" | "HashMap", [key_ty, inner_ty]) => {
+            let key = parse_single_field_type(key_ty, files)?;
+            match key.can_be_map_key() {
+                true => Some((
+                    parse_single_field_type(inner_ty, files)?,
+                    Cardinality::Map { key },
+                )),
+                false => None,
+            }
+        }
+        ("Vec" | "Option" | "BTreeMap" | "HashMap", _) => None,
+        _ => Some((parse_single_field_type(ty, files)?, Cardinality::Single)),
+    }
+}
+
+fn parse_single_field_type(
+    ty: &syn::Type,
+    files: &BTreeMap<PathBuf, syn::File>,
+) -> Option<FieldType> {
+    let type_name = match ty {
+        syn::Type::Path(type_path) => type_path.path.segments.last()?.ident.to_string(),
+        _ => return None,
+    };
+
+    FieldType::list()
+        .into_iter()
+        .find(|ft| {
+            !matches!(ft, FieldType::Enum { .. } | FieldType::Struct { .. })
+                && ft.rust_type().to_string().eq(&type_name)
+        })
+        .or_else(|| {
+            // Enums and structs are defined in the integrity zome, next to the entry struct
+            files
+                .values()
+                .flat_map(|file| file.items.iter())
+                .find_map(|item| match item {
+                    // Enums and structs are only parsed if all their fields can be scaffolded too
+                    syn::Item::Enum(item_enum) if item_enum.ident.eq(&type_name) => {
+                        let variants = item_enum
+                            .variants
+                            .iter()
+                            .map(|variant| {
+                                Some(EnumVariant {
+                                    name: variant.ident.to_string(),
+                                    fields: parse_named_fields(&variant.fields, files)?,
+                                })
+                            })
+                            .collect::<Option<Vec<EnumVariant>>>()?;
+
+                        Some(FieldType::Enum {
+                            label: type_name.clone(),
+                            variants,
+                        })
+                    }
+                    syn::Item::Struct(item_struct) if item_struct.ident.eq(&type_name) => {
+                        Some(FieldType::Struct {
+                            label: type_name.clone(),
+                            fields: parse_named_fields(&item_struct.fields, files)?,
+                        })
+                    }
+                    _ => None,
+                })
+        })
+}
+
+fn parse_named_fields(
+    fields: &syn::Fields,
+    files: &BTreeMap<PathBuf, syn::File>,
+) -> Option<Vec<FieldDefinition>> {
+    fields
+        .iter()
+        .map(|field| {
+            let (field_type, cardinality) = parse_field_type(&field.ty, files)?;
+            Some(FieldDefinition {
+                field_name: field.ident.as_ref()?.to_string(),
+                field_type,
+                widget: None,
+                cardinality,
+                linked_from: None,
+                constraints: FieldConstraints::default(),
+            })
+        })
+        .collect()
+}
+
+/// Sets the widgets of the fields of the given struct type, or of the variants of the given enum type,
+/// and of the types nested in them, to the ones that render them in the create component
+fn find_struct_widgets(
+    field_type: &mut FieldType,
+    template_file_tree: &FileTree,
+    create_component: &str,
+) {
+    let fields: Vec<&mut FieldDefinition> = match field_type {
+        FieldType::Struct { fields, .. } => fields.iter_mut().collect(),
+        FieldType::Enum { variants, .. } => variants
+            .iter_mut()
+            .flat_map(|variant| variant.fields.iter_mut())
+            .collect(),
+        _ => vec![],
+    };
+
+    for field_def in fields {
+        find_struct_widgets(
+            &mut field_def.field_type,
+            template_file_tree,
+            create_component,
+        );
+        field_def.widget = find_widget(
+            template_file_tree,
+            create_component,
+            &field_def.field_name,
+            &field_def.field_type,
+        );
+    }
+}
+
+/// Finds what the given field links from, by looking for the link that the create function creates from its value
+fn find_linked_from(
+    create_fn: &syn::ItemFn,
+    snake_entry_type: &str,
+    field_name: &str,
+    field_type: &FieldType,
+    referenceable: &Referenceable,
+) -> Option<Referenceable> {
+    let link_types: Vec<String> = create_fn
+        .block
+        .stmts
+        .iter()
+        .map(|stmt| stmt.to_token_stream())
+        .filter(|tokens| mentions_field(tokens.clone(), snake_entry_type, field_name))
+        .flat_map(link_types_in)
+        .collect();
+
+    // Link types for linked from fields are named "<FROM>To<ENTRY_TYPE_PLURAL>"
+    let suffix = format!(
+        "To{}",
+        pluralizer::pluralize(snake_entry_type, 2, false).to_case(Case::Pascal)
+    );
+
+    link_types.into_iter().find_map(|link_type| {
+        let from = link_type.strip_suffix(&suffix)?;
+
+        let linked_from = match field_type {
+            FieldType::AgentPubKey => Referenceable::Agent {
+                role: from.to_case(Case::Snake),
+            },
+            FieldType::ActionHash | FieldType::EntryHash => {
+                Referenceable::EntryType(EntryTypeReference {
+                    entry_type: from.to_string(),
+                    reference_entry_hash: matches!(field_type, FieldType::EntryHash),
+                })
+            }
+            _ => return None,
+        };
+
+        link_type_name(&linked_from, referenceable)
+            .eq(&link_type)
+            .then_some(linked_from)
+    })
+}
+
+/// Reads the constraints of the given field back from the checks that were scaffolded for them in the given function
+fn find_constraints(validate_create_fn: &syn::ItemFn, field_name: &str) -> FieldConstraints {
+    let mut constraints = FieldConstraints::default();
+    let entry_arg = match typed_args(validate_create_fn).last() {
+        Some(pat_ident) => pat_ident.ident.to_string(),
+        None => return constraints,
+    };
+
+    let mut conditions: Vec<TokenStream> = vec![];
+    for stmt in &validate_create_fn.block.stmts {
+        if mentions_field(stmt.to_token_stream(), &entry_arg, field_name) {
+            collect_conditions(stmt, &mut conditions);
+        }
+    }
+
+    let length_regex = Regex::new(r"(chars \(\) \. count \(\)|len \(\)) ([<>]) (\d+)\z").unwrap();
+    let number_regex = Regex::new(r"([<>]) (- )?([0-9][0-9._eE]*)\z").unwrap();
+
+    for condition in conditions {
+        let condition_str = condition.to_string();
+
+        if condition_str.contains("is_match") {
+            constraints.pattern = first_string_literal(condition).map(|pattern| {
+                pattern
+                    .strip_prefix("^(?:")
+                    .and_then(|p| p.strip_suffix(")$"))
+                    .unwrap_or(&pattern)
+                    .to_string()
+            });
+        } else if condition_str.ends_with("is_empty ()") {
+            constraints.non_empty = true;
+        } else if let Some(captures) = length_regex.captures(&condition_str) {
+            let length = captures[3].parse::<usize>().ok();
+            match &captures[2] {
+                "<" => constraints.min_length = length,
+                _ => constraints.max_length = length,
+            }
+        } else if let Some(captures) = number_regex.captures(&condition_str) {
+            let number = format!(
+                "{}{}",
+                captures.get(2).map(|_| "-").unwrap_or_default(),
+                &captures[3]
+            );
+            match &captures[1] {
+                "<" => constraints.min = Some(number),
+                _ => constraints.max = Some(number),
+            }
+        }
+    }
+
+    constraints
+}
+
+// Collects the conditions of the checks in the given statement, including the ones nested in "if let" and "for" blocks
+fn collect_conditions(stmt: &syn::Stmt, conditions: &mut Vec<TokenStream>) {
+    let nested_stmts = match stmt {
+        syn::Stmt::Expr(syn::Expr::If(expr_if), _) => match &*expr_if.cond {
+            syn::Expr::Let(_) => &expr_if.then_branch.stmts,
+            cond => {
+                conditions.push(cond.to_token_stream());
+                return;
+            }
+        },
+        syn::Stmt::Expr(syn::Expr::ForLoop(expr_for), _) => &expr_for.body.stmts,
+        _ => return,
+    };
+
+    for nested_stmt in nested_stmts {
+        collect_conditions(nested_stmt, conditions);
+    }
+}
+
+fn first_string_literal(tokens: TokenStream) -> Option<String> {
+    tokens.into_iter().find_map(|token| match token {
+        TokenTree::Literal(literal) => syn::parse_str::<syn::LitStr>(&literal.to_string())
+            .ok()
+            .map(|lit_str| lit_str.value()),
+        TokenTree::Group(group) => first_string_literal(group.stream()),
+        _ => None,
+    })
+}
+
+/// Whether the given tokens access the given field of the given binding, as in "<BINDING>.<FIELD>"
+fn mentions_field(tokens: TokenStream, binding: &str, field_name: &str) -> bool {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+    tokens.iter().enumerate().any(|(i, token)| match token {
+        TokenTree::Group(group) => mentions_field(group.stream(), binding, field_name),
+        TokenTree::Ident(ident) if ident.eq(binding) => matches!(
+            (tokens.get(i + 1), tokens.get(i + 2)),
+            (Some(TokenTree::Punct(p)), Some(TokenTree::Ident(ident)))
+                if p.as_char() == '.' && ident.eq(field_name)
+        ),
+        _ => false,
+    })
+}
+
+/// The link types used in the given tokens, as in "LinkTypes::<LINK_TYPE>"
+fn link_types_in(tokens: TokenStream) -> Vec<String> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+    tokens
+        .iter()
+        .enumerate()
+        .flat_map(|(i, token)| match token {
+            TokenTree::Group(group) => link_types_in(group.stream()),
+            TokenTree::Ident(ident) if ident.eq("LinkTypes") => {
+                match (tokens.get(i + 1), tokens.get(i + 2), tokens.get(i + 3)) {
+                    (
+                        Some(TokenTree::Punct(p1)),
+                        Some(TokenTree::Punct(p2)),
+                        Some(TokenTree::Ident(link_type)),
+                    ) if p1.as_char() == ':' && p2.as_char() == ':' => vec![link_type.to_string()],
+                    _ => vec![],
+                }
+            }
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// The content of the component that the templates generated to create entries of the given type, if any
+fn read_create_component(
+    file_tree: &FileTree,
+    dna_name: &str,
+    coordinator_zome_name: &str,
+    pascal_entry_type: &str,
+) -> Option<String> {
+    let folder_path = PathBuf::from("ui/src")
+        .join(dna_name)
+        .join(coordinator_zome_name);
+    let name = format!("create{}", pascal_entry_type.to_lowercase());
+
+    dir_content(file_tree, &folder_path)
+        .ok()?
+        .into_keys()
+        .find(|file_name| normalized_file_name(&file_name.to_string_lossy()).eq(&name))
+        .and_then(|file_name| file_content(file_tree, &folder_path.join(file_name)).ok())
+}
+
+/// Finds the widget that renders the given field in the create component
+///
+/// The element that each widget able to render the field type starts with is looked for in the component,
+/// and the widget is the one whose element is labeled with the field name or bound to its variable
+fn find_widget(
+    template_file_tree: &FileTree,
+    create_component: &str,
+    field_name: &str,
+    field_type: &FieldType,
+) -> Option<String> {
+    let widgets_path = PathBuf::from("field-types").join(field_type.to_string());
+    let widgets = dir_content(template_file_tree, &widgets_path).ok()?;
+
+    let label = format!("label=\"{}\"", field_name.to_case(Case::Title));
+    let variable = Regex::new(&format!(
+        r"(^|[^A-Za-z0-9]){}($|[^A-Za-z0-9_])",
+        field_name.to_case(Case::Camel)
+    ))
+    .ok()?;
+    let tag_regex = Regex::new(r"<([A-Za-z][A-Za-z0-9-]*)").ok()?;
+
+    widgets
+        .into_iter()
+        .filter(|(_name, content)| content.dir_content().is_some())
+        .map(|(name, _content)| name.to_string_lossy().to_string())
+        .find(|widget| {
+            let render = match file_content(
+                template_file_tree,
+                &widgets_path.join(widget).join("edit").join("render.hbs"),
+            ) {
+                Ok(render) => render,
+                Err(_) => return false,
+            };
+            let tag = match tag_regex.captures(&render).and_then(|c| c.get(1)) {
+                Some(tag) => tag.as_str().to_string(),
+                None => return false,
+            };
+            let closing_tag = format!("</{}", tag);
+
+            create_component
+                .match_indices(&format!("<{}", tag))
+                .any(|(start, _)| {
+                    let element = &create_component[start..];
+                    let end = element
+                        .find(&closing_tag)
+                        .or_else(|| element.find('\n'))
+                        .unwrap_or(element.len());
+                    let element = &element[..end];
+
+                    element.contains(&label) || variable.is_match(element)
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(
+        ty: syn::Type,
+        files: &BTreeMap<PathBuf, syn::File>,
+    ) -> Option<(FieldType, Cardinality)> {
+        parse_field_type(&ty, files)
+    }
+
+    #[test]
+    fn test_parse_field_type() {
+        let files = BTreeMap::new();
+
+        assert!(matches!(
+            parse(syn::parse_quote! { String }, &files),
+            Some((FieldType::String, Cardinality::Single))
+        ));
+        assert!(matches!(
+            parse(syn::parse_quote! { Option<ActionHash> }, &files),
+            Some((FieldType::ActionHash, Cardinality::Option))
+        ));
+        assert!(matches!(
+            parse(syn::parse_quote! { Vec<u32> }, &files),
+            Some((FieldType::U32, Cardinality::Vector))
+        ));
+        assert!(matches!(
+            parse(syn::parse_quote! { BTreeMap<String, f64> }, &files),
+            Some((
+                FieldType::F64,
+                Cardinality::Map {
+                    key: FieldType::String
+                }
+            ))
+        ));
+
+        assert!(parse(syn::parse_quote! { BTreeMap<u32, f64> }, &files).is_none());
+        assert!(parse(syn::parse_quote! { Vec<Vec<String>> }, &files).is_none());
+        assert!(parse(syn::parse_quote! { (String, u32) }, &files).is_none());
+        assert!(parse(syn::parse_quote! { Unknown }, &files).is_none());
+    }
+
+    #[test]
+    fn test_parse_enum_and_struct_field_types() {
+        let files = BTreeMap::from([(
+            PathBuf::from("post.rs"),
+            syn::parse_quote! {
+                pub enum Status {
+                    Draft,
+                    Rejected { reason: String },
+                }
+                pub struct Location {
+                    pub latitude: f64,
+                    pub longitude: f64,
+                }
+                pub struct Unsupported {
+                    pub pair: (String, u32),
+                }
+            },
+        )]);
+
+        match parse(syn::parse_quote! { Status }, &files) {
+            Some((FieldType::Enum { label, variants }, Cardinality::Single)) => {
+                assert_eq!(label, "Status");
+                assert_eq!(variants.len(), 2);
+                assert_eq!(variants[1].fields[0].field_name, "reason");
+            }
+            other => panic!("unexpected field type {:?}", other),
+        }
+        match parse(syn::parse_quote! { Vec<Location> }, &files) {
+            Some((FieldType::Struct { label, fields }, Cardinality::Vector)) => {
+                assert_eq!(label, "Location");
+                assert_eq!(fields.len(), 2);
+            }
+            other => panic!("unexpected field type {:?}", other),
+        }
+        assert!(parse(syn::parse_quote! { Unsupported }, &files).is_none());
+    }
+}
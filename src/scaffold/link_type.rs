@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use std::{ffi::OsString, path::PathBuf};
+
+use build_fs_tree::dir;
 
 use convert_case::{Case, Casing};
 use dialoguer::{theme::ColorfulTheme, Confirm, Select};
@@ -7,24 +9,29 @@ use crate::{
     error::{ScaffoldError, ScaffoldResult},
     file_tree::{insert_file, map_file, FileTree},
     templates::{link_type::scaffold_link_type_templates, ScaffoldedTemplate},
-    utils::input_with_case,
+    utils::{check_can_prompt, input_with_case},
 };
 
 use self::{
-    coordinator::add_link_type_functions_to_coordinator, integrity::add_link_type_to_integrity_zome,
+    coordinator::add_link_type_functions_to_coordinator, index::scaffold_index_link_type,
+    integrity::add_link_type_to_integrity_zome,
 };
 
 use super::{
     app::AppFileTree,
     dna::DnaFileTree,
     entry_type::{
-        definitions::{Cardinality, Referenceable},
+        check_struct_fields,
+        definitions::{Cardinality, EntryTypeReference, FieldDefinition, FieldType, Referenceable},
+        fields::widgets_that_can_render,
+        integrity::check_entry_type_is_public,
         utils::{get_or_choose_optional_reference_type, get_or_choose_referenceable},
     },
-    zome::{utils::get_coordinator_zomes_for_integrity, ZomeFileTree},
+    zome::{utils::get_or_choose_coordinator_zome_for_integrity, ZomeFileTree},
 };
 
 pub mod coordinator;
+pub mod index;
 pub mod integrity;
 
 pub fn link_type_name(
@@ -48,13 +55,59 @@ pub fn link_type_name(
     )
 }
 
+/// The typed tag of the given link type, a struct with the given fields that is serialized into the tag of its links
+///
+/// It can only be rendered in the UI if all its fields have a widget and none of them are vectors or maps
+pub fn link_tag(
+    link_type_name: &String,
+    fields: &[FieldDefinition],
+    template_file_tree: &FileTree,
+) -> ScaffoldResult<FieldDefinition> {
+    let field_type = FieldType::Struct {
+        label: format!("{}Tag", link_type_name.to_case(Case::Pascal)),
+        fields: fields.to_vec(),
+    };
+
+    let v: Vec<OsString> = PathBuf::from("field-types")
+        .iter()
+        .map(|s| s.to_os_string())
+        .collect();
+    let empty_dir = dir! {};
+    let widget = match field_type.can_be_rendered() {
+        true => widgets_that_can_render(
+            &field_type,
+            template_file_tree.path(&mut v.iter()).unwrap_or(&empty_dir),
+        )
+        .into_iter()
+        .next(),
+        false => None,
+    };
+
+    let tag = FieldDefinition {
+        field_name: String::from("tag"),
+        field_type,
+        widget,
+        cardinality: Cardinality::Single,
+        linked_from: None,
+        constraints: Default::default(),
+    };
+    check_struct_fields(&tag)?;
+
+    Ok(tag)
+}
+
 pub fn scaffold_link_type(
     zome_file_tree: ZomeFileTree,
     template_file_tree: &FileTree,
     from_referenceable: &Option<Referenceable>,
     to_referenceable: &Option<Referenceable>,
     delete: &Option<bool>,
+    only_creator_can_delete: &Option<bool>,
     bidireccional: &Option<bool>,
+    tag_fields: &Option<Vec<FieldDefinition>>,
+    anchor_field: &Option<String>,
+    max_links: &Option<usize>,
+    coordinator_zome: &Option<String>,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
     let zome_manifest = zome_file_tree.zome_manifest.clone();
@@ -63,14 +116,47 @@ pub fn scaffold_link_type(
         &zome_file_tree,
         from_referenceable,
         &String::from("Link from which entry type?"),
+        "<FROM_REFERENCEABLE>",
     )?;
 
+    if let Some(anchor_field) = anchor_field {
+        let Referenceable::EntryType(entry_type) = from_referenceable else {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "only entry types can be indexed by the value of one of their fields",
+            )));
+        };
+        if to_referenceable.is_some()
+            || delete.is_some()
+            || only_creator_can_delete.is_some()
+            || bidireccional.is_some()
+            || tag_fields.is_some()
+            || max_links.is_some()
+        {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "the links of an index always go from its anchors to the indexed entries: \"--anchor-field\" can't be combined with a target entry type, \"--delete\", \"--only-creator-can-delete\", \"--bidireccional\", \"--tag-fields\" or \"--max-links\"",
+            )));
+        }
+
+        return scaffold_index_link_type(
+            zome_file_tree,
+            template_file_tree,
+            &entry_type,
+            anchor_field,
+            coordinator_zome,
+        );
+    }
+
     let to_referenceable = get_or_choose_optional_reference_type(
         &zome_file_tree,
         to_referenceable,
         &String::from("Link to which entry type?"),
+        "<TO_REFERENCEABLE>",
     )?;
 
+    if let Some(Referenceable::EntryType(to_entry_type)) = &to_referenceable {
+        check_entry_type_is_public(&zome_file_tree, &to_entry_type.entry_type, "linked to")?;
+    }
+
     let link_type = match to_referenceable.clone() {
         Some(to_referenceable) => link_type_name(&from_referenceable, &to_referenceable),
         None => input_with_case(&String::from("Enter link type name:"), Case::Pascal)?,
@@ -79,31 +165,116 @@ pub fn scaffold_link_type(
     let bidireccional = match (&to_referenceable, bidireccional) {
         (None, _) => false,
         (_, Some(b)) => b.clone(),
-        (_, None) => Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Should the link be bidireccional?")
-            .interact()?,
+        (_, None) => {
+            check_can_prompt(
+                "whether the link is bidireccional",
+                "--bidireccional <true|false>",
+            )?;
+
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Should the link be bidireccional?")
+                .interact()?
+        }
     };
+
+    // Bidireccional links also point back to the entries they are created from
+    if let (true, Referenceable::EntryType(from_entry_type)) = (bidireccional, &from_referenceable)
+    {
+        check_entry_type_is_public(&zome_file_tree, &from_entry_type.entry_type, "linked to")?;
+    }
     let delete = match delete {
         Some(d) => d.clone(),
-        None => Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Can the link be deleted?")
-            .interact()?,
+        None => {
+            check_can_prompt("whether the link can be deleted", "--delete <true|false>")?;
+
+            Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Can the link be deleted?")
+                .interact()?
+        }
+    };
+    let only_creator_can_delete = match delete {
+        true => match only_creator_can_delete {
+            Some(o) => *o,
+            None => {
+                check_can_prompt(
+                    "whether only the creator of a link can delete it",
+                    "--only-creator-can-delete <true|false>",
+                )?;
+
+                let selection = Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Who should be able to delete the links of this type?")
+                    .default(0)
+                    .item("Only their creator (recommended)")
+                    .item("Any agent")
+                    .interact()?;
+
+                selection == 0
+            }
+        },
+        false => false,
     };
 
-    // 1. Create an LINK_TYPE_NAME.rs in "src/", with the link type validation
+    if let Some(max_links) = max_links {
+        if to_referenceable.is_none() {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "\"--max-links\" needs a target entry type to link to",
+            )));
+        }
+        if !delete {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "the links over \"--max-links\" are replaced by deleting the oldest ones, so the link type needs to be deletable",
+            )));
+        }
+        if !only_creator_can_delete {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "the links of a link type with \"--max-links\" are counted from the deletes in the source chain of the owner of their base, so \"--only-creator-can-delete\" must be true",
+            )));
+        }
+        if let Referenceable::EntryType(EntryTypeReference {
+            reference_entry_hash: true,
+            ..
+        }) = from_referenceable
+        {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "only the author of the base can create the links of a link type with \"--max-links\", so its base must be an agent or an entry type referenced by its action hash",
+            )));
+        }
+        if *max_links == 0 {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "\"--max-links\" must be at least 1",
+            )));
+        }
+    }
+
+    let tag = match tag_fields {
+        Some(fields) => Some(link_tag(&link_type, fields, template_file_tree)?),
+        None => None,
+    };
+    let tag_type = tag
+        .as_ref()
+        .map(|tag| tag.field_type.rust_type().to_string());
+
+    // 1. Create an LINK_TYPE_NAME.rs in "src/", with the link type validation and its typed tag
     let crate_src_path = zome_file_tree.zome_crate_path.join("src");
 
     let link_type_file_name = PathBuf::from(format!("{}.rs", link_type.to_case(Case::Snake)));
 
     let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
 
+    let tag_definition = tag
+        .as_ref()
+        .and_then(|tag| tag.field_type.rust_type_definition())
+        .map(|definition| definition.to_string())
+        .unwrap_or_default();
+
     insert_file(
         &mut file_tree,
         &crate_src_path.join(&link_type_file_name),
         &format!(
             "use hdi::prelude::*;
 
-"
+{}",
+            tag_definition
         ),
     )?;
 
@@ -132,7 +303,10 @@ pub use {}::*;
         &Some(from_referenceable.clone()),
         &to_referenceable,
         delete,
+        only_creator_can_delete,
+        *max_links,
         &link_type_file_name,
+        &tag_type,
     )?;
 
     let inverse_link_type = if bidireccional {
@@ -144,7 +318,10 @@ pub use {}::*;
                 &to_referenceable,
                 &Some(from_referenceable.clone()),
                 delete,
+                only_creator_can_delete,
+                None,
                 &link_type_file_name,
+                &tag_type,
             )?;
             Some(inverse_link_type)
         } else {
@@ -156,33 +333,12 @@ pub use {}::*;
 
     let integrity_zome_name = zome_file_tree.zome_manifest.name.0.to_string();
 
-    let coordinator_zomes_for_integrity = get_coordinator_zomes_for_integrity(
+    let coordinator_zome = get_or_choose_coordinator_zome_for_integrity(
         &zome_file_tree.dna_file_tree.dna_manifest,
-        &zome_file_tree.zome_manifest.name.0.to_string(),
-    );
-
-    let coordinator_zome = match coordinator_zomes_for_integrity.len() {
-        0 => Err(ScaffoldError::NoCoordinatorZomesFoundForIntegrityZome(
-            zome_file_tree.dna_file_tree.dna_manifest.name(),
-            zome_file_tree.zome_manifest.name.0.to_string(),
-        )),
-        1 => Ok(coordinator_zomes_for_integrity[0].clone()),
-        _ => {
-            let names: Vec<String> = coordinator_zomes_for_integrity
-                .iter()
-                .map(|z| z.name.0.to_string())
-                .collect();
-            let selection = Select::with_theme(&ColorfulTheme::default())
-                .with_prompt(
-                    "Which coordinator zome should the link type functions be scaffolded in?",
-                )
-                .default(0)
-                .items(&names[..])
-                .interact()?;
-
-            Ok(coordinator_zomes_for_integrity[selection].clone())
-        }
-    }?;
+        &integrity_zome_name,
+        coordinator_zome,
+        "Which coordinator zome should the link type functions be scaffolded in?",
+    )?;
 
     let dna_manifest = zome_file_tree.dna_file_tree.dna_manifest.clone();
 
@@ -197,6 +353,8 @@ pub use {}::*;
         &to_referenceable,
         delete,
         bidireccional,
+        &tag_type,
+        *max_links,
     )?;
 
     let app_file_tree =
@@ -214,6 +372,11 @@ pub use {}::*;
         &from_referenceable,
         &to_referenceable,
         delete,
+        only_creator_can_delete,
         &inverse_link_type,
+        &tag,
+        &None,
+        &None,
+        *max_links,
     )
 }
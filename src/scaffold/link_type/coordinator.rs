@@ -16,6 +16,7 @@ fn metadata_handlers(
     integrity_zome_name: &String,
     link_type_name: &String,
     from_referenceable: &Referenceable,
+    tag: &Option<String>,
 ) -> String {
     let snake_from_arg = from_referenceable
         .field_name(&Cardinality::Single)
@@ -33,6 +34,27 @@ fn metadata_handlers(
         pluralizer::pluralize(&link_type_name.to_case(Case::Snake), 2, false);
     let pascal_link_type_name = link_type_name.to_case(Case::Pascal);
 
+    // Typed tags are serialized into the link tag, instead of storing the raw string
+    let (tag_type, create_tag, decode_tag) = match tag {
+        Some(tag) => (
+            tag.clone(),
+            format!(
+                "LinkTag::new(ExternIO::encode(input.{snake_link_type_name}).map_err(|e| wasm_error!(e))?.0)"
+            ),
+            format!(
+                "ExternIO(link.tag.into_inner()).decode::<{tag}>().map_err(|e| wasm_error!(e))"
+            ),
+        ),
+        None => (
+            String::from("String"),
+            format!("input.{snake_link_type_name}"),
+            String::from(
+                r#"String::from_utf8(link.tag.into_inner())
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Error converting link tag to string: {:?}", e))))"#,
+            ),
+        ),
+    };
+
     format!(
         r#"use hdk::prelude::*;
 use {integrity_zome_name}::*;
@@ -40,26 +62,25 @@ use {integrity_zome_name}::*;
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Add{pascal_link_type_name}For{pascal_from}Input {{
     pub {snake_from_arg}: {from_arg_type},
-    pub {snake_link_type_name}: String,
+    pub {snake_link_type_name}: {tag_type},
 }}
 #[hdk_extern]
 pub fn add_{snake_link_type_name}_for_{snake_from}(input: Add{pascal_link_type_name}For{pascal_from}Input) -> ExternResult<()> {{
-    create_link(input.{snake_from_arg}.clone(), input.{snake_from_arg}, LinkTypes::{pascal_link_type_name}, input.{snake_link_type_name})?;
+    create_link(input.{snake_from_arg}.clone(), input.{snake_from_arg}, LinkTypes::{pascal_link_type_name}, {create_tag})?;
 
     Ok(())    
 }}
 
 #[hdk_extern]
-pub fn get_{plural_snake_link_type_name}_for_{snake_from}({snake_from_arg}: {from_arg_type}) -> ExternResult<Vec<String>> {{
+pub fn get_{plural_snake_link_type_name}_for_{snake_from}({snake_from_arg}: {from_arg_type}) -> ExternResult<Vec<{tag_type}>> {{
     let links = get_links({snake_from_arg}, LinkTypes::{pascal_link_type_name}, None)?;
     
-    let {snake_link_type_name}: Vec<String> = links
+    let {snake_link_type_name}: Vec<{tag_type}> = links
         .into_iter()
         .map(|link| 
-          String::from_utf8(link.tag.into_inner())
-            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Error converting link tag to string: {{:?}}", e))))
+          {decode_tag}
         )
-        .collect::<ExternResult<Vec<String>>>()?;
+        .collect::<ExternResult<Vec<{tag_type}>>>()?;
 
     Ok({snake_link_type_name})
 }}"#
@@ -70,6 +91,8 @@ pub fn add_link_handler(
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
     bidireccional: bool,
+    tag: &Option<String>,
+    max_links: Option<usize>,
 ) -> String {
     let from_hash_type = from_referenceable.hash_type().to_string();
     let from_arg_name = from_referenceable.field_name(&Cardinality::Single);
@@ -91,22 +114,78 @@ pub fn add_link_handler(
         .to_string(&Cardinality::Single)
         .to_case(Case::Pascal);
 
+    // Typed tags are serialized into the link tag, which both directions of a bidireccional link share
+    let (tag_input_field, encode_tag, link_tag, inverse_link_tag) = match tag {
+        Some(tag) => (
+            format!(
+                r#"
+    pub tag: {tag},"#
+            ),
+            String::from(
+                r#"let tag = LinkTag::new(ExternIO::encode(input.tag).map_err(|e| wasm_error!(e))?.0);
+    "#,
+            ),
+            match bidireccional {
+                true => "tag.clone()",
+                false => "tag",
+            },
+            "tag",
+        ),
+        None => (String::new(), String::new(), "()", "()"),
+    };
+
     let bidireccional_create = match bidireccional {
         true => format!(
-            r#"create_link(input.target_{to_arg_name}, input.base_{from_arg_name}, LinkTypes::{inverse_link_type_name}, ())?;"#
+            r#"create_link(input.target_{to_arg_name}, input.base_{from_arg_name}, LinkTypes::{inverse_link_type_name}, {inverse_link_tag})?;"#
         ),
         false => format!(""),
     };
 
+    // When the base already has the maximum number of links, the oldest ones get replaced
+    // Only its owner can create them, but other agents could have created inverse links to the base
+    let replace_links = match max_links {
+        Some(max_links) => {
+            let bidireccional_replace = match bidireccional {
+                true => format!(
+                    r#"
+        let inverse_links = get_links(link.target.clone(), LinkTypes::{inverse_link_type_name}, None)?;
+        for inverse_link in inverse_links {{
+            if inverse_link.author.eq(&my_pub_key) && inverse_link.target.eq(&AnyLinkableHash::from(input.base_{from_arg_name}.clone())) {{
+                delete_link(inverse_link.create_link_hash)?;
+            }}
+        }}"#
+                ),
+                false => String::new(),
+            };
+
+            let my_pub_key = match bidireccional {
+                true => "let my_pub_key = agent_info()?.agent_initial_pubkey;\n    ",
+                false => "",
+            };
+
+            format!(
+                r#"{my_pub_key}let mut links = get_links(input.base_{from_arg_name}.clone(), LinkTypes::{normal_link_type_name}, None)?;
+    links.sort_by_key(|link| link.timestamp);
+    let links_to_replace = (links.len() + 1).saturating_sub({max_links});
+    for link in links.into_iter().take(links_to_replace) {{
+        delete_link(link.create_link_hash)?;{bidireccional_replace}
+    }}
+
+    "#
+            )
+        }
+        None => String::new(),
+    };
+
     format!(
         r#"#[derive(Serialize, Deserialize, Debug)]
 pub struct Add{singular_pascal_to_entry_type}For{singular_pascal_from_entry_type}Input {{
     pub base_{from_arg_name}: {from_hash_type},
-    pub target_{to_arg_name}: {to_hash_type},
+    pub target_{to_arg_name}: {to_hash_type},{tag_input_field}
 }}
 #[hdk_extern]
 pub fn add_{singular_snake_to_entry_type}_for_{singular_snake_from_entry_type}(input: Add{singular_pascal_to_entry_type}For{singular_pascal_from_entry_type}Input) -> ExternResult<()> {{
-    create_link(input.base_{from_arg_name}.clone(), input.target_{to_arg_name}.clone(), LinkTypes::{normal_link_type_name}, ())?;
+    {replace_links}{encode_tag}create_link(input.base_{from_arg_name}.clone(), input.target_{to_arg_name}.clone(), LinkTypes::{normal_link_type_name}, {link_tag})?;
     {bidireccional_create}
 
     Ok(())    
@@ -118,12 +197,60 @@ pub fn get_links_handler(
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
     delete: bool,
+    tag: &Option<String>,
 ) -> String {
     match to_referenceable {
         Referenceable::Agent { .. } => {
-            get_links_handler_to_agent(from_referenceable, to_referenceable, delete)
+            get_links_handler_to_agent(from_referenceable, to_referenceable, delete, tag)
         }
-        Referenceable::EntryType(e) => get_links_handler_to_entry(from_referenceable, e, delete),
+        Referenceable::EntryType(e) => {
+            get_links_handler_to_entry(from_referenceable, e, delete, tag)
+        }
+    }
+}
+
+/// Renders the function that gets the links of the given link type, decoding their tags if it has a typed tag
+fn get_links_fn(
+    fn_name: &String,
+    from_arg_name: &String,
+    from_hash_type: &String,
+    pascal_link_type_name: &String,
+    tag: &Option<String>,
+) -> String {
+    match tag {
+        None => format!(
+            r#"#[hdk_extern]
+pub fn {fn_name}({from_arg_name}: {from_hash_type}) -> ExternResult<Vec<Link>> {{
+    get_links({from_arg_name}, LinkTypes::{pascal_link_type_name}, None)
+}}"#
+        ),
+        Some(tag) => format!(
+            r#"#[derive(Serialize, Deserialize, Debug)]
+pub struct {pascal_link_type_name}Link {{
+    pub target: AnyLinkableHash,
+    pub author: AgentPubKey,
+    pub timestamp: Timestamp,
+    pub create_link_hash: ActionHash,
+    pub tag: {tag},
+}}
+#[hdk_extern]
+pub fn {fn_name}({from_arg_name}: {from_hash_type}) -> ExternResult<Vec<{pascal_link_type_name}Link>> {{
+    let links = get_links({from_arg_name}, LinkTypes::{pascal_link_type_name}, None)?;
+
+    links
+        .into_iter()
+        .map(|link| {{
+            Ok({pascal_link_type_name}Link {{
+                tag: ExternIO(link.tag.into_inner()).decode().map_err(|e| wasm_error!(e))?,
+                target: link.target,
+                author: link.author,
+                timestamp: link.timestamp,
+                create_link_hash: link.create_link_hash,
+            }})
+        }})
+        .collect()
+}}"#
+        ),
     }
 }
 
@@ -131,6 +258,7 @@ fn get_links_handler_to_agent(
     from_referenceable: &Referenceable,
     to_referenceable: &Referenceable,
     delete: bool,
+    tag: &Option<String>,
 ) -> String {
     let from_hash_type = from_referenceable.hash_type().to_string();
     let from_arg_name = from_referenceable.field_name(&Cardinality::Single);
@@ -162,11 +290,16 @@ pub fn get_deleted_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_t
         format!("")
     };
 
+    let get_links_fn = get_links_fn(
+        &format!("get_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_type}"),
+        &from_arg_name,
+        &from_hash_type,
+        &pascal_link_type_name,
+        tag,
+    );
+
     format!(
-        r#"#[hdk_extern]
-pub fn get_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_type}({from_arg_name}: {from_hash_type}) -> ExternResult<Vec<Link>> {{
-    get_links({from_arg_name}, LinkTypes::{pascal_link_type_name}, None)
-}}
+        r#"{get_links_fn}
 {get_deleted_links_handler}
 "#,
     )
@@ -176,6 +309,7 @@ fn get_links_handler_to_entry(
     from_referenceable: &Referenceable,
     to_entry_type: &EntryTypeReference,
     delete: bool,
+    tag: &Option<String>,
 ) -> String {
     let from_hash_type = from_referenceable.hash_type().to_string();
     let from_arg_name = from_referenceable.field_name(&Cardinality::Single);
@@ -209,11 +343,16 @@ pub fn get_deleted_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_t
         false => format!(""),
     };
 
+    let get_links_fn = get_links_fn(
+        &format!("get_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_type}"),
+        &from_arg_name,
+        &from_hash_type,
+        &pascal_link_type_name,
+        tag,
+    );
+
     format!(
-        r#"#[hdk_extern]
-pub fn get_{plural_snake_to_entry_type}_for_{singular_snake_from_entry_type}({from_arg_name}: {from_hash_type}) -> ExternResult<Vec<Link>> {{
-    get_links({from_arg_name}, LinkTypes::{pascal_link_type_name}, None)
-}}
+        r#"{get_links_fn}
 {get_deleted_links_handler}
 "#,
     )
@@ -301,13 +440,15 @@ fn normal_handlers(
     to_referenceable: &Referenceable,
     delete: bool,
     bidireccional: bool,
+    tag: &Option<String>,
+    max_links: Option<usize>,
 ) -> String {
     let inverse_get = match bidireccional {
         true => format!(
             r#"
 
 {}"#,
-            get_links_handler(to_referenceable, from_referenceable, delete)
+            get_links_handler(to_referenceable, from_referenceable, delete, tag)
         ),
         false => format!(""),
     };
@@ -327,8 +468,14 @@ use {integrity_zome_name}::*;
 {}
         
 {}"#,
-        add_link_handler(from_referenceable, to_referenceable, bidireccional),
-        get_links_handler(from_referenceable, to_referenceable, delete),
+        add_link_handler(
+            from_referenceable,
+            to_referenceable,
+            bidireccional,
+            tag,
+            max_links,
+        ),
+        get_links_handler(from_referenceable, to_referenceable, delete, tag),
         inverse_get,
         delete_link_handler
     )
@@ -342,6 +489,8 @@ pub fn add_link_type_functions_to_coordinator(
     to_referenceable: &Option<Referenceable>,
     delete: bool,
     bidireccional: bool,
+    tag: &Option<String>,
+    max_links: Option<usize>,
 ) -> ScaffoldResult<ZomeFileTree> {
     let dna_manifest_path = coordinator_zome_file_tree
         .dna_file_tree
@@ -362,13 +511,15 @@ pub fn add_link_type_functions_to_coordinator(
     let mut file_tree = coordinator_zome_file_tree.dna_file_tree.file_tree();
 
     let handlers = match to_referenceable {
-        None => metadata_handlers(integrity_zome_name, link_type_name, from_referenceable),
+        None => metadata_handlers(integrity_zome_name, link_type_name, from_referenceable, tag),
         Some(r) => normal_handlers(
             integrity_zome_name,
             from_referenceable,
             r,
             delete,
             bidireccional,
+            tag,
+            max_links,
         ),
     };
 
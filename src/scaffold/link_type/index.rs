@@ -0,0 +1,569 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use convert_case::{Case, Casing};
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{insert_file, map_file, map_rust_files, FileTree},
+    scaffold::{
+        app::AppFileTree,
+        dna::DnaFileTree,
+        entry_type::{
+            constraints::{number_literal, LOREM_IPSUM},
+            definitions::{
+                Cardinality, EntryTypeReference, FieldDefinition, FieldType, Referenceable,
+            },
+            integrity::check_entry_type_is_public,
+        },
+        field::{entry_type_field, read_scaffolded_entry_type},
+        zome::{
+            coordinator::find_extern_function_in_zomes,
+            utils::{
+                get_coordinator_zomes_for_integrity, get_or_choose_coordinator_zome_for_integrity,
+            },
+            ZomeFileTree,
+        },
+    },
+    templates::{link_type::scaffold_link_type_templates, ScaffoldedTemplate},
+};
+
+use super::integrity::add_link_type_to_integrity_zome;
+
+/// The name of the link type that indexes the entries of the given entry type by the given field, eg. "PostsByTag"
+pub fn index_link_type_name(entry_type: &EntryTypeReference, anchor_field: &str) -> String {
+    format!(
+        "{}By{}",
+        entry_type
+            .to_string(&Cardinality::Vector)
+            .to_case(Case::Pascal),
+        anchor_field.to_case(Case::Pascal)
+    )
+}
+
+/// The name of the link type that links the root path of the index to each of its anchors
+pub fn anchors_link_type_name(index_link_type_name: &str) -> String {
+    format!("{}Anchors", index_link_type_name.to_case(Case::Pascal))
+}
+
+/// The expression that derives the anchor from the field of the given entry variable
+fn anchor_expr(
+    entry_variable: &str,
+    field_name: &str,
+    field_type: &FieldType,
+) -> ScaffoldResult<String> {
+    match field_type {
+        FieldType::String => Ok(format!("{}.{}.clone()", entry_variable, field_name)),
+        FieldType::Bool
+        | FieldType::U8
+        | FieldType::U32
+        | FieldType::I32
+        | FieldType::U64
+        | FieldType::I64 => Ok(format!("{}.{}.to_string()", entry_variable, field_name)),
+        FieldType::Enum { variants, .. } if variants.iter().all(|v| v.fields.is_empty()) => Ok(
+            format!("format!(\"{{:?}}\", {}.{})", entry_variable, field_name),
+        ),
+        _ => Err(ScaffoldError::InvalidArguments(format!(
+            "field \"{}\" can't be used as an anchor: only String, bool, integer and enum fields whose variants have no fields can",
+            field_name
+        ))),
+    }
+}
+
+/// A value of the anchor field, as a JavaScript literal, that is different from the one of the sample entry of the tests
+/// and still satisfies the constraints of the field, or None if the field can't be updated or no such value is known
+fn anchor_update_sample(field: &FieldDefinition) -> Option<String> {
+    if field.constraints.immutable {
+        return None;
+    }
+
+    match &field.field_type {
+        FieldType::Bool => Some(String::from("false")),
+        FieldType::Enum { variants, .. } => variants
+            .get(1)
+            .map(|variant| format!("{{ type: '{}' }}", variant.name)),
+        FieldType::String if field.constraints.pattern.is_none() => {
+            let sample = match field.constraints.string_sample(&field.cardinality) {
+                Some(literal) => serde_json::from_str::<String>(&literal).ok()?,
+                None => LOREM_IPSUM.to_string(),
+            };
+            // The reversed sample has the same length
+            let updated_sample: String = sample.chars().rev().collect();
+            match updated_sample.eq(&sample) {
+                true => None,
+                false => serde_json::to_string(&updated_sample).ok(),
+            }
+        }
+        FieldType::U8 | FieldType::U32 | FieldType::U64 | FieldType::I32 | FieldType::I64 => {
+            let min = match &field.constraints.min {
+                Some(min) => Some(min.parse::<i64>().ok()?),
+                None => None,
+            };
+            let max = match &field.constraints.max {
+                Some(max) => Some(max.parse::<i64>().ok()?),
+                None => None,
+            };
+            // The sample is the minimum, or else the maximum, or else a value other than 1 that depends on the template
+            let updated_sample = match (min, max) {
+                (Some(min), Some(max)) => Some(max).filter(|max| max.ne(&min)),
+                (Some(min), None) => Some(min + 1),
+                (None, Some(max)) => Some(max - 1),
+                (None, None) => Some(1),
+            };
+            updated_sample
+                .map(|updated_sample| updated_sample.to_string())
+                .filter(|updated_sample| {
+                    number_literal(updated_sample, &field.field_name, &field.field_type).is_ok()
+                })
+        }
+        _ => None,
+    }
+}
+
+/// The zome functions of the index, where the entries are removed from all the anchors of the index
+/// since their updates may have moved them to an anchor other than the one of their original value
+fn index_functions(
+    integrity_zome_name: &String,
+    entry_type: &EntryTypeReference,
+    link_type_name: &String,
+    anchor_field: &String,
+    anchor: &String,
+) -> String {
+    let snake_entry_type = entry_type.entry_type.to_case(Case::Snake);
+    let pascal_entry_type = entry_type.entry_type.to_case(Case::Pascal);
+    let snake_index = link_type_name.to_case(Case::Snake);
+    let anchors_link_type = anchors_link_type_name(link_type_name);
+    let snake_anchor_field = anchor_field.to_case(Case::Snake);
+    let hash_type = entry_type.hash_type().to_string();
+    let into_hash_fn = match entry_type.reference_entry_hash {
+        true => "into_entry_hash",
+        false => "into_action_hash",
+    };
+    let original_hash = match entry_type.reference_entry_hash {
+        true => String::from(
+            r#"action => action.entry_hash().cloned().ok_or(wasm_error!(WasmErrorInner::Guest(String::from("The action has no entry")))),"#,
+        ),
+        false => format!("_ => Ok({}_hash),", snake_entry_type),
+    };
+
+    format!(
+        r#"use hdk::prelude::*;
+use {integrity_zome_name}::*;
+
+fn {snake_index}_anchor({snake_anchor_field}: String) -> ExternResult<TypedPath> {{
+    Path::from(vec![Component::from("{snake_index}"), Component::from({snake_anchor_field})])
+        .typed(LinkTypes::{anchors_link_type})
+}}
+
+fn get_indexed_{snake_entry_type}<H>(hash: H) -> ExternResult<{pascal_entry_type}>
+where
+    AnyDhtHash: From<H>,
+{{
+    let record = get(hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Could not find the {pascal_entry_type}"))))?;
+
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("The record does not contain a {pascal_entry_type}"))))
+}}
+
+fn original_{snake_entry_type}_hash({snake_entry_type}_hash: ActionHash) -> ExternResult<{hash_type}> {{
+    let record = get({snake_entry_type}_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from("Could not find the {pascal_entry_type}"))))?;
+
+    match record.action() {{
+        Action::Update(update) => original_{snake_entry_type}_hash(update.original_action_address.clone()),
+        {original_hash}
+    }}
+}}
+
+fn add_{snake_entry_type}_to_{snake_index}_anchor({snake_anchor_field}: String, {snake_entry_type}_hash: {hash_type}) -> ExternResult<()> {{
+    let path = {snake_index}_anchor({snake_anchor_field})?;
+    path.ensure()?;
+    create_link(path.path_entry_hash()?, {snake_entry_type}_hash, LinkTypes::{link_type_name}, ())?;
+
+    Ok(())
+}}
+
+fn remove_{snake_entry_type}_from_{snake_index}_anchor({snake_anchor_field}: String, {snake_entry_type}_hash: {hash_type}) -> ExternResult<()> {{
+    let path = {snake_index}_anchor({snake_anchor_field})?;
+    let links = get_links(path.path_entry_hash()?, LinkTypes::{link_type_name}, None)?;
+    for link in links {{
+        if let Some(hash) = link.target.{into_hash_fn}() {{
+            if hash.eq(&{snake_entry_type}_hash) {{
+                delete_link(link.create_link_hash)?;
+            }}
+        }}
+    }}
+
+    Ok(())
+}}
+
+#[hdk_extern]
+pub fn add_{snake_entry_type}_to_{snake_index}({snake_entry_type}_hash: {hash_type}) -> ExternResult<()> {{
+    let {snake_entry_type} = get_indexed_{snake_entry_type}({snake_entry_type}_hash.clone())?;
+
+    add_{snake_entry_type}_to_{snake_index}_anchor({anchor}, {snake_entry_type}_hash)
+}}
+
+#[hdk_extern]
+pub fn remove_{snake_entry_type}_from_{snake_index}({snake_entry_type}_hash: {hash_type}) -> ExternResult<()> {{
+    for {snake_anchor_field} in get_{snake_index}_anchors(())? {{
+        remove_{snake_entry_type}_from_{snake_index}_anchor({snake_anchor_field}, {snake_entry_type}_hash.clone())?;
+    }}
+
+    Ok(())
+}}
+
+pub fn update_{snake_entry_type}_in_{snake_index}(
+    previous_{snake_entry_type}_hash: ActionHash,
+    updated_{snake_entry_type}: &{pascal_entry_type},
+) -> ExternResult<()> {{
+    let {snake_entry_type} = get_indexed_{snake_entry_type}(previous_{snake_entry_type}_hash.clone())?;
+    let previous_anchor = {anchor};
+    let {snake_entry_type} = updated_{snake_entry_type};
+    let updated_anchor = {anchor};
+
+    if previous_anchor.eq(&updated_anchor) {{
+        return Ok(());
+    }}
+
+    let {snake_entry_type}_hash = original_{snake_entry_type}_hash(previous_{snake_entry_type}_hash)?;
+    remove_{snake_entry_type}_from_{snake_index}_anchor(previous_anchor, {snake_entry_type}_hash.clone())?;
+    add_{snake_entry_type}_to_{snake_index}_anchor(updated_anchor, {snake_entry_type}_hash)
+}}
+
+#[hdk_extern]
+pub fn get_{snake_index}_anchors(_: ()) -> ExternResult<Vec<String>> {{
+    let path = Path::from("{snake_index}").typed(LinkTypes::{anchors_link_type})?;
+
+    path.children_paths()?
+        .into_iter()
+        .filter_map(|path| path.leaf().cloned())
+        .map(|component| String::try_from(&component).map_err(|e| wasm_error!(e)))
+        .collect()
+}}
+
+#[hdk_extern]
+pub fn get_{snake_index}({snake_anchor_field}: String) -> ExternResult<Vec<Link>> {{
+    let path = {snake_index}_anchor({snake_anchor_field})?;
+
+    get_links(path.path_entry_hash()?, LinkTypes::{link_type_name}, None)
+}}
+"#,
+    )
+}
+
+/// Where the statements are inserted in the body of a zome function
+enum StatementsPosition {
+    Start,
+    BeforeReturn,
+}
+
+/// Inserts the given statements at the given position of the body of the given zome function
+fn insert_statements_in_fn(
+    zome_file_tree: ZomeFileTree,
+    fn_name: &syn::Ident,
+    new_stmts: Vec<String>,
+    position: StatementsPosition,
+) -> ScaffoldResult<ZomeFileTree> {
+    let stmts = new_stmts
+        .into_iter()
+        .map(|s| syn::parse_str::<syn::Stmt>(s.as_str()))
+        .collect::<Result<Vec<syn::Stmt>, syn::Error>>()?;
+
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    let v: Vec<OsString> = crate_src_path
+        .clone()
+        .iter()
+        .map(|s| s.to_os_string())
+        .collect();
+    map_rust_files(
+        file_tree
+            .path_mut(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        |_file_path, mut file| {
+            for item in &mut file.items {
+                if let syn::Item::Fn(item_fn) = item {
+                    if item_fn
+                        .attrs
+                        .iter()
+                        .any(|a| a.path().segments.iter().any(|s| s.ident.eq("hdk_extern")))
+                        && item_fn.sig.ident.eq(fn_name)
+                    {
+                        let index = match position {
+                            StatementsPosition::Start => 0,
+                            StatementsPosition::BeforeReturn => item_fn.block.stmts.len() - 1,
+                        };
+                        item_fn.block.stmts.splice(index..index, stmts.clone());
+                    }
+                }
+            }
+
+            Ok(file)
+        },
+    )
+    .map_err(|e| match e {
+        ScaffoldError::MalformedFile(path, error) => {
+            ScaffoldError::MalformedFile(crate_src_path.join(&path), error)
+        }
+        _ => e,
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)
+}
+
+/// Scaffolds a link type that indexes the entries of the given entry type under path anchors derived from
+/// the value of the given field, eg. "posts by tag"
+///
+/// Each anchor is a child of the root path of the index, so all of them can be listed. The zome functions
+/// to add and remove an entry from the index are called when the entry is created and deleted, and updates
+/// move the entry to the anchor of the new value of the field
+pub fn scaffold_index_link_type(
+    zome_file_tree: ZomeFileTree,
+    template_file_tree: &FileTree,
+    entry_type: &EntryTypeReference,
+    anchor_field: &String,
+    coordinator_zome: &Option<String>,
+) -> ScaffoldResult<ScaffoldedTemplate> {
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+    let zome_manifest = zome_file_tree.zome_manifest.clone();
+    let integrity_zome_name = zome_manifest.name.0.to_string();
+    let snake_entry_type = entry_type.entry_type.to_case(Case::Snake);
+    check_entry_type_is_public(&zome_file_tree, &entry_type.entry_type, "indexed")?;
+
+    let (field_type, cardinality) =
+        entry_type_field(&zome_file_tree, &entry_type.entry_type, anchor_field)?.ok_or(
+            ScaffoldError::InvalidArguments(format!(
+                "entry type \"{}\" has no field \"{}\"",
+                entry_type.entry_type, anchor_field
+            )),
+        )?;
+    if !matches!(cardinality, Cardinality::Single) {
+        return Err(ScaffoldError::InvalidArguments(format!(
+            "field \"{}\" can't be used as an anchor: it must hold a single value",
+            anchor_field
+        )));
+    }
+    let anchor = anchor_expr(&snake_entry_type, anchor_field, &field_type)?;
+
+    // Only the tests use the entry type as it was scaffolded, to update an entry to another anchor
+    let anchor_update_sample =
+        read_scaffolded_entry_type(&zome_file_tree, template_file_tree, &entry_type.entry_type)
+            .ok()
+            .filter(|scaffolded_entry_type| scaffolded_entry_type.crud.update)
+            .and_then(|scaffolded_entry_type| {
+                scaffolded_entry_type
+                    .entry_def
+                    .fields
+                    .into_iter()
+                    .find(|field| field.field_name.eq(anchor_field))
+            })
+            .and_then(|field| anchor_update_sample(&field));
+
+    let link_type = index_link_type_name(entry_type, anchor_field);
+    let anchors_link_type = anchors_link_type_name(&link_type);
+
+    // 1. Create an LINK_TYPE_NAME.rs in "src/", with the validation of both link types
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let link_type_file_name = PathBuf::from(format!("{}.rs", link_type.to_case(Case::Snake)));
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    insert_file(
+        &mut file_tree,
+        &crate_src_path.join(&link_type_file_name),
+        &String::from("use hdi::prelude::*;\n"),
+    )?;
+
+    // 2. Add this file as a module in the entry point for the crate
+    map_file(&mut file_tree, &crate_src_path.join("lib.rs"), |s| {
+        format!(
+            r#"pub mod {};
+pub use {}::*;
+
+{}"#,
+            link_type.to_case(Case::Snake),
+            link_type.to_case(Case::Snake),
+            s
+        )
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+    let zome_file_tree = add_link_type_to_integrity_zome(
+        zome_file_tree,
+        &link_type,
+        &None,
+        &Some(Referenceable::EntryType(entry_type.clone())),
+        true,
+        false,
+        None,
+        &link_type_file_name,
+        &None,
+    )?;
+    let zome_file_tree = add_link_type_to_integrity_zome(
+        zome_file_tree,
+        &anchors_link_type,
+        &None,
+        &None,
+        false,
+        false,
+        None,
+        &link_type_file_name,
+        &None,
+    )?;
+
+    // 3. Add the index functions next to the zome functions of the entry type
+    let dna_file_tree = zome_file_tree.dna_file_tree;
+    let coordinator_zomes_for_integrity =
+        get_coordinator_zomes_for_integrity(&dna_file_tree.dna_manifest, &integrity_zome_name);
+
+    let create_fn_name = format!("create_{}", snake_entry_type);
+    let coordinator_zome = match (
+        coordinator_zome,
+        find_extern_function_in_zomes(
+            &dna_file_tree,
+            &coordinator_zomes_for_integrity,
+            &create_fn_name,
+        )?,
+    ) {
+        (None, Some((coordinator_zome, _))) => coordinator_zome,
+        _ => get_or_choose_coordinator_zome_for_integrity(
+            &dna_file_tree.dna_manifest,
+            &integrity_zome_name,
+            coordinator_zome,
+            "Which coordinator zome should the index functions be scaffolded in?",
+        )?,
+    };
+
+    let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome.clone())?;
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+
+    insert_file(
+        &mut file_tree,
+        &crate_src_path.join(&link_type_file_name),
+        &index_functions(
+            &integrity_zome_name,
+            entry_type,
+            &link_type,
+            anchor_field,
+            &anchor,
+        ),
+    )?;
+    map_file(&mut file_tree, &crate_src_path.join("lib.rs"), |s| {
+        format!(
+            r#"pub mod {};
+
+{}"#,
+            link_type.to_case(Case::Snake),
+            s
+        )
+    })?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    let mut zome_file_tree =
+        ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome.clone())?;
+
+    let snake_index = link_type.to_case(Case::Snake);
+    let new_hash = match entry_type.reference_entry_hash {
+        true => format!("hash_entry(&{})?", snake_entry_type),
+        false => format!("{}_hash.clone()", snake_entry_type),
+    };
+    if let Some((_, create_fn)) = find_extern_function_in_zomes(
+        &zome_file_tree.dna_file_tree,
+        &vec![coordinator_zome.clone()],
+        &create_fn_name,
+    )? {
+        zome_file_tree = insert_statements_in_fn(
+            zome_file_tree,
+            &create_fn.sig.ident,
+            vec![format!(
+                "crate::{snake_index}::add_{snake_entry_type}_to_{snake_index}({new_hash})?;"
+            )],
+            StatementsPosition::BeforeReturn,
+        )?;
+    }
+
+    let deleted_hash = match entry_type.reference_entry_hash {
+        true => format!(
+            r#"must_get_action(original_{snake_entry_type}_hash.clone())?.action().entry_hash().cloned().ok_or(wasm_error!(WasmErrorInner::Guest(String::from("The deleted action has no entry"))))?"#
+        ),
+        false => format!("original_{}_hash.clone()", snake_entry_type),
+    };
+    let deletable = match find_extern_function_in_zomes(
+        &zome_file_tree.dna_file_tree,
+        &vec![coordinator_zome.clone()],
+        &format!("delete_{}", snake_entry_type),
+    )? {
+        Some((_, delete_fn)) => {
+            zome_file_tree = insert_statements_in_fn(
+                zome_file_tree,
+                &delete_fn.sig.ident,
+                vec![format!(
+                    "crate::{snake_index}::remove_{snake_entry_type}_from_{snake_index}({deleted_hash})?;"
+                )],
+                StatementsPosition::BeforeReturn,
+            )?;
+            true
+        }
+        None => false,
+    };
+
+    // The update moves the entry to its new anchor before the previous entry is replaced
+    if let Some((_, update_fn)) = find_extern_function_in_zomes(
+        &zome_file_tree.dna_file_tree,
+        &vec![coordinator_zome.clone()],
+        &format!("update_{}", snake_entry_type),
+    )? {
+        zome_file_tree = insert_statements_in_fn(
+            zome_file_tree,
+            &update_fn.sig.ident,
+            vec![format!(
+                "crate::{snake_index}::update_{snake_entry_type}_in_{snake_index}(input.previous_{snake_entry_type}_hash.clone(), &input.updated_{snake_entry_type})?;"
+            )],
+            StatementsPosition::Start,
+        )?;
+    }
+
+    let dna_manifest = zome_file_tree.dna_file_tree.dna_manifest.clone();
+    let app_file_tree =
+        AppFileTree::get_or_choose(zome_file_tree.dna_file_tree.file_tree(), &None)?;
+    let app_name = app_file_tree.app_manifest.app_name().to_string();
+
+    let anchor_field = FieldDefinition {
+        field_name: anchor_field.clone(),
+        field_type,
+        widget: None,
+        cardinality,
+        linked_from: None,
+        constraints: Default::default(),
+    };
+
+    scaffold_link_type_templates(
+        app_file_tree.file_tree(),
+        template_file_tree,
+        &app_name,
+        &dna_manifest.name(),
+        &coordinator_zome,
+        &link_type,
+        &Referenceable::EntryType(entry_type.clone()),
+        &None,
+        deletable,
+        false,
+        &None,
+        &None,
+        &Some(anchor_field),
+        &anchor_update_sample,
+        None,
+    )
+}
@@ -57,7 +57,10 @@ pub fn add_link_type_to_integrity_zome(
     from_referenceable: &Option<Referenceable>,
     to_referenceable: &Option<Referenceable>,
     delete: bool,
+    only_creator_can_delete: bool,
+    max_links: Option<usize>,
     file_to_add_validation_to: &PathBuf,
+    tag: &Option<String>,
 ) -> ScaffoldResult<ZomeFileTree> {
     let crate_src_path = zome_file_tree.zome_crate_path.join("src");
 
@@ -233,8 +236,35 @@ pub fn add_link_type_to_integrity_zome(
                     link_type_name.to_case(Case::Pascal)
                 );
 
+                // Only the agent that created a link can delete it
+                let (delete_action_arg, delete_original_action_arg, delete_creator_check) =
+                    match delete && only_creator_can_delete {
+                        true => {
+                            let invalid_reason = format!(
+                                "Only the creator of a {} link can delete it",
+                                link_type_name.to_case(Case::Pascal)
+                            );
+                            (
+                                format_ident!("action"),
+                                format_ident!("original_action"),
+                                quote! {
+                                    if action.author.ne(&original_action.author) {
+                                        return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_reason)));
+                                    }
+                                },
+                            )
+                        }
+                        false => (
+                            format_ident!("_action"),
+                            format_ident!("_original_action"),
+                            quote! {},
+                        ),
+                    };
+
                 let validate_delete_result: TokenStream = match delete {
                     true => quote! {
+                        #delete_creator_check
+
                         /// TODO: add the appropriate validation rules
                         Ok(ValidateCallbackResult::Valid)
                     },
@@ -243,11 +273,100 @@ pub fn add_link_type_to_integrity_zome(
                     },
                 };
 
-                let base_address_ident = match from_referenceable {
-                    Some(Referenceable::EntryType(_)) => format_ident!("base_address"),
+                let base_address_ident = match (from_referenceable, max_links) {
+                    (Some(Referenceable::EntryType(_)), _) | (_, Some(_)) => {
+                        format_ident!("base_address")
+                    }
                     _ => format_ident!("_base_address"),
                 };
 
+                // Validation can't get all the links from a base deterministically, so only the owner of the base
+                // can create them, and they are counted in its source chain
+                let (create_action_ident, validate_create_max_links) = match max_links {
+                    Some(max_links) => {
+                        let link_type_ident =
+                            format_ident!("{}", link_type_name.to_case(Case::Pascal));
+                        let max_links_literal = proc_macro2::Literal::usize_unsuffixed(max_links);
+                        let invalid_reason = format!(
+                            "There can't be more than {} {} links from the same base",
+                            max_links,
+                            link_type_name.to_case(Case::Pascal)
+                        );
+                        let owner_check = match from_referenceable {
+                            Some(Referenceable::Agent { .. }) => {
+                                let invalid_owner_reason = format!(
+                                    "Agents can only create {} links from themselves",
+                                    link_type_name.to_case(Case::Pascal)
+                                );
+                                quote! {
+                                    /// Check that the author is the agent that the links go from
+                                    let base_agent = #base_address_ident.clone().into_agent_pub_key().ok_or(wasm_error!(
+                                        WasmErrorInner::Guest(String::from("No agent pub key associated with link"))
+                                    ))?;
+                                    if base_agent.ne(&action.author) {
+                                        return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_owner_reason)));
+                                    }
+                                }
+                            }
+                            Some(Referenceable::EntryType(entry_type)) => {
+                                let invalid_owner_reason = format!(
+                                    "Only the author of a {} can create {} links from it",
+                                    entry_type.entry_type.to_case(Case::Lower),
+                                    link_type_name.to_case(Case::Pascal)
+                                );
+                                quote! {
+                                    /// Check that the author is the author of the entry that the links go from
+                                    let base_action_hash = #base_address_ident.clone().into_action_hash().ok_or(wasm_error!(
+                                        WasmErrorInner::Guest(String::from("No action hash associated with link"))
+                                    ))?;
+                                    let base_action = must_get_action(base_action_hash)?;
+                                    if base_action.action().author().ne(&action.author) {
+                                        return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_owner_reason)));
+                                    }
+                                }
+                            }
+                            None => quote! {},
+                        };
+                        (
+                            format_ident!("action"),
+                            quote! {
+                                #owner_check
+
+                                /// Check that the base doesn't exceed the maximum number of links
+                                let activity = must_get_agent_activity(
+                                    action.author.clone(),
+                                    ChainFilter::new(action.prev_action.clone()),
+                                )?;
+                                let deleted_links: Vec<ActionHash> = activity
+                                    .iter()
+                                    .filter_map(|activity| match activity.action.action() {
+                                        Action::DeleteLink(delete_link) => Some(delete_link.link_add_address.clone()),
+                                        _ => None,
+                                    })
+                                    .collect();
+                                let mut links_count = 0;
+                                for activity in activity.iter() {
+                                    if let Action::CreateLink(create_link) = activity.action.action() {
+                                        if create_link.base_address.ne(&#base_address_ident)
+                                            || deleted_links.contains(activity.action.action_address())
+                                        {
+                                            continue;
+                                        }
+                                        let link_type = crate::LinkTypes::from_type(create_link.zome_index, create_link.link_type)?;
+                                        if link_type == Some(crate::LinkTypes::#link_type_ident) {
+                                            links_count += 1;
+                                        }
+                                    }
+                                }
+                                if links_count >= #max_links_literal {
+                                    return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_reason)));
+                                }
+                            },
+                        )
+                    }
+                    None => (format_ident!("_action"), quote! {}),
+                };
+
                 let validate_create_from = match from_referenceable {
                     Some(r) => Some(validate_referenceable(r, &base_address_ident)),
                     _ => None,
@@ -262,17 +381,41 @@ pub fn add_link_type_to_integrity_zome(
                     _ => None,
                 };
 
+                let tag_ident = match tag {
+                    Some(_) => format_ident!("tag"),
+                    None => format_ident!("_tag"),
+                };
+
+                let validate_create_tag = tag.as_ref().map(|tag| {
+                    let tag_type = format_ident!("{}", tag);
+                    let invalid_tag_reason = format!(
+                        "The tag of {} links must be a valid {}",
+                        link_type_name.to_case(Case::Pascal),
+                        tag
+                    );
+                    quote! {
+                        /// Check that the tag can be decoded into the typed tag of the link type
+                        if ExternIO(#tag_ident.into_inner()).decode::<#tag_type>().is_err() {
+                            return Ok(ValidateCallbackResult::Invalid(String::from(#invalid_tag_reason)));
+                        }
+                    }
+                });
+
                 let create_token_stream = quote! {
                     pub fn #validate_create_fn(
-                        _action: CreateLink,
+                        #create_action_ident: CreateLink,
                         #base_address_ident: AnyLinkableHash,
                         #target_address_ident: AnyLinkableHash,
-                        _tag: LinkTag,
+                        #tag_ident: LinkTag,
                     ) -> ExternResult<ValidateCallbackResult> {
+                        #validate_create_max_links
+
                         #validate_create_from
 
                         #validate_create_to
 
+                        #validate_create_tag
+
                         /// TODO: add the appropriate validation rules
                         Ok(ValidateCallbackResult::Valid)
                   }
@@ -280,8 +423,8 @@ pub fn add_link_type_to_integrity_zome(
 
                 let delete_token_stream = quote! {
                     pub fn #validate_delete_fn(
-                        _action: DeleteLink,
-                        _original_action: CreateLink,
+                        #delete_action_arg: DeleteLink,
+                        #delete_original_action_arg: CreateLink,
                         _base: AnyLinkableHash,
                         _target: AnyLinkableHash,
                         _tag: LinkTag
@@ -326,6 +469,40 @@ pub fn add_link_type_to_integrity_zome(
     Ok(zome_file_tree)
 }
 
+/// Returns the variants of the #[hdk_link_types] enum of the given integrity zome
+pub fn get_all_link_types(zome_file_tree: &ZomeFileTree) -> ScaffoldResult<Vec<String>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    let hdk_link_types_instances = find_map_rust_files(
+        zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_path, file| {
+            file.items.iter().find_map(|i| {
+                if let syn::Item::Enum(item_enum) = i {
+                    if item_enum.attrs.iter().any(|a| {
+                        a.path()
+                            .segments
+                            .iter()
+                            .any(|s| s.ident.eq("hdk_link_types"))
+                    }) {
+                        return Some(item_enum.clone());
+                    }
+                }
+                None
+            })
+        },
+    );
+
+    Ok(hdk_link_types_instances
+        .values()
+        .flat_map(|item_enum| item_enum.variants.iter().map(|v| v.ident.to_string()))
+        .collect())
+}
+
 fn add_link_type_signals(
     mut file_tree: FileTree,
     zome_crate_path: &PathBuf,
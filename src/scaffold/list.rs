@@ -0,0 +1,377 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use convert_case::{Case, Casing};
+use holochain_types::prelude::{DnaManifest, ZomeManifest};
+use mr_bundle::Location;
+use path_clean::PathClean;
+use quote::ToTokens;
+use serde::Serialize;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{find_map_rust_files, FileTree},
+};
+
+use super::{
+    app::find_app_manifests,
+    dna::{find_dna_manifests, DnaFileTree},
+    entry_type::integrity::get_all_entry_types,
+    link_type::integrity::get_all_link_types,
+    zome::{coordinator::find_all_extern_functions, ZomeFileTree},
+};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AppListing {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub roles: Vec<RoleListing>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RoleListing {
+    pub name: String,
+    /// None if the DNA for this role is not bundled from this repository
+    pub dna: Option<DnaListing>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct DnaListing {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub integrity_zomes: Vec<IntegrityZomeListing>,
+    pub coordinator_zomes: Vec<CoordinatorZomeListing>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct IntegrityZomeListing {
+    pub name: String,
+    pub entry_types: Vec<EntryTypeListing>,
+    pub link_types: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EntryTypeListing {
+    pub name: String,
+    /// The hash type that the rest of the app uses to reference entries of this type
+    pub hash_type: String,
+    pub fields: Vec<FieldListing>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct FieldListing {
+    pub name: String,
+    pub field_type: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CoordinatorZomeListing {
+    pub name: String,
+    pub dependencies: Vec<String>,
+    pub collections: Vec<String>,
+    pub extern_functions: Vec<String>,
+}
+
+/// Walks all the apps in the given project structure, listing their roles, DNAs, zomes
+/// and everything that has been scaffolded in them
+pub fn list_apps(file_tree: &FileTree) -> ScaffoldResult<Vec<AppListing>> {
+    let dna_manifest_paths: Vec<PathBuf> = find_dna_manifests(file_tree)?.into_keys().collect();
+
+    let mut apps = vec![];
+
+    for (app_manifest_path, app_manifest) in find_app_manifests(file_tree)? {
+        let mut app_workdir_path = app_manifest_path.clone();
+        app_workdir_path.pop();
+
+        let mut roles = vec![];
+
+        for role in app_manifest.app_roles() {
+            let dna_manifest_path = match role.dna.location.clone() {
+                Some(Location::Bundled(mut bundled_location)) => {
+                    bundled_location.pop();
+                    let dna_workdir_path = app_workdir_path.join(bundled_location).clean();
+
+                    dna_manifest_paths
+                        .iter()
+                        .find(|p| p.parent() == Some(dna_workdir_path.as_path()))
+                }
+                _ => None,
+            };
+
+            let dna = match dna_manifest_path {
+                Some(dna_manifest_path) => Some(list_dna(file_tree, dna_manifest_path)?),
+                None => None,
+            };
+
+            roles.push(RoleListing {
+                name: role.name.to_string(),
+                dna,
+            });
+        }
+
+        apps.push(AppListing {
+            name: app_manifest.app_name().to_string(),
+            manifest_path: app_manifest_path,
+            roles,
+        });
+    }
+
+    Ok(apps)
+}
+
+fn list_dna(file_tree: &FileTree, dna_manifest_path: &Path) -> ScaffoldResult<DnaListing> {
+    let mut dna_file_tree =
+        DnaFileTree::from_dna_manifest_path(file_tree.clone(), &dna_manifest_path.to_path_buf())?;
+
+    let (integrity_manifests, coordinator_manifests) = match dna_file_tree.dna_manifest.clone() {
+        DnaManifest::V1(m) => (m.integrity.zomes, m.coordinator.zomes),
+    };
+
+    let mut integrity_zomes = vec![];
+    let mut all_link_types = vec![];
+
+    for zome_manifest in integrity_manifests {
+        let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+        let link_types = get_all_link_types(&zome_file_tree)?;
+        all_link_types.extend(link_types.clone());
+
+        integrity_zomes.push(IntegrityZomeListing {
+            name: zome_file_tree.zome_manifest.name.0.to_string(),
+            entry_types: list_entry_types(&zome_file_tree)?,
+            link_types,
+        });
+
+        dna_file_tree = zome_file_tree.dna_file_tree;
+    }
+
+    let mut coordinator_zomes = vec![];
+
+    for zome_manifest in coordinator_manifests {
+        let dependencies = dependencies_names(&zome_manifest);
+        let zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)?;
+
+        let extern_functions: Vec<String> = find_all_extern_functions(&zome_file_tree)?
+            .into_iter()
+            .map(|item_fn| item_fn.sig.ident.to_string())
+            .collect();
+
+        // Collections are link types with a "get_{collection_name}" extern function to retrieve them
+        let collections = all_link_types
+            .iter()
+            .map(|link_type| link_type.to_case(Case::Snake))
+            .filter(|collection| extern_functions.contains(&format!("get_{}", collection)))
+            .collect();
+
+        coordinator_zomes.push(CoordinatorZomeListing {
+            name: zome_file_tree.zome_manifest.name.0.to_string(),
+            dependencies,
+            collections,
+            extern_functions,
+        });
+
+        dna_file_tree = zome_file_tree.dna_file_tree;
+    }
+
+    Ok(DnaListing {
+        name: dna_file_tree.dna_manifest.name(),
+        manifest_path: dna_manifest_path.to_path_buf(),
+        integrity_zomes,
+        coordinator_zomes,
+    })
+}
+
+fn dependencies_names(zome_manifest: &ZomeManifest) -> Vec<String> {
+    zome_manifest
+        .dependencies
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|d| d.name.0.to_string())
+        .collect()
+}
+
+fn list_entry_types(zome_file_tree: &ZomeFileTree) -> ScaffoldResult<Vec<EntryTypeListing>> {
+    let entry_types = get_all_entry_types(zome_file_tree)?.unwrap_or_default();
+    let entry_structs = find_entry_structs(zome_file_tree)?;
+
+    Ok(entry_types
+        .into_iter()
+        .map(|entry_type| {
+            let fields = match entry_structs.get(&entry_type.entry_type) {
+                Some(item_struct) => item_struct
+                    .fields
+                    .iter()
+                    .filter_map(|field| {
+                        field.ident.as_ref().map(|ident| FieldListing {
+                            name: ident.to_string(),
+                            field_type: field.ty.to_token_stream().to_string().replace(' ', ""),
+                        })
+                    })
+                    .collect(),
+                None => vec![],
+            };
+
+            EntryTypeListing {
+                name: entry_type.entry_type.to_case(Case::Snake),
+                hash_type: entry_type.hash_type().to_string(),
+                fields,
+            }
+        })
+        .collect())
+}
+
+/// Returns all the #[hdk_entry_helper] structs in the given integrity zome, by name
+fn find_entry_structs(
+    zome_file_tree: &ZomeFileTree,
+) -> ScaffoldResult<BTreeMap<String, syn::ItemStruct>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    let entry_structs = find_map_rust_files(
+        zome_file_tree
+            .dna_file_tree
+            .file_tree_ref()
+            .path(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.clone()))?,
+        &|_path, file| {
+            let structs: Vec<syn::ItemStruct> = file
+                .items
+                .iter()
+                .filter_map(|i| match i {
+                    syn::Item::Struct(item_struct)
+                        if item_struct.attrs.iter().any(|a| {
+                            a.path()
+                                .segments
+                                .iter()
+                                .any(|s| s.ident.eq("hdk_entry_helper"))
+                        }) =>
+                    {
+                        Some(item_struct.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+            Some(structs)
+        },
+    );
+
+    Ok(entry_structs
+        .into_values()
+        .flatten()
+        .map(|item_struct| (item_struct.ident.to_string(), item_struct))
+        .collect())
+}
+
+/// A node of the tree printed by `hc scaffold list`
+struct TreeNode {
+    label: String,
+    children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    fn new(label: String, children: Vec<TreeNode>) -> TreeNode {
+        TreeNode { label, children }
+    }
+
+    fn leaf(label: String) -> TreeNode {
+        TreeNode::new(label, vec![])
+    }
+
+    fn render(&self, prefix: &str, out: &mut String) {
+        for (i, child) in self.children.iter().enumerate() {
+            let last = i == self.children.len() - 1;
+            let (branch, indent) = match last {
+                true => ("└── ", "    "),
+                false => ("├── ", "│   "),
+            };
+            out.push_str(&format!("{}{}{}\n", prefix, branch, child.label));
+            child.render(&format!("{}{}", prefix, indent), out);
+        }
+    }
+}
+
+/// Renders the given apps as a tree, for human consumption
+pub fn render_apps_tree(apps: &[AppListing]) -> String {
+    let mut out = String::new();
+
+    for app in apps {
+        out.push_str(&format!(
+            "app {} ({})\n",
+            app.name,
+            app.manifest_path.to_string_lossy()
+        ));
+
+        let roles = TreeNode::new(String::new(), app.roles.iter().map(role_node).collect());
+        roles.render("", &mut out);
+    }
+
+    out
+}
+
+fn role_node(role: &RoleListing) -> TreeNode {
+    let children = match &role.dna {
+        Some(dna) => vec![dna_node(dna)],
+        None => vec![TreeNode::leaf(String::from(
+            "dna not found in this repository",
+        ))],
+    };
+    TreeNode::new(format!("role {}", role.name), children)
+}
+
+fn dna_node(dna: &DnaListing) -> TreeNode {
+    let integrity_zomes = dna.integrity_zomes.iter().map(|zome| {
+        let entry_types = zome.entry_types.iter().map(|entry_type| {
+            TreeNode::new(
+                format!(
+                    "entry type {} (referenced by {})",
+                    entry_type.name, entry_type.hash_type
+                ),
+                entry_type
+                    .fields
+                    .iter()
+                    .map(|f| TreeNode::leaf(format!("{}: {}", f.name, f.field_type)))
+                    .collect(),
+            )
+        });
+        let link_types = zome
+            .link_types
+            .iter()
+            .map(|link_type| TreeNode::leaf(format!("link type {}", link_type)));
+
+        TreeNode::new(
+            format!("integrity zome {}", zome.name),
+            entry_types.chain(link_types).collect(),
+        )
+    });
+
+    let coordinator_zomes = dna.coordinator_zomes.iter().map(|zome| {
+        let collections = zome
+            .collections
+            .iter()
+            .map(|collection| TreeNode::leaf(format!("collection {}", collection)));
+        let extern_functions = zome
+            .extern_functions
+            .iter()
+            .map(|f| TreeNode::leaf(format!("extern fn {}", f)));
+
+        let label = match zome.dependencies.is_empty() {
+            true => format!("coordinator zome {}", zome.name),
+            false => format!(
+                "coordinator zome {} (depends on {})",
+                zome.name,
+                zome.dependencies.join(", ")
+            ),
+        };
+
+        TreeNode::new(label, collections.chain(extern_functions).collect())
+    });
+
+    TreeNode::new(
+        format!("dna {} ({})", dna.name, dna.manifest_path.to_string_lossy()),
+        integrity_zomes.chain(coordinator_zomes).collect(),
+    )
+}
@@ -0,0 +1,778 @@
+use std::{
+    collections::BTreeMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use convert_case::{Case, Casing};
+use holochain_types::prelude::{AppManifest, DnaManifest, ZomeManifest};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{
+        dir_content, file_content, find_map_rust_files, insert_file, remove_path, FileTree,
+    },
+    utils::unparse,
+};
+
+use self::{
+    coordinator::{
+        remove_entry_type_from_coordinator, remove_link_types_from_coordinator, remove_signals,
+    },
+    integrity::{
+        link_types_for_entry_type, link_types_validated_in, remove_entry_type_from_integrity_zome,
+        remove_link_types_from_integrity_zome,
+    },
+};
+
+use super::{
+    app::{
+        cargo::{remove_workspace_dependency, remove_workspace_members_in},
+        find_app_manifests,
+    },
+    collection::shards_link_type_name,
+    dna::{find_dna_manifests, DnaFileTree},
+    doctor::role_dna_manifest_path,
+    entry_type::integrity::get_all_entry_types,
+    link_type::integrity::get_all_link_types,
+    zome::{
+        integrity_zome_name, utils::get_coordinator_zomes_for_integrity, zome_crate_path,
+        ZomeFileTree,
+    },
+};
+
+pub mod coordinator;
+pub mod integrity;
+
+/// Removes the given entry type from the integrity zome, together with its zome functions,
+/// the link types that point to or from it, and its UI components and tests
+pub fn remove_entry_type(
+    integrity_zome_file_tree: ZomeFileTree,
+    entry_type: &str,
+) -> ScaffoldResult<FileTree> {
+    let pascal_entry_type = entry_type.to_case(Case::Pascal);
+    let dna_name = integrity_zome_file_tree.dna_file_tree.dna_manifest.name();
+    let integrity_zome_name = integrity_zome_file_tree.zome_manifest.name.0.to_string();
+
+    let entry_types = get_all_entry_types(&integrity_zome_file_tree)?.unwrap_or_default();
+
+    if !entry_types
+        .iter()
+        .any(|et| et.entry_type.eq(&pascal_entry_type))
+    {
+        return Err(ScaffoldError::EntryTypeNotFound(
+            entry_type.to_string(),
+            dna_name,
+            integrity_zome_name,
+        ));
+    }
+
+    let link_types = link_types_for_entry_type(&integrity_zome_file_tree, &pascal_entry_type)?;
+
+    let mut ui_names = entry_type_ui_names(&pascal_entry_type);
+    for link_type in &link_types {
+        ui_names.extend(link_type_ui_names(link_type));
+    }
+
+    let dna_file_tree = remove_from_zomes(
+        integrity_zome_file_tree,
+        Some(&pascal_entry_type),
+        &link_types,
+        &ui_names,
+    )?;
+
+    let dna_manifest = dna_file_tree.dna_manifest.clone();
+    let mut file_tree = dna_file_tree.file_tree();
+
+    for coordinator_zome in get_coordinator_zomes_for_integrity(&dna_manifest, &integrity_zome_name)
+    {
+        let zome_name = coordinator_zome.name.0.to_string();
+
+        let types_ts_path = PathBuf::from("ui/src")
+            .join(&dna_name)
+            .join(&zome_name)
+            .join("types.ts");
+        if let Ok(types_ts) = file_content(&file_tree, &types_ts_path) {
+            insert_file(
+                &mut file_tree,
+                &types_ts_path,
+                &remove_entry_type_from_types_ts(&types_ts, &pascal_entry_type),
+            )?;
+        }
+
+        let common_ts_path = PathBuf::from("tests/src")
+            .join(&dna_name)
+            .join(&zome_name)
+            .join("common.ts");
+        if let Ok(common_ts) = file_content(&file_tree, &common_ts_path) {
+            insert_file(
+                &mut file_tree,
+                &common_ts_path,
+                &remove_entry_type_from_common_ts(&common_ts, &pascal_entry_type),
+            )?;
+        }
+    }
+
+    Ok(file_tree)
+}
+
+/// Removes the given link type from the integrity zome, together with its zome functions,
+/// its inverse link type if it was bidireccional, and its UI components and tests
+pub fn remove_link_type(
+    integrity_zome_file_tree: ZomeFileTree,
+    link_type: &str,
+) -> ScaffoldResult<FileTree> {
+    let pascal_link_type = link_type.to_case(Case::Pascal);
+
+    if !get_all_link_types(&integrity_zome_file_tree)?.contains(&pascal_link_type) {
+        return Err(ScaffoldError::LinkTypeNotFound(
+            link_type.to_string(),
+            integrity_zome_file_tree.dna_file_tree.dna_manifest.name(),
+            integrity_zome_file_tree.zome_manifest.name.0.to_string(),
+        ));
+    }
+
+    // The inverse of a bidireccional link type is validated in the same file
+    let mut link_types = vec![pascal_link_type.clone()];
+    for l in link_types_validated_in(
+        &integrity_zome_file_tree,
+        &format!("{}.rs", pascal_link_type.to_case(Case::Snake)),
+    )? {
+        if !link_types.contains(&l) {
+            link_types.push(l);
+        }
+    }
+
+    let ui_names: Vec<String> = link_types
+        .iter()
+        .flat_map(|l| link_type_ui_names(l))
+        .collect();
+
+    let integrity_zome_name = integrity_zome_file_tree.zome_manifest.name.0.to_string();
+    let dna_file_tree = remove_from_zomes(integrity_zome_file_tree, None, &link_types, &ui_names)?;
+
+    let dna_name = dna_file_tree.dna_manifest.name();
+    let dna_manifest = dna_file_tree.dna_manifest.clone();
+    let mut file_tree = dna_file_tree.file_tree();
+
+    // Typed tags are described by an interface in the UI types
+    for coordinator_zome in get_coordinator_zomes_for_integrity(&dna_manifest, &integrity_zome_name)
+    {
+        let types_ts_path = PathBuf::from("ui/src")
+            .join(&dna_name)
+            .join(coordinator_zome.name.0.to_string())
+            .join("types.ts");
+        if let Ok(types_ts) = file_content(&file_tree, &types_ts_path) {
+            let types_ts = link_types.iter().fold(types_ts, |types_ts, l| {
+                remove_interface_from_types_ts(&types_ts, &format!("{}Tag", l))
+            });
+            insert_file(&mut file_tree, &types_ts_path, &types_ts)?;
+        }
+    }
+
+    Ok(file_tree)
+}
+
+/// Removes the given collection: its link type, its getter, the statements that add
+/// every new entry to it, and its UI components and tests
+pub fn remove_collection(
+    integrity_zome_file_tree: ZomeFileTree,
+    collection_name: &str,
+) -> ScaffoldResult<FileTree> {
+    let link_type = collection_name.to_case(Case::Pascal);
+
+    let all_link_types = get_all_link_types(&integrity_zome_file_tree)?;
+    if !all_link_types.contains(&link_type) {
+        return Err(ScaffoldError::CollectionNotFound(
+            collection_name.to_string(),
+            integrity_zome_file_tree.dna_file_tree.dna_manifest.name(),
+            integrity_zome_file_tree.zome_manifest.name.0.to_string(),
+        ));
+    }
+
+    let ui_names = link_type_ui_names(&link_type);
+
+    // Time indexed collections also link their shards with each other
+    let mut link_types = vec![link_type];
+    let shards_link_type = shards_link_type_name(collection_name);
+    if all_link_types.contains(&shards_link_type) {
+        link_types.push(shards_link_type);
+    }
+
+    Ok(remove_from_zomes(integrity_zome_file_tree, None, &link_types, &ui_names)?.file_tree())
+}
+
+/// Removes the given entry type and link types from the integrity zome and from all the coordinator zomes that depend on it
+pub fn remove_from_zomes(
+    integrity_zome_file_tree: ZomeFileTree,
+    entry_type: Option<&String>,
+    link_types: &[String],
+    ui_names: &[String],
+) -> ScaffoldResult<DnaFileTree> {
+    let dna_name = integrity_zome_file_tree.dna_file_tree.dna_manifest.name();
+    let dna_manifest_path = integrity_zome_file_tree
+        .dna_file_tree
+        .dna_manifest_path
+        .clone();
+    let integrity_zome_name = integrity_zome_file_tree.zome_manifest.name.0.to_string();
+
+    let entry_types_count = get_all_entry_types(&integrity_zome_file_tree)?
+        .unwrap_or_default()
+        .len();
+    let all_link_types = get_all_link_types(&integrity_zome_file_tree)?;
+
+    let no_entry_types_left = entry_type.is_some() && entry_types_count == 1;
+    let no_link_types_left =
+        !link_types.is_empty() && all_link_types.iter().all(|l| link_types.contains(l));
+
+    let mut zome_file_tree =
+        remove_link_types_from_integrity_zome(integrity_zome_file_tree, link_types)?;
+    if let Some(entry_type) = entry_type {
+        zome_file_tree = remove_entry_type_from_integrity_zome(zome_file_tree, entry_type)?;
+    }
+
+    let mut crate_src_paths = vec![zome_file_tree.zome_crate_path.join("src")];
+
+    let coordinator_zomes = get_coordinator_zomes_for_integrity(
+        &zome_file_tree.dna_file_tree.dna_manifest,
+        &integrity_zome_name,
+    );
+
+    let mut dna_file_tree = zome_file_tree.dna_file_tree;
+
+    for coordinator_zome in coordinator_zomes {
+        let zome_name = coordinator_zome.name.0.to_string();
+        let mut zome_file_tree = ZomeFileTree::from_zome_manifest(dna_file_tree, coordinator_zome)?;
+        crate_src_paths.push(zome_file_tree.zome_crate_path.join("src"));
+
+        zome_file_tree = remove_link_types_from_coordinator(zome_file_tree, link_types)?;
+        if let Some(entry_type) = entry_type {
+            zome_file_tree = remove_entry_type_from_coordinator(zome_file_tree, entry_type)?;
+        }
+        zome_file_tree = remove_signals(zome_file_tree, no_entry_types_left, no_link_types_left)?;
+
+        let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+        remove_template_files(&mut file_tree, &dna_name, &zome_name, ui_names)?;
+
+        dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    }
+
+    if let Some(entry_type) = entry_type {
+        // Other entry types may still be validating or linking to this one
+        let mut referencing_files: Vec<String> = vec![];
+        for crate_src_path in crate_src_paths {
+            for (path, file) in rust_files(dna_file_tree.file_tree_ref(), &crate_src_path)? {
+                if mentions_ident(file.to_token_stream(), entry_type) {
+                    referencing_files.push(format!(
+                        "\"{}\"",
+                        crate_src_path.join(path).to_string_lossy()
+                    ));
+                }
+            }
+        }
+
+        if !referencing_files.is_empty() {
+            return Err(ScaffoldError::EntryTypeStillReferenced(
+                entry_type.to_case(Case::Snake),
+                referencing_files.join(", "),
+            ));
+        }
+    }
+
+    Ok(dna_file_tree)
+}
+
+/// Removes the given zome from its DNA, together with its crate and its UI components and tests
+///
+/// Removing a coordinator zome also removes its "<ZOME_NAME>_integrity" zome, if no other coordinator zome depends on it
+pub fn remove_zome(dna_file_tree: DnaFileTree, zome_name: &str) -> ScaffoldResult<FileTree> {
+    let dna_name = dna_file_tree.dna_manifest.name();
+
+    let (integrity_zomes, coordinator_zomes) = match dna_file_tree.dna_manifest.clone() {
+        DnaManifest::V1(m) => (m.integrity.zomes, m.coordinator.zomes),
+    };
+
+    let zomes_to_remove: Vec<ZomeManifest> =
+        match coordinator_zomes.iter().find(|z| z.name.0.eq(zome_name)) {
+            Some(coordinator_zome) => {
+                let paired_integrity_zome = integrity_zome_name(&zome_name.to_string());
+                let depends_on_paired_zome = |z: &ZomeManifest| {
+                    z.dependencies
+                        .clone()
+                        .unwrap_or_default()
+                        .iter()
+                        .any(|d| d.name.0.eq(&paired_integrity_zome))
+                };
+
+                let mut zomes = vec![coordinator_zome.clone()];
+
+                if depends_on_paired_zome(coordinator_zome)
+                    && !coordinator_zomes
+                        .iter()
+                        .any(|z| !z.name.0.eq(zome_name) && depends_on_paired_zome(z))
+                {
+                    zomes.extend(
+                        integrity_zomes
+                            .iter()
+                            .filter(|z| z.name.0.eq(&paired_integrity_zome))
+                            .cloned(),
+                    );
+                }
+
+                zomes
+            }
+            None => {
+                let integrity_zome = integrity_zomes
+                    .iter()
+                    .find(|z| z.name.0.eq(zome_name))
+                    .ok_or(ScaffoldError::ZomeNotFound(
+                        zome_name.to_string(),
+                        dna_name.clone(),
+                    ))?;
+
+                let dependents: Vec<String> = get_coordinator_zomes_for_integrity(
+                    &dna_file_tree.dna_manifest,
+                    &zome_name.to_string(),
+                )
+                .into_iter()
+                .map(|z| format!("\"{}\"", z.name.0))
+                .collect();
+
+                if !dependents.is_empty() {
+                    return Err(ScaffoldError::IntegrityZomeHasDependents(
+                        zome_name.to_string(),
+                        dependents.join(", "),
+                    ));
+                }
+
+                vec![integrity_zome.clone()]
+            }
+        };
+
+    Ok(remove_zomes(dna_file_tree, &zomes_to_remove)?.file_tree())
+}
+
+/// Removes the given DNA from the apps that include it, together with all its zomes and its UI components and tests
+pub fn remove_dna(dna_file_tree: DnaFileTree) -> ScaffoldResult<FileTree> {
+    let dna_name = dna_file_tree.dna_manifest.name();
+    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
+
+    let zomes: Vec<ZomeManifest> = match dna_file_tree.dna_manifest.clone() {
+        DnaManifest::V1(m) => m
+            .coordinator
+            .zomes
+            .into_iter()
+            .chain(m.integrity.zomes)
+            .collect(),
+    };
+
+    let dna_file_tree = remove_zomes(dna_file_tree, &zomes)?;
+
+    let dna_manifests = find_dna_manifests(dna_file_tree.file_tree_ref())?;
+    let mut file_tree = dna_file_tree.file_tree();
+
+    for (app_manifest_path, mut app_manifest) in find_app_manifests(&file_tree)? {
+        let removed_roles = match &mut app_manifest {
+            AppManifest::V1(m) => {
+                let roles_count = m.roles.len();
+                m.roles.retain(|role| {
+                    role_dna_manifest_path(&app_manifest_path, &role.dna.location, &dna_manifests)
+                        != Some(Some(dna_manifest_path.clone()))
+                });
+                m.roles.len() != roles_count
+            }
+        };
+
+        if removed_roles {
+            insert_file(
+                &mut file_tree,
+                &app_manifest_path,
+                &serde_yaml::to_string(&app_manifest)?,
+            )?;
+        }
+    }
+
+    // DNAs are scaffolded in "<DNA_NAME>/workdir/dna.yaml", with their zomes in "<DNA_NAME>/zomes"
+    let mut dna_path = dna_manifest_path;
+    dna_path.pop();
+    if dna_path.file_name() == Some(OsString::from("workdir").as_os_str()) {
+        dna_path.pop();
+    }
+
+    remove_path(&mut file_tree, &dna_path)?;
+    let mut file_tree = remove_workspace_members_in(file_tree, &dna_path)?;
+
+    remove_path(&mut file_tree, &PathBuf::from("ui/src").join(&dna_name))?;
+    remove_path(&mut file_tree, &PathBuf::from("tests/src").join(&dna_name))?;
+
+    Ok(file_tree)
+}
+
+/// Removes the given zomes from the DNA manifest and their crates from the workspace,
+/// together with the UI components and tests of the coordinator zomes
+fn remove_zomes(dna_file_tree: DnaFileTree, zomes: &[ZomeManifest]) -> ScaffoldResult<DnaFileTree> {
+    let dna_name = dna_file_tree.dna_manifest.name();
+    let dna_manifest_path = dna_file_tree.dna_manifest_path.clone();
+    let zome_names: Vec<String> = zomes.iter().map(|z| z.name.0.to_string()).collect();
+
+    // Zomes whose crate is not in this workspace only need to be removed from the manifest
+    let crate_paths: Vec<PathBuf> = zomes
+        .iter()
+        .filter_map(|z| zome_crate_path(&dna_file_tree, z).ok())
+        .collect();
+
+    let mut dna_manifest = dna_file_tree.dna_manifest.clone();
+    let coordinator_zome_names: Vec<String> = match &mut dna_manifest {
+        DnaManifest::V1(m) => {
+            m.integrity
+                .zomes
+                .retain(|z| !zome_names.contains(&z.name.0.to_string()));
+
+            let coordinator_zome_names = m
+                .coordinator
+                .zomes
+                .iter()
+                .map(|z| z.name.0.to_string())
+                .filter(|z| zome_names.contains(z))
+                .collect();
+            m.coordinator
+                .zomes
+                .retain(|z| !zome_names.contains(&z.name.0.to_string()));
+
+            coordinator_zome_names
+        }
+    };
+
+    let mut file_tree = dna_file_tree.file_tree();
+
+    insert_file(
+        &mut file_tree,
+        &dna_manifest_path,
+        &serde_yaml::to_string(&dna_manifest)?,
+    )?;
+
+    for crate_path in crate_paths {
+        remove_path(&mut file_tree, &crate_path)?;
+        file_tree = remove_workspace_members_in(file_tree, &crate_path)?;
+    }
+    for zome_name in &zome_names {
+        file_tree = remove_workspace_dependency(file_tree, zome_name)?;
+    }
+
+    for zome_name in coordinator_zome_names {
+        remove_path(
+            &mut file_tree,
+            &PathBuf::from("ui/src").join(&dna_name).join(&zome_name),
+        )?;
+        remove_path(
+            &mut file_tree,
+            &PathBuf::from("tests/src").join(&dna_name).join(&zome_name),
+        )?;
+    }
+
+    DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)
+}
+
+/// Applies the given function to all the rust files of the given zome, and then removes the modules that it left empty
+pub fn map_zome_files<F>(zome_file_tree: ZomeFileTree, map_fn: F) -> ScaffoldResult<ZomeFileTree>
+where
+    F: FnOnce(&mut BTreeMap<PathBuf, syn::File>, &mut FileTree, &Path) -> ScaffoldResult<()>,
+{
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let zome_manifest = zome_file_tree.zome_manifest;
+    let dna_manifest_path = zome_file_tree.dna_file_tree.dna_manifest_path.clone();
+
+    let mut file_tree = zome_file_tree.dna_file_tree.file_tree();
+    let mut files = rust_files(&file_tree, &crate_src_path)?;
+
+    map_fn(&mut files, &mut file_tree, &crate_src_path)?;
+    remove_empty_modules(&mut files, &mut file_tree, &crate_src_path)?;
+    write_rust_files(&mut file_tree, &crate_src_path, files)?;
+
+    let dna_file_tree = DnaFileTree::from_dna_manifest_path(file_tree, &dna_manifest_path)?;
+    ZomeFileTree::from_zome_manifest(dna_file_tree, zome_manifest)
+}
+
+/// Returns all the rust files in the given crate source folder, keyed by their path relative to it
+pub fn rust_files(
+    file_tree: &FileTree,
+    crate_src_path: &Path,
+) -> ScaffoldResult<BTreeMap<PathBuf, syn::File>> {
+    let v: Vec<OsString> = crate_src_path.iter().map(|s| s.to_os_string()).collect();
+
+    Ok(find_map_rust_files(
+        file_tree
+            .path(&mut v.iter())
+            .ok_or(ScaffoldError::PathNotFound(crate_src_path.to_path_buf()))?,
+        &|_path, file| Some(file.clone()),
+    ))
+}
+
+/// Writes back the given rust files, skipping the ones that didn't change so that they keep their formatting
+fn write_rust_files(
+    file_tree: &mut FileTree,
+    crate_src_path: &Path,
+    files: BTreeMap<PathBuf, syn::File>,
+) -> ScaffoldResult<()> {
+    let previous_files = rust_files(file_tree, crate_src_path)?;
+
+    for (path, file) in files {
+        if previous_files.get(&path) != Some(&file) {
+            insert_file(file_tree, &crate_src_path.join(path), &unparse(&file))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the "<MODULE>.rs" file, and its "mod" and "use" statements from lib.rs
+fn remove_module(
+    files: &mut BTreeMap<PathBuf, syn::File>,
+    file_tree: &mut FileTree,
+    crate_src_path: &Path,
+    module: &str,
+) -> ScaffoldResult<()> {
+    let module_path = PathBuf::from(format!("{}.rs", module));
+    files.remove(&module_path);
+    remove_path(file_tree, &crate_src_path.join(module_path))?;
+
+    if let Some(lib_rs) = files.get_mut(&PathBuf::from("lib.rs")) {
+        lib_rs.items.retain(|item| match item {
+            syn::Item::Mod(item_mod) => !item_mod.ident.eq(module),
+            syn::Item::Use(item_use) => match &item_use.tree {
+                syn::UseTree::Path(use_path) => {
+                    !(use_path.ident.eq(module) && matches!(*use_path.tree, syn::UseTree::Glob(_)))
+                }
+                _ => true,
+            },
+            _ => true,
+        });
+    }
+
+    Ok(())
+}
+
+/// Removes the modules that were left with nothing but "use" statements
+fn remove_empty_modules(
+    files: &mut BTreeMap<PathBuf, syn::File>,
+    file_tree: &mut FileTree,
+    crate_src_path: &Path,
+) -> ScaffoldResult<()> {
+    let empty_modules: Vec<String> = files
+        .iter()
+        .filter(|(path, file)| {
+            path.parent() == Some(Path::new(""))
+                && !path.ends_with("lib.rs")
+                && file
+                    .items
+                    .iter()
+                    .all(|item| matches!(item, syn::Item::Use(_)))
+        })
+        .filter_map(|(path, _)| path.file_stem())
+        .map(|stem| stem.to_string_lossy().to_string())
+        .collect();
+
+    for module in empty_modules {
+        remove_module(files, file_tree, crate_src_path, &module)?;
+    }
+
+    Ok(())
+}
+
+/// Whether the given tokens contain the given identifier
+pub fn mentions_ident(tokens: TokenStream, ident: &str) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(i) => i.eq(ident),
+        TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Whether the given tokens contain the path "<FIRST>::<SECOND>"
+fn mentions_path(tokens: TokenStream, first: &str, second: &str) -> bool {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+    tokens.iter().enumerate().any(|(i, token)| match token {
+        TokenTree::Group(group) => mentions_path(group.stream(), first, second),
+        TokenTree::Ident(ident) if ident.eq(first) => matches!(
+            (tokens.get(i + 1), tokens.get(i + 2), tokens.get(i + 3)),
+            (Some(TokenTree::Punct(p1)), Some(TokenTree::Punct(p2)), Some(TokenTree::Ident(ident)))
+                if p1.as_char() == ':' && p2.as_char() == ':' && ident.eq(second)
+        ),
+        _ => false,
+    })
+}
+
+/// Removes the match arms whose pattern matches on "<ENUM_NAME>::<VARIANT>", in the given expression and the ones nested in it
+fn remove_match_arms(expr: &mut syn::Expr, enum_name: &str, variant: &str) {
+    match expr {
+        syn::Expr::Match(expr_match) => {
+            expr_match
+                .arms
+                .retain(|arm| !mentions_path(arm.pat.to_token_stream(), enum_name, variant));
+            for arm in &mut expr_match.arms {
+                remove_match_arms(&mut arm.body, enum_name, variant);
+            }
+        }
+        syn::Expr::Block(expr_block) => {
+            remove_match_arms_in_block(&mut expr_block.block, enum_name, variant)
+        }
+        _ => {}
+    }
+}
+
+fn remove_match_arms_in_block(block: &mut syn::Block, enum_name: &str, variant: &str) {
+    for stmt in &mut block.stmts {
+        match stmt {
+            syn::Stmt::Expr(expr, _) => remove_match_arms(expr, enum_name, variant),
+            syn::Stmt::Local(local) => {
+                if let Some(init) = &mut local.init {
+                    remove_match_arms(&mut init.expr, enum_name, variant);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether the given match has no arms left other than the wildcard one
+fn only_wildcard_arms(expr_match: &syn::ExprMatch) -> bool {
+    expr_match
+        .arms
+        .iter()
+        .all(|arm| matches!(arm.pat, syn::Pat::Wild(_)))
+}
+
+/// The names of the files that the templates generate for an entry type, as returned by `normalized_file_name`
+fn entry_type_ui_names(pascal_entry_type: &str) -> Vec<String> {
+    let name = pascal_entry_type.to_lowercase();
+    vec![
+        format!("create{}", name),
+        format!("edit{}", name),
+        format!("{}detail", name),
+        name,
+    ]
+}
+
+/// The names of the files that the templates generate for a link type or a collection, as returned by `normalized_file_name`
+pub fn link_type_ui_names(pascal_link_type: &str) -> Vec<String> {
+    let snake_link_type = pascal_link_type.to_case(Case::Snake);
+    let mut names = vec![snake_link_type.replace('_', "")];
+
+    // "<FROM>_to_<TO>" link types also get a "<TO>-for-<FROM>" component
+    if let Some((from, to)) = snake_link_type.split_once("_to_") {
+        names.push(format!(
+            "{}for{}",
+            to.replace('_', ""),
+            from.replace('_', "")
+        ));
+    }
+
+    names
+}
+
+/// Strips the extensions, the separators and the casing from the given file name,
+/// so that "create-post.ts", "CreatePost.svelte" and "create_post.test.ts" all become "createpost"
+pub fn normalized_file_name(file_name: &str) -> String {
+    file_name
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .replace(['-', '_'], "")
+        .to_lowercase()
+}
+
+/// Removes the UI components and tests that the templates generated with one of the given names
+fn remove_template_files(
+    file_tree: &mut FileTree,
+    dna_name: &str,
+    coordinator_zome_name: &str,
+    names: &[String],
+) -> ScaffoldResult<()> {
+    for folder in ["ui/src", "tests/src"] {
+        let folder_path = PathBuf::from(folder)
+            .join(dna_name)
+            .join(coordinator_zome_name);
+
+        if let Ok(contents) = dir_content(file_tree, &folder_path) {
+            for (file_name, _) in contents {
+                if names.contains(&normalized_file_name(&file_name.to_string_lossy())) {
+                    remove_path(file_tree, &folder_path.join(file_name))?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the entry type from the `EntryTypes` union and its interface from the UI types
+pub fn remove_entry_type_from_types_ts(types_ts: &str, pascal_entry_type: &str) -> String {
+    let mut lines: Vec<String> = types_ts.lines().map(|l| l.to_string()).collect();
+
+    let union_member = format!("type: '{}'; }} & {})", pascal_entry_type, pascal_entry_type);
+    if let Some(i) = lines.iter().position(|l| {
+        l.trim_start().starts_with('|') && l.replace("  ", " ").contains(&union_member)
+    }) {
+        let last_member = lines[i].trim_end().ends_with(';');
+        lines.remove(i);
+
+        if i > 0 {
+            if lines[i - 1].trim_end().eq("export type EntryTypes =") {
+                if last_member {
+                    lines[i - 1] = String::from("export type EntryTypes = {};");
+                }
+            } else if last_member {
+                lines[i - 1].push(';');
+            }
+        }
+    }
+
+    let mut types_ts = lines.join("\n");
+    types_ts.push('\n');
+    remove_interface_from_types_ts(&types_ts, pascal_entry_type)
+}
+
+/// Removes the interface, or the union type of an enum, with the given name from the UI types
+pub fn remove_interface_from_types_ts(types_ts: &str, interface: &str) -> String {
+    let mut lines: Vec<String> = types_ts.lines().map(|l| l.to_string()).collect();
+
+    let interface_start = format!("export interface {} {{", interface);
+    let type_start = format!("export type {} =", interface);
+    if let Some(start) = lines
+        .iter()
+        .position(|l| l.starts_with(&interface_start) || l.starts_with(&type_start))
+    {
+        // The variants of enums are joined by "} | {" lines
+        if let Some(end) = lines[start..]
+            .iter()
+            .position(|l| l.starts_with('}') && !l.ends_with('{'))
+        {
+            lines.drain(start..=start + end);
+        }
+    }
+
+    let mut types_ts = lines.join("\n");
+    types_ts.push('\n');
+    types_ts
+}
+
+/// Removes the `sample<ENTRY_TYPE>` and `create<ENTRY_TYPE>` helpers from the tests' common.ts
+pub fn remove_entry_type_from_common_ts(common_ts: &str, pascal_entry_type: &str) -> String {
+    let mut lines: Vec<String> = common_ts.lines().map(|l| l.to_string()).collect();
+
+    for helper in ["sample", "create"] {
+        let helper_start = format!("export async function {}{}(", helper, pascal_entry_type);
+        if let Some(start) = lines.iter().position(|l| l.starts_with(&helper_start)) {
+            if let Some(end) = lines[start..].iter().position(|l| l.starts_with('}')) {
+                lines.drain(start..=start + end);
+            }
+        }
+    }
+
+    let mut common_ts = lines.join("\n");
+    common_ts.push('\n');
+    common_ts
+}
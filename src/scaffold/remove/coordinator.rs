@@ -0,0 +1,339 @@
+use convert_case::{Case, Casing};
+use quote::ToTokens;
+
+use crate::{
+    error::ScaffoldResult,
+    scaffold::{entry_type::integrity::find_ending_match_expr_in_block, zome::ZomeFileTree},
+};
+
+use super::{
+    map_zome_files, mentions_ident, mentions_path, only_wildcard_arms, remove_match_arms_in_block,
+    remove_module,
+};
+
+/// Bindings that the delete handler of an entry type always declares, even if it has no links to delete
+const DELETE_HANDLER_BINDINGS: [&str; 2] = ["details", "record"];
+
+/// Removes the module with the zome functions for the given entry type
+pub fn remove_entry_type_from_coordinator(
+    zome_file_tree: ZomeFileTree,
+    pascal_entry_type: &str,
+) -> ScaffoldResult<ZomeFileTree> {
+    map_zome_files(zome_file_tree, |files, file_tree, crate_src_path| {
+        remove_module(
+            files,
+            file_tree,
+            crate_src_path,
+            &pascal_entry_type.to_case(Case::Snake),
+        )
+    })
+}
+
+/// Removes the zome functions that use the given link types, together with their input structs, their private
+/// helper functions and the calls to them
+///
+/// Functions that also create, update or delete entries are kept, and only the statements that create,
+/// get or delete the links are removed from them
+pub fn remove_link_types_from_coordinator(
+    zome_file_tree: ZomeFileTree,
+    link_types: &[String],
+) -> ScaffoldResult<ZomeFileTree> {
+    if link_types.is_empty() {
+        return Ok(zome_file_tree);
+    }
+
+    let mentions_link_types = |tokens: proc_macro2::TokenStream| {
+        link_types
+            .iter()
+            .any(|link_type| mentions_path(tokens.clone(), "LinkTypes", link_type))
+    };
+
+    map_zome_files(zome_file_tree, |files, _file_tree, _crate_src_path| {
+        let mut removed_fns: Vec<syn::ItemFn> = vec![];
+
+        for file in files.values_mut() {
+            let mut items = vec![];
+
+            for item in file.items.drain(..) {
+                match item {
+                    syn::Item::Fn(mut item_fn)
+                        if mentions_link_types(item_fn.to_token_stream()) =>
+                    {
+                        let tokens = item_fn.to_token_stream();
+                        let commits_entries = ["create_entry", "update_entry", "delete_entry"]
+                            .iter()
+                            .any(|f| mentions_ident(tokens.clone(), f));
+
+                        if commits_entries
+                            && remove_statements(&mut item_fn.block, &mentions_link_types)
+                        {
+                            items.push(syn::Item::Fn(item_fn));
+                        } else {
+                            removed_fns.push(item_fn);
+                        }
+                    }
+                    item => items.push(item),
+                }
+            }
+
+            file.items = items;
+        }
+
+        // Calls to the removed zome functions, like the ones that add each new entry to an index
+        let removed_fn_names: Vec<String> = removed_fns
+            .iter()
+            .map(|item_fn| item_fn.sig.ident.to_string())
+            .collect();
+        let calls_removed_fns = |tokens: proc_macro2::TokenStream| {
+            removed_fn_names
+                .iter()
+                .any(|fn_name| mentions_ident(tokens.clone(), fn_name))
+        };
+        for file in files.values_mut() {
+            for item in &mut file.items {
+                if let syn::Item::Fn(item_fn) = item {
+                    if calls_removed_fns(item_fn.block.to_token_stream()) {
+                        remove_statements(&mut item_fn.block, &calls_removed_fns);
+                    }
+                }
+            }
+        }
+
+        // Private helper functions that were only used by the removed ones, which can only be in the same file
+        for file in files.values_mut() {
+            let unused_fns: Vec<String> = file
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    syn::Item::Fn(item_fn) if matches!(item_fn.vis, syn::Visibility::Inherited) => {
+                        Some(item_fn.sig.ident.to_string())
+                    }
+                    _ => None,
+                })
+                .filter(|ident| {
+                    removed_fns
+                        .iter()
+                        .any(|item_fn| mentions_ident(item_fn.to_token_stream(), ident))
+                })
+                .filter(|ident| {
+                    !file.items.iter().any(|item| {
+                        !matches!(item, syn::Item::Fn(f) if f.sig.ident.eq(ident))
+                            && mentions_ident(item.to_token_stream(), ident)
+                    })
+                })
+                .collect();
+
+            file.items.retain(|item| match item {
+                syn::Item::Fn(item_fn) => !unused_fns.contains(&item_fn.sig.ident.to_string()),
+                _ => true,
+            });
+        }
+
+        // Input structs that are no longer used by any of the remaining items
+        let unused_structs: Vec<String> = files
+            .values()
+            .flat_map(|file| file.items.iter())
+            .filter_map(|item| match item {
+                syn::Item::Struct(item_struct) => Some(item_struct.ident.to_string()),
+                _ => None,
+            })
+            .filter(|ident| {
+                removed_fns
+                    .iter()
+                    .any(|item_fn| mentions_ident(item_fn.to_token_stream(), ident))
+            })
+            .filter(|ident| {
+                !files
+                    .values()
+                    .flat_map(|file| file.items.iter())
+                    .any(|item| {
+                        !matches!(item, syn::Item::Struct(s) if s.ident.eq(ident))
+                            && mentions_ident(item.to_token_stream(), ident)
+                    })
+            })
+            .collect();
+
+        for file in files.values_mut() {
+            file.items.retain(|item| match item {
+                syn::Item::Struct(item_struct) => {
+                    !unused_structs.contains(&item_struct.ident.to_string())
+                }
+                _ => true,
+            });
+        }
+
+        Ok(())
+    })
+}
+
+/// Removes the signal variants and the `signal_action` arms for entries and/or links,
+/// for when the integrity zome no longer has any entry or link types
+pub fn remove_signals(
+    zome_file_tree: ZomeFileTree,
+    no_entry_types_left: bool,
+    no_link_types_left: bool,
+) -> ScaffoldResult<ZomeFileTree> {
+    if !no_entry_types_left && !no_link_types_left {
+        return Ok(zome_file_tree);
+    }
+
+    let mut variants: Vec<&str> = vec![];
+    let mut actions: Vec<&str> = vec![];
+    if no_entry_types_left {
+        variants.extend(["EntryCreated", "EntryUpdated", "EntryDeleted"]);
+        actions.extend(["Create", "Update", "Delete"]);
+    }
+    if no_link_types_left {
+        variants.extend(["LinkCreated", "LinkDeleted"]);
+        actions.extend(["CreateLink", "DeleteLink"]);
+    }
+
+    map_zome_files(zome_file_tree, |files, _file_tree, _crate_src_path| {
+        for file in files.values_mut() {
+            if no_entry_types_left {
+                file.items.retain(|item| {
+                    !matches!(item, syn::Item::Fn(item_fn) if item_fn.sig.ident.eq("get_entry_for_action"))
+                });
+            }
+
+            for item in &mut file.items {
+                match item {
+                    syn::Item::Enum(item_enum) if item_enum.ident.eq("Signal") => {
+                        item_enum.variants = item_enum
+                            .variants
+                            .clone()
+                            .into_iter()
+                            .filter(|v| !variants.iter().any(|variant| v.ident.eq(variant)))
+                            .collect();
+                    }
+                    syn::Item::Fn(item_fn) if item_fn.sig.ident.eq("signal_action") => {
+                        for action in &actions {
+                            remove_match_arms_in_block(&mut item_fn.block, "Action", action);
+                        }
+
+                        if find_ending_match_expr_in_block(&mut item_fn.block)
+                            .is_some_and(|expr_match| only_wildcard_arms(expr_match))
+                        {
+                            *item_fn.block = syn::parse_str::<syn::Block>("{ Ok(()) }")?;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Removes the statements that mention the link types, together with the ones that depend on them
+/// and the bindings that only they were using
+///
+/// Returns false if the function can't be kept, because its return value was removed
+pub fn remove_statements<F>(block: &mut syn::Block, mentions_link_types: &F) -> bool
+where
+    F: Fn(proc_macro2::TokenStream) -> bool,
+{
+    let stmts = &block.stmts;
+    let mut removed: Vec<bool> = stmts
+        .iter()
+        .map(|stmt| mentions_link_types(stmt.to_token_stream()))
+        .collect();
+
+    loop {
+        let mut changed = false;
+
+        for (i, stmt) in stmts.iter().enumerate() {
+            let bindings = bindings(stmt);
+            let users: Vec<usize> = bindings
+                .iter()
+                .flat_map(|binding| users_of_binding(stmts, i, binding))
+                .collect();
+
+            // Statements that use a removed binding are removed too
+            if removed[i] {
+                for user in users {
+                    if !removed[user] {
+                        removed[user] = true;
+                        changed = true;
+                    }
+                }
+            // Bindings that were only used by removed statements are removed too
+            } else if !users.is_empty()
+                && users.iter().all(|user| removed[*user])
+                && !bindings
+                    .iter()
+                    .any(|binding| DELETE_HANDLER_BINDINGS.contains(&binding.as_str()))
+            {
+                removed[i] = true;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let return_value_removed = matches!(stmts.last(), Some(syn::Stmt::Expr(_, None)))
+        && removed.last().is_some_and(|r| *r);
+
+    let mut removed = removed.into_iter();
+    block.stmts.retain(|_| !removed.next().unwrap_or_default());
+
+    !return_value_removed
+}
+
+/// The statements after the given one that use the given binding, until it is shadowed
+fn users_of_binding(stmts: &[syn::Stmt], binding_index: usize, binding: &str) -> Vec<usize> {
+    let mut users = vec![];
+
+    for (i, stmt) in stmts.iter().enumerate().skip(binding_index + 1) {
+        // A `let` statement that shadows the binding only uses it if its initializer does
+        let uses_binding = match stmt {
+            syn::Stmt::Local(local) => local.init.as_ref().is_some_and(|init| {
+                mentions_ident(init.expr.to_token_stream(), binding)
+                    || init.diverge.as_ref().is_some_and(|(_, diverge)| {
+                        mentions_ident(diverge.to_token_stream(), binding)
+                    })
+            }),
+            _ => mentions_ident(stmt.to_token_stream(), binding),
+        };
+        if uses_binding {
+            users.push(i);
+        }
+        if bindings(stmt).iter().any(|b| b.eq(binding)) {
+            break;
+        }
+    }
+
+    users
+}
+
+/// The identifiers bound by the given statement, if it's a `let` statement
+fn bindings(stmt: &syn::Stmt) -> Vec<String> {
+    match stmt {
+        syn::Stmt::Local(local) => pattern_bindings(&local.pat),
+        _ => vec![],
+    }
+}
+
+fn pattern_bindings(pat: &syn::Pat) -> Vec<String> {
+    match pat {
+        syn::Pat::Ident(pat_ident) => vec![pat_ident.ident.to_string()],
+        syn::Pat::Type(pat_type) => pattern_bindings(&pat_type.pat),
+        syn::Pat::Reference(pat_reference) => pattern_bindings(&pat_reference.pat),
+        syn::Pat::Tuple(pat_tuple) => pat_tuple.elems.iter().flat_map(pattern_bindings).collect(),
+        syn::Pat::TupleStruct(pat_tuple_struct) => pat_tuple_struct
+            .elems
+            .iter()
+            .flat_map(pattern_bindings)
+            .collect(),
+        syn::Pat::Struct(pat_struct) => pat_struct
+            .fields
+            .iter()
+            .flat_map(|field| pattern_bindings(&field.pat))
+            .collect(),
+        _ => vec![],
+    }
+}
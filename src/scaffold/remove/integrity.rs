@@ -0,0 +1,281 @@
+use std::path::PathBuf;
+
+use convert_case::{Case, Casing};
+use quote::ToTokens;
+
+use crate::{
+    error::ScaffoldResult,
+    scaffold::{
+        entry_type::integrity::{find_ending_match_expr, find_ending_match_expr_in_block},
+        link_type::integrity::get_all_link_types,
+        zome::{integrity::initial_lib_rs, ZomeFileTree},
+    },
+    utils::{find_fn, has_attribute},
+};
+
+use super::{
+    map_zome_files, mentions_ident, only_wildcard_arms, remove_match_arms_in_block, remove_module,
+    rust_files,
+};
+
+/// Returns the link types whose validation functions are defined in the given file of the integrity zome
+pub fn link_types_validated_in(
+    zome_file_tree: &ZomeFileTree,
+    file_name: &str,
+) -> ScaffoldResult<Vec<String>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let files = rust_files(
+        zome_file_tree.dna_file_tree.file_tree_ref(),
+        &crate_src_path,
+    )?;
+
+    let link_types = match files.get(&PathBuf::from(file_name)) {
+        Some(file) => get_all_link_types(zome_file_tree)?
+            .into_iter()
+            .filter(|link_type| find_fn(file, &validate_create_link_fn_name(link_type)).is_some())
+            .collect(),
+        None => vec![],
+    };
+
+    Ok(link_types)
+}
+
+/// Returns the link types that were scaffolded for the given entry type:
+/// the ones validated in its file, and the ones whose validation retrieves entries of its type
+pub fn link_types_for_entry_type(
+    zome_file_tree: &ZomeFileTree,
+    pascal_entry_type: &str,
+) -> ScaffoldResult<Vec<String>> {
+    let crate_src_path = zome_file_tree.zome_crate_path.join("src");
+    let files = rust_files(
+        zome_file_tree.dna_file_tree.file_tree_ref(),
+        &crate_src_path,
+    )?;
+    let entry_type_file = PathBuf::from(format!("{}.rs", pascal_entry_type.to_case(Case::Snake)));
+
+    Ok(get_all_link_types(zome_file_tree)?
+        .into_iter()
+        .filter(|link_type| {
+            let fn_name = validate_create_link_fn_name(link_type);
+
+            files
+                .iter()
+                .any(|(path, file)| match find_fn(file, &fn_name) {
+                    Some(item_fn) => {
+                        path.eq(&entry_type_file)
+                            || mentions_ident(item_fn.to_token_stream(), pascal_entry_type)
+                    }
+                    None => false,
+                })
+        })
+        .collect())
+}
+
+/// Removes the given link types from the `LinkTypes` enum, together with their validation functions and match arms
+pub fn remove_link_types_from_integrity_zome(
+    zome_file_tree: ZomeFileTree,
+    link_types: &[String],
+) -> ScaffoldResult<ZomeFileTree> {
+    if link_types.is_empty() {
+        return Ok(zome_file_tree);
+    }
+
+    let validation_fns: Vec<String> = link_types
+        .iter()
+        .flat_map(|link_type| {
+            [
+                validate_create_link_fn_name(link_type),
+                format!("validate_delete_link_{}", link_type.to_case(Case::Snake)),
+            ]
+        })
+        .collect();
+
+    // The typed tags of the link types are defined next to their validation functions
+    let tag_structs: Vec<String> = link_types
+        .iter()
+        .map(|link_type| format!("{}Tag", link_type.to_case(Case::Pascal)))
+        .collect();
+
+    map_zome_files(zome_file_tree, |files, _file_tree, _crate_src_path| {
+        for file in files.values_mut() {
+            file.items.retain(|item| match item {
+                syn::Item::Fn(item_fn) => !validation_fns.contains(&item_fn.sig.ident.to_string()),
+                syn::Item::Struct(item_struct) => {
+                    !tag_structs.contains(&item_struct.ident.to_string())
+                }
+                _ => true,
+            });
+            remove_variants(file, "hdk_link_types", "LinkTypes", link_types, 1)?;
+        }
+        Ok(())
+    })
+}
+
+/// Removes the given entry type from the `EntryTypes` enum, together with its module and its validation match arms
+pub fn remove_entry_type_from_integrity_zome(
+    zome_file_tree: ZomeFileTree,
+    pascal_entry_type: &String,
+) -> ScaffoldResult<ZomeFileTree> {
+    map_zome_files(zome_file_tree, |files, file_tree, crate_src_path| {
+        remove_module(
+            files,
+            file_tree,
+            crate_src_path,
+            &pascal_entry_type.to_case(Case::Snake),
+        )?;
+        for file in files.values_mut() {
+            remove_variants(
+                file,
+                "hdk_entry_defs",
+                "EntryTypes",
+                std::slice::from_ref(pascal_entry_type),
+                0,
+            )?;
+        }
+        Ok(())
+    })
+}
+
+fn validate_create_link_fn_name(link_type: &str) -> String {
+    format!("validate_create_link_{}", link_type.to_case(Case::Snake))
+}
+
+/// Removes the given variants from the enum with the given attribute, and their match arms from the validate function
+///
+/// If the enum is left empty, it is removed and its argument in `op.flattened::<EntryTypes, LinkTypes>()` is set back to `()`
+fn remove_variants(
+    file: &mut syn::File,
+    enum_attribute: &str,
+    enum_name: &str,
+    variants: &[String],
+    flattened_arg_index: usize,
+) -> ScaffoldResult<()> {
+    let mut enum_emptied = false;
+
+    for item in &mut file.items {
+        if let syn::Item::Enum(item_enum) = item {
+            if has_attribute(&item_enum.attrs, enum_attribute) {
+                item_enum.variants = item_enum
+                    .variants
+                    .clone()
+                    .into_iter()
+                    .filter(|v| !variants.contains(&v.ident.to_string()))
+                    .collect();
+                enum_emptied = item_enum.variants.is_empty();
+            }
+        }
+    }
+
+    if enum_emptied {
+        file.items.retain(|item| match item {
+            syn::Item::Enum(item_enum) => !has_attribute(&item_enum.attrs, enum_attribute),
+            _ => true,
+        });
+    }
+
+    for item in &mut file.items {
+        if let syn::Item::Fn(item_fn) = item {
+            if item_fn.sig.ident.eq("validate") {
+                for variant in variants {
+                    remove_match_arms_in_block(&mut item_fn.block, enum_name, variant);
+                }
+
+                if enum_emptied {
+                    set_flattened_arg_to_unit(item_fn, flattened_arg_index)?;
+                }
+
+                restore_emptied_arms(item_fn)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn set_flattened_arg_to_unit(
+    validate_fn: &mut syn::ItemFn,
+    flattened_arg_index: usize,
+) -> ScaffoldResult<()> {
+    if let Some(expr_match) = find_ending_match_expr_in_block(&mut validate_fn.block) {
+        if let syn::Expr::Try(try_expr) = &mut *expr_match.expr {
+            if let syn::Expr::MethodCall(call) = &mut *try_expr.expr {
+                if call.method.eq("flattened") {
+                    if let Some(turbofish) = &mut call.turbofish {
+                        if let Some(arg) = turbofish.args.iter_mut().nth(flattened_arg_index) {
+                            *arg = syn::GenericArgument::Type(syn::parse_str::<syn::Type>("()")?);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces the arms that were left matching on no entry or link types
+/// with the ones from a newly scaffolded integrity zome
+fn restore_emptied_arms(validate_fn: &mut syn::ItemFn) -> ScaffoldResult<()> {
+    let initial_lib_rs: syn::File = syn::parse_str(initial_lib_rs().as_str())?;
+
+    let initial_validate_fn = initial_lib_rs
+        .items
+        .into_iter()
+        .find_map(|item| match item {
+            syn::Item::Fn(item_fn) if item_fn.sig.ident.eq("validate") => Some(item_fn),
+            _ => None,
+        });
+
+    if let Some(mut initial_validate_fn) = initial_validate_fn {
+        if let (Some(expr_match), Some(initial_expr_match)) = (
+            find_ending_match_expr_in_block(&mut validate_fn.block),
+            find_ending_match_expr_in_block(&mut initial_validate_fn.block),
+        ) {
+            restore_emptied_arms_in_match(expr_match, initial_expr_match);
+        }
+    }
+
+    Ok(())
+}
+
+fn restore_emptied_arms_in_match(
+    expr_match: &mut syn::ExprMatch,
+    initial_expr_match: &mut syn::ExprMatch,
+) {
+    for arm in &mut expr_match.arms {
+        let pattern = pattern_path(&arm.pat);
+        let initial_arm = match initial_expr_match
+            .arms
+            .iter_mut()
+            .find(|a| pattern_path(&a.pat).eq(&pattern))
+        {
+            Some(initial_arm) => initial_arm,
+            None => continue,
+        };
+
+        let emptied = find_ending_match_expr(&mut arm.body).map(|m| only_wildcard_arms(m));
+
+        match emptied {
+            Some(true) => arm.body = initial_arm.body.clone(),
+            Some(false) => {
+                if let (Some(m), Some(initial_m)) = (
+                    find_ending_match_expr(&mut arm.body),
+                    find_ending_match_expr(&mut initial_arm.body),
+                ) {
+                    restore_emptied_arms_in_match(m, initial_m);
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+fn pattern_path(pat: &syn::Pat) -> String {
+    match pat {
+        syn::Pat::Struct(pat_struct) => pat_struct.path.to_token_stream().to_string(),
+        syn::Pat::TupleStruct(pat_tuple_struct) => {
+            pat_tuple_struct.path.to_token_stream().to_string()
+        }
+        _ => pat.to_token_stream().to_string(),
+    }
+}
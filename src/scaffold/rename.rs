@@ -0,0 +1,493 @@
+use std::path::{Path, PathBuf};
+
+use convert_case::{Case, Casing};
+use proc_macro2::{Group, Ident, Literal, TokenStream, TokenTree};
+use quote::ToTokens;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::{dir_content, insert_file, remove_path, FileTree},
+    reserved_words::check_for_reserved_words,
+    utils::unparse,
+};
+
+use super::{
+    entry_type::integrity::get_all_entry_types,
+    remove::rust_files,
+    zome::{utils::get_coordinator_zomes_for_integrity, zome_crate_path, ZomeFileTree},
+};
+
+/// Identifiers from the HDK, the HDI and the holochain client that the templates use,
+/// which keep their name even if they contain the name of the entry type being renamed
+const FRAMEWORK_IDENTIFIERS: [&str; 24] = [
+    "post_commit",
+    "recv_remote_signal",
+    "emit_signal",
+    "Signal",
+    "Link",
+    "LinkTag",
+    "LinkTypes",
+    "CreateLink",
+    "DeleteLink",
+    "create_link",
+    "delete_link",
+    "get_links",
+    "get_link_details",
+    "Path",
+    "path_entry_hash",
+    "Create",
+    "Update",
+    "Delete",
+    "create_entry",
+    "update_entry",
+    "delete_entry",
+    "get_details",
+    "Details",
+    "Timestamp",
+];
+
+/// Words after which a name is still part of the code, and not of a sentence
+const CODE_KEYWORDS: [&str; 22] = [
+    "as",
+    "async",
+    "await",
+    "case",
+    "class",
+    "const",
+    "each",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "interface",
+    "let",
+    "new",
+    "of",
+    "return",
+    "type",
+];
+
+/// Words after which a name is always part of a sentence, like in "Error fetching the posts: ..."
+const ARTICLES: [&str; 9] = [
+    "a", "an", "the", "this", "that", "these", "those", "no", "all",
+];
+
+const NAME_CASES: [Case; 6] = [
+    Case::Pascal,
+    Case::Camel,
+    Case::Snake,
+    Case::Kebab,
+    Case::Title,
+    Case::Lower,
+];
+
+/// Renames the given entry type everywhere it was scaffolded: its struct, its `EntryTypes` variant,
+/// its validation and zome functions, the link types and collections that were named after it,
+/// and the UI components and tests of the coordinator zomes, together with their file names
+pub fn rename_entry_type(
+    integrity_zome_file_tree: ZomeFileTree,
+    entry_type: &str,
+    new_entry_type: &str,
+) -> ScaffoldResult<FileTree> {
+    check_for_reserved_words(&new_entry_type.to_string())?;
+
+    let snake_entry_type = entry_type.to_case(Case::Snake);
+    let new_snake_entry_type = new_entry_type.to_case(Case::Snake);
+    let dna_name = integrity_zome_file_tree.dna_file_tree.dna_manifest.name();
+    let integrity_zome_name = integrity_zome_file_tree.zome_manifest.name.0.to_string();
+
+    let entry_types: Vec<String> = get_all_entry_types(&integrity_zome_file_tree)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|et| et.entry_type.to_case(Case::Snake))
+        .collect();
+
+    if !entry_types.contains(&snake_entry_type) {
+        return Err(ScaffoldError::EntryTypeNotFound(
+            entry_type.to_string(),
+            dna_name,
+            integrity_zome_name,
+        ));
+    }
+    if entry_types.contains(&new_snake_entry_type) {
+        return Err(ScaffoldError::EntryTypeAlreadyExists(
+            new_entry_type.to_string(),
+            dna_name,
+            integrity_zome_name,
+        ));
+    }
+
+    let coordinator_zomes = get_coordinator_zomes_for_integrity(
+        &integrity_zome_file_tree.dna_file_tree.dna_manifest,
+        &integrity_zome_name,
+    );
+
+    // The zome and DNA names, as they are used in the code: "posts_integrity::*", "PostsSignal" or "zome_name: 'posts'"
+    let mut protected: Vec<String> = FRAMEWORK_IDENTIFIERS
+        .iter()
+        .map(|i| i.to_string())
+        .collect();
+    protected.push(integrity_zome_name.to_case(Case::Snake));
+    for name in coordinator_zomes
+        .iter()
+        .map(|z| z.name.0.to_string())
+        .chain([integrity_zome_name.clone(), dna_name.clone()])
+    {
+        protected.extend([
+            format!("{}Signal", name.to_case(Case::Pascal)),
+            format!("'{}'", name),
+            format!("\"{}\"", name),
+        ]);
+    }
+    // Other entry types whose names contain this one, eg. "post_tag" when renaming "post"
+    for other_entry_type in entry_types.iter().filter(|et| {
+        !et.eq(&&snake_entry_type)
+            && format!("_{}_", et).contains(&format!("_{}_", snake_entry_type))
+    }) {
+        protected.extend(
+            name_forms(other_entry_type)
+                .into_iter()
+                .map(|(form, _)| form),
+        );
+    }
+
+    let renamer = Renamer::new(&snake_entry_type, &new_snake_entry_type, protected);
+
+    let mut crate_src_paths = vec![zome_crate_path(
+        &integrity_zome_file_tree.dna_file_tree,
+        &integrity_zome_file_tree.zome_manifest,
+    )?
+    .join("src")];
+    for coordinator_zome in &coordinator_zomes {
+        crate_src_paths.push(
+            zome_crate_path(&integrity_zome_file_tree.dna_file_tree, coordinator_zome)?.join("src"),
+        );
+    }
+
+    let mut file_tree = integrity_zome_file_tree.dna_file_tree.file_tree();
+
+    for crate_src_path in crate_src_paths {
+        for (path, file) in rust_files(&file_tree, &crate_src_path)? {
+            let tokens = file.to_token_stream();
+            let renamed_tokens = renamer.rename_tokens(tokens.clone())?;
+            let new_path = renamer.rename_path(&path, Case::Snake);
+
+            if new_path.ne(&path) {
+                remove_path(&mut file_tree, &crate_src_path.join(&path))?;
+            }
+            if new_path.ne(&path) || renamed_tokens.to_string().ne(&tokens.to_string()) {
+                let renamed_file: syn::File = syn::parse2(renamed_tokens)?;
+                insert_file(
+                    &mut file_tree,
+                    &crate_src_path.join(new_path),
+                    &unparse(&renamed_file),
+                )?;
+            }
+        }
+    }
+
+    for coordinator_zome in &coordinator_zomes {
+        for folder in ["ui/src", "tests/src"] {
+            let folder_path = PathBuf::from(folder)
+                .join(&dna_name)
+                .join(coordinator_zome.name.0.to_string());
+
+            if let Ok(contents) = dir_content(&file_tree, &folder_path) {
+                for (file_name, file) in contents {
+                    let file_content = match file.file_content() {
+                        Some(file_content) => file_content,
+                        None => continue,
+                    };
+                    let file_name = file_name.to_string_lossy().to_string();
+                    let new_file_name = renamer.rename(&file_name, Case::Kebab);
+                    let new_file_content = renamer.rename(file_content, Case::Camel);
+
+                    if new_file_name.ne(&file_name) {
+                        remove_path(&mut file_tree, &folder_path.join(&file_name))?;
+                    }
+                    if new_file_name.ne(&file_name) || new_file_content.ne(file_content) {
+                        insert_file(
+                            &mut file_tree,
+                            &folder_path.join(new_file_name),
+                            &new_file_content,
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(file_tree)
+}
+
+/// The forms in which the templates render the given snake_case name, in singular and in plural,
+/// longest first, together with whether each of them is the plural one
+fn name_forms(snake_name: &str) -> Vec<(String, bool)> {
+    let plural_snake_name = pluralizer::pluralize(snake_name, 2, false);
+    let mut forms: Vec<(String, bool)> = vec![];
+
+    for (name, plural) in [(snake_name, false), (plural_snake_name.as_str(), true)] {
+        for case in NAME_CASES {
+            let form = name.to_case(case);
+            if !forms.iter().any(|(f, _)| f.eq(&form)) {
+                forms.push((form, plural));
+            }
+        }
+    }
+
+    forms.sort_by_key(|(form, _)| std::cmp::Reverse(form.len()));
+    forms
+}
+
+/// Replaces the words of a name with the words of a new name, in all the cases that the templates render names with,
+/// leaving untouched the given protected words
+struct Renamer {
+    forms: Vec<(String, bool)>,
+    new_snake_name: String,
+    new_plural_snake_name: String,
+    protected: Vec<String>,
+}
+
+impl Renamer {
+    fn new(snake_name: &str, new_snake_name: &str, protected: Vec<String>) -> Self {
+        Renamer {
+            forms: name_forms(snake_name),
+            new_snake_name: new_snake_name.to_string(),
+            new_plural_snake_name: pluralizer::pluralize(new_snake_name, 2, false),
+            protected,
+        }
+    }
+
+    /// Renames the name in the given text, using `default_case` for the occurrences
+    /// whose case can't be told from the text, like "post" in "const post" or in "mod post"
+    fn rename(&self, text: &str, default_case: Case) -> String {
+        let mut renamed = String::with_capacity(text.len());
+        let mut i = 0;
+
+        'scan: while i < text.len() {
+            let rest = &text[i..];
+
+            for protected in &self.protected {
+                if rest.starts_with(protected.as_str()) && is_word(text, i, protected) {
+                    renamed.push_str(protected);
+                    i += protected.len();
+                    continue 'scan;
+                }
+            }
+
+            for (form, plural) in &self.forms {
+                if rest.starts_with(form.as_str()) && is_word(text, i, form) {
+                    let new_name = match plural {
+                        true => &self.new_plural_snake_name,
+                        false => &self.new_snake_name,
+                    };
+                    renamed.push_str(&new_name.to_case(case_at(text, i, form, default_case)));
+                    i += form.len();
+                    continue 'scan;
+                }
+            }
+
+            match rest.chars().next() {
+                Some(c) => {
+                    renamed.push(c);
+                    i += c.len_utf8();
+                }
+                None => break,
+            }
+        }
+
+        renamed
+    }
+
+    fn rename_path(&self, path: &Path, default_case: Case) -> PathBuf {
+        path.iter()
+            .map(|component| self.rename(&component.to_string_lossy(), default_case))
+            .collect()
+    }
+
+    /// Renames the name in all the identifiers and string literals of the given tokens
+    fn rename_tokens(&self, tokens: TokenStream) -> ScaffoldResult<TokenStream> {
+        tokens
+            .into_iter()
+            .map(|token| match token {
+                TokenTree::Group(group) => {
+                    let mut renamed_group =
+                        Group::new(group.delimiter(), self.rename_tokens(group.stream())?);
+                    renamed_group.set_span(group.span());
+                    Ok(TokenTree::Group(renamed_group))
+                }
+                TokenTree::Ident(ident) => {
+                    let name = ident.to_string();
+                    let new_name = self.rename(&name, Case::Snake);
+                    match new_name.eq(&name) {
+                        true => Ok(TokenTree::Ident(ident)),
+                        false => Ok(TokenTree::Ident(Ident::new(&new_name, ident.span()))),
+                    }
+                }
+                TokenTree::Literal(literal) => {
+                    let literal_str = literal.to_string();
+                    let is_string = literal_str.starts_with('"') || literal_str.starts_with('r');
+                    let new_literal_str = self.rename(&literal_str, Case::Snake);
+
+                    match is_string && new_literal_str.ne(&literal_str) {
+                        true => {
+                            let mut new_literal: Literal =
+                                syn::parse_str::<syn::LitStr>(&new_literal_str)?.token();
+                            new_literal.set_span(literal.span());
+                            Ok(TokenTree::Literal(new_literal))
+                        }
+                        false => Ok(TokenTree::Literal(literal)),
+                    }
+                }
+                token => Ok(token),
+            })
+            .collect()
+    }
+}
+
+/// Whether the given word is found at the given position of the text as a whole word,
+/// either on its own or as one of the words of a bigger identifier like "get_post" or "PostDetail"
+fn is_word(text: &str, start: usize, word: &str) -> bool {
+    let previous = text[..start].chars().next_back();
+    let next = text[start + word.len()..].chars().next();
+
+    let separated_from_previous = match previous {
+        None => true,
+        Some(p) if word.starts_with(|c: char| c.is_ascii_uppercase()) => {
+            !p.is_ascii_uppercase() && !p.is_ascii_digit()
+        }
+        Some(p) => !p.is_ascii_alphanumeric(),
+    };
+    let separated_from_next = !next.is_some_and(|n| n.is_ascii_lowercase() || n.is_ascii_digit());
+
+    separated_from_previous && separated_from_next
+}
+
+/// The case in which the name was rendered, at the given position of the text
+fn case_at(text: &str, start: usize, form: &str, default_case: Case) -> Case {
+    let uppercase = form.starts_with(|c: char| c.is_ascii_uppercase());
+
+    if form.contains(' ') {
+        return match uppercase {
+            true => Case::Title,
+            false => Case::Lower,
+        };
+    }
+    if form.contains('_') {
+        return Case::Snake;
+    }
+    if form.contains('-') {
+        return Case::Kebab;
+    }
+    if !uppercase && form.contains(|c: char| c.is_ascii_uppercase()) {
+        return Case::Camel;
+    }
+
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '$';
+    let end = start + form.len();
+    let identifier_start = text[..start]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_identifier_char(*c))
+        .last()
+        .map_or(start, |(i, _)| i);
+    let identifier_end = text[end..]
+        .char_indices()
+        .find(|(_, c)| !is_identifier_char(*c))
+        .map_or(text.len(), |(i, _)| end + i);
+    let identifier = &text[identifier_start..identifier_end];
+
+    if identifier.len() == form.len() && is_in_sentence(text, start, form) {
+        return match uppercase {
+            true => Case::Title,
+            false => Case::Lower,
+        };
+    }
+
+    match uppercase {
+        true => Case::Pascal,
+        false if identifier.contains('_') => Case::Snake,
+        false if identifier.contains('-') => Case::Kebab,
+        false if identifier.contains(|c: char| c.is_ascii_uppercase()) => Case::Camel,
+        false => default_case,
+    }
+}
+
+/// Whether the word at the given position is part of a sentence rather than of the code,
+/// like in "No posts found" or in "Create Post"
+fn is_in_sentence(text: &str, start: usize, word: &str) -> bool {
+    let previous_word = match text[..start].strip_suffix(' ') {
+        Some(before) => before
+            .rsplit(|c: char| !c.is_alphabetic())
+            .next()
+            .unwrap_or_default(),
+        None => return false,
+    };
+    if previous_word.is_empty() || CODE_KEYWORDS.contains(&previous_word) {
+        return false;
+    }
+    if ARTICLES.contains(&previous_word.to_lowercase().as_str()) {
+        return true;
+    }
+
+    let mut next = text[start + word.len()..].chars();
+    match next.next() {
+        Some(':' | '(' | '=' | '[' | '{' | '}' | ';' | '!' | '?') => false,
+        Some('.') => !next.next().is_some_and(|c| c.is_alphanumeric() || c == '_'),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renamer() -> Renamer {
+        Renamer::new("post", "blog_post", vec![String::from("post_commit")])
+    }
+
+    #[test]
+    fn test_rename_in_sentences() {
+        let renamer = renamer();
+
+        assert_eq!(
+            renamer.rename("No posts found", Case::Camel),
+            "No blog posts found"
+        );
+        assert_eq!(
+            renamer.rename("Error fetching the posts: ", Case::Camel),
+            "Error fetching the blog posts: "
+        );
+        assert_eq!(
+            renamer.rename("<button>Create Post</button>", Case::Camel),
+            "<button>Create Blog Post</button>"
+        );
+    }
+
+    #[test]
+    fn test_rename_in_code() {
+        let renamer = renamer();
+
+        assert_eq!(
+            renamer.rename("const post = new Post(record);", Case::Camel),
+            "const blogPost = new BlogPost(record);"
+        );
+        assert_eq!(
+            renamer.rename("if (post) return posts;", Case::Camel),
+            "if (blogPost) return blogPosts;"
+        );
+        assert_eq!(
+            renamer.rename("get_all_posts(post_hash)", Case::Snake),
+            "get_all_blog_posts(blog_post_hash)"
+        );
+        assert_eq!(
+            renamer.rename("fn post_commit(post: Post)", Case::Snake),
+            "fn post_commit(blog_post: BlogPost)"
+        );
+    }
+}
@@ -0,0 +1,556 @@
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use build_fs_tree::{Build, MergeableFileSystemTree};
+use convert_case::{Case, Casing};
+use serde::Deserialize;
+
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    file_tree::FileTree,
+    templates::ScaffoldedTemplate,
+};
+
+use super::{
+    app::AppFileTree,
+    collection::{scaffold_collection, CollectionType},
+    dna::{scaffold_dna, DnaFileTree},
+    entry_type::{
+        crud::{parse_crud, Crud},
+        definitions::{
+            parse_entry_type_reference, parse_referenceable, EntryDefSettings, FieldDefinition,
+            Referenceable,
+        },
+        fields::parse_fields,
+        scaffold_entry_type,
+    },
+    link_type::scaffold_link_type,
+    zome::{
+        integrity_zome_name, scaffold_coordinator_zome_in_path, scaffold_integrity_zome_with_path,
+        try_to_guess_coordinator_zomes_location, try_to_guess_integrity_zomes_location,
+        ZomeFileTree,
+    },
+};
+
+/// Declarative description of a whole hApp, as read from the file given to `hc scaffold apply`
+///
+/// Example:
+///
+/// ```yaml
+/// name: forum
+/// template: lit
+/// dnas:
+///   - name: forum
+///     zomes:
+///       - name: posts
+///         entry_types:
+///           - name: post
+///             fields:
+///               - title:String:TextField
+///               - content:String:TextArea
+///           - name: comment
+///             crud: crd
+///             fields:
+///               - comment:String:TextArea
+///               - post_hash:ActionHash::post
+///         link_types:
+///           - from: agent
+///             to: post
+///             delete: true
+///         collections:
+///           - name: all_posts
+///             type: global
+///             entry_type: post
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+pub struct AppSpec {
+    pub name: String,
+    pub description: Option<String>,
+    /// UI framework used to create the web-app if it doesn't exist yet,
+    /// or name of the custom template in the ".templates" folder if it does
+    pub template: Option<String>,
+    /// Only used when creating the web-app
+    #[serde(default)]
+    pub setup_nix: bool,
+    #[serde(default)]
+    pub dnas: Vec<DnaSpec>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct DnaSpec {
+    pub name: String,
+    /// Folder in which to scaffold the DNA, only needed if the app has no "dnas" folder
+    pub path: Option<PathBuf>,
+    #[serde(default)]
+    pub zomes: Vec<ZomeSpec>,
+}
+
+/// An integrity/coordinator zome-pair: the integrity zome is named "<name>_integrity"
+#[derive(Deserialize, Debug, Clone)]
+pub struct ZomeSpec {
+    pub name: String,
+    pub integrity_path: Option<PathBuf>,
+    pub coordinator_path: Option<PathBuf>,
+    #[serde(default)]
+    pub entry_types: Vec<EntryTypeSpec>,
+    #[serde(default)]
+    pub link_types: Vec<LinkTypeSpec>,
+    #[serde(default)]
+    pub collections: Vec<CollectionSpec>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EntryTypeSpec {
+    pub name: String,
+    /// Same grammar as the "--fields" argument of `hc scaffold entry-type`
+    pub fields: Vec<String>,
+    /// Same grammar as the "--crud" argument of `hc scaffold entry-type`, defaults to "crud"
+    pub crud: Option<String>,
+    #[serde(default)]
+    pub reference_entry_hash: bool,
+    /// Defaults to true
+    pub link_from_original_to_each_update: Option<bool>,
+    #[serde(default)]
+    pub only_author_can_update_and_delete: bool,
+    /// Same as the "--visibility", "--required-validations" and "--cache-at-agent-activity" arguments of `hc scaffold entry-type`
+    #[serde(flatten)]
+    pub settings: EntryDefSettings,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LinkTypeSpec {
+    pub from: String,
+    /// Not given for the link types that index the entries of "from" by one of their fields
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub delete: bool,
+    #[serde(default)]
+    pub only_creator_can_delete: bool,
+    #[serde(default)]
+    pub bidireccional: bool,
+    /// Same grammar as the "--tag-fields" argument of `hc scaffold link-type`
+    #[serde(default)]
+    pub tag_fields: Vec<String>,
+    /// Same as the "--anchor-field" argument of `hc scaffold link-type`
+    pub anchor_field: Option<String>,
+    /// Same as the "--max-links" argument of `hc scaffold link-type`, which needs "delete" and "only_creator_can_delete"
+    pub max_links: Option<usize>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CollectionSpec {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub collection_type: String,
+    pub entry_type: String,
+}
+
+pub fn read_app_spec(path: &PathBuf) -> ScaffoldResult<AppSpec> {
+    let contents = fs::read_to_string(path)?;
+
+    // YAML is a superset of JSON, so this parses both formats
+    let spec: AppSpec = serde_yaml::from_str(contents.as_str())?;
+
+    Ok(spec)
+}
+
+pub struct AppliedSpec {
+    pub file_tree: FileTree,
+    pub scaffolded: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Scaffolds every DNA, zome, entry type, link type and collection in the spec that doesn't exist yet
+/// in the given app file tree, in dependency order
+///
+/// Once the zomes are scaffolded, the file tree is written to `build_dir` and the current directory is set to it,
+/// since finding the zome crates requires running `cargo metadata`. `build_dir` should be a temporary directory:
+/// nothing gets written to the app itself, so that the caller can diff and record all the changes at once
+pub fn apply_app_spec(
+    file_tree: FileTree,
+    template_file_tree: &FileTree,
+    spec: &AppSpec,
+    build_dir: &Path,
+) -> ScaffoldResult<AppliedSpec> {
+    let mut applied = AppliedSpec {
+        file_tree,
+        scaffolded: vec![],
+        skipped: vec![],
+    };
+
+    for dna in &spec.dnas {
+        apply_dna_spec(&mut applied, template_file_tree, &spec.name, dna)?;
+    }
+
+    // Scaffold the app here to enable ZomeFileTree::from_manifest(), which calls `cargo metadata`
+    MergeableFileSystemTree::<OsString, String>::from(applied.file_tree.clone())
+        .build(&build_dir.to_path_buf())
+        .map_err(|e| ScaffoldError::MiscError(Box::new(e)))?;
+    std::env::set_current_dir(build_dir)?;
+
+    for dna in &spec.dnas {
+        for zome in &dna.zomes {
+            apply_zome_contents_spec(&mut applied, template_file_tree, dna, zome)?;
+        }
+    }
+
+    Ok(applied)
+}
+
+fn apply_dna_spec(
+    applied: &mut AppliedSpec,
+    template_file_tree: &FileTree,
+    app_name: &str,
+    dna: &DnaSpec,
+) -> ScaffoldResult<()> {
+    let app_file_tree =
+        AppFileTree::get_or_choose(applied.file_tree.clone(), &Some(app_name.to_string()))?;
+
+    match scaffold_dna(app_file_tree, template_file_tree, &dna.name, &dna.path) {
+        Ok(ScaffoldedTemplate { file_tree, .. }) => {
+            applied.file_tree = file_tree;
+            applied.scaffolded.push(format!("DNA \"{}\"", dna.name));
+        }
+        Err(ScaffoldError::DnaAlreadyExists(_)) => {
+            applied.skipped.push(format!("DNA \"{}\"", dna.name));
+        }
+        Err(e) => return Err(e),
+    }
+
+    for zome in &dna.zomes {
+        let integrity_name = integrity_zome_name(&zome.name);
+
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(applied.file_tree.clone(), &Some(dna.name.clone()))?;
+        let integrity_path = match &zome.integrity_path {
+            Some(p) => p.clone(),
+            None => try_to_guess_integrity_zomes_location(&dna_file_tree)?
+                .unwrap_or(default_zomes_path(&dna.name, "integrity")),
+        };
+        match scaffold_integrity_zome_with_path(
+            dna_file_tree,
+            template_file_tree,
+            &integrity_name,
+            &integrity_path,
+        ) {
+            Ok(ScaffoldedTemplate { file_tree, .. }) => {
+                applied.file_tree = file_tree;
+                applied.scaffolded.push(format!(
+                    "integrity zome \"{}\" in DNA \"{}\"",
+                    integrity_name, dna.name
+                ));
+            }
+            Err(ScaffoldError::ZomeAlreadyExists(_, _)) => {
+                applied.skipped.push(format!(
+                    "integrity zome \"{}\" in DNA \"{}\"",
+                    integrity_name, dna.name
+                ));
+            }
+            Err(e) => return Err(e),
+        }
+
+        let dna_file_tree =
+            DnaFileTree::get_or_choose(applied.file_tree.clone(), &Some(dna.name.clone()))?;
+        let coordinator_path = match &zome.coordinator_path {
+            Some(p) => p.clone(),
+            None => try_to_guess_coordinator_zomes_location(&dna_file_tree)?
+                .unwrap_or(default_zomes_path(&dna.name, "coordinator")),
+        };
+        match scaffold_coordinator_zome_in_path(
+            dna_file_tree,
+            template_file_tree,
+            &zome.name,
+            &Some(vec![integrity_name]),
+            &coordinator_path,
+        ) {
+            Ok(ScaffoldedTemplate { file_tree, .. }) => {
+                applied.file_tree = file_tree;
+                applied.scaffolded.push(format!(
+                    "coordinator zome \"{}\" in DNA \"{}\"",
+                    zome.name, dna.name
+                ));
+            }
+            Err(ScaffoldError::ZomeAlreadyExists(_, _)) => {
+                applied.skipped.push(format!(
+                    "coordinator zome \"{}\" in DNA \"{}\"",
+                    zome.name, dna.name
+                ));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+fn apply_zome_contents_spec(
+    applied: &mut AppliedSpec,
+    template_file_tree: &FileTree,
+    dna: &DnaSpec,
+    zome: &ZomeSpec,
+) -> ScaffoldResult<()> {
+    let integrity_name = integrity_zome_name(&zome.name);
+
+    for entry_type in sort_entry_types_by_dependencies(&zome.entry_types)? {
+        let fields = entry_type
+            .fields
+            .iter()
+            .map(|f| parse_fields(f.as_str()))
+            .collect::<ScaffoldResult<Vec<FieldDefinition>>>()?;
+        let crud = match &entry_type.crud {
+            Some(c) => parse_crud(c.as_str()).map_err(ScaffoldError::InvalidArguments)?,
+            None => Crud {
+                update: true,
+                delete: true,
+                author_only: false,
+            },
+        };
+
+        let zome_file_tree = integrity_zome_file_tree(&applied.file_tree, dna, &integrity_name)?;
+        let description = format!(
+            "entry type \"{}\" in zome \"{}\"",
+            entry_type.name, integrity_name
+        );
+        match scaffold_entry_type(
+            zome_file_tree,
+            template_file_tree,
+            &entry_type.name,
+            &Some(crud),
+            &Some(entry_type.reference_entry_hash),
+            &Some(entry_type.link_from_original_to_each_update.unwrap_or(true)),
+            &Some(entry_type.only_author_can_update_and_delete),
+            &Some(fields),
+            &Some(zome.name.clone()),
+            &entry_type.settings,
+            false,
+        ) {
+            Ok(ScaffoldedTemplate { file_tree, .. }) => {
+                applied.file_tree = file_tree;
+                applied.scaffolded.push(description);
+            }
+            Err(ScaffoldError::EntryTypeAlreadyExists(_, _, _)) => {
+                applied.skipped.push(description);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    for link_type in &zome.link_types {
+        let from = parse_referenceable(link_type.from.as_str())?;
+        let to = link_type
+            .to
+            .as_ref()
+            .map(|to| parse_referenceable(to.as_str()))
+            .transpose()?;
+
+        let tag_fields = match link_type.tag_fields.is_empty() {
+            true => None,
+            false => Some(
+                link_type
+                    .tag_fields
+                    .iter()
+                    .map(|f| parse_fields(f.as_str()))
+                    .collect::<ScaffoldResult<Vec<FieldDefinition>>>()?,
+            ),
+        };
+
+        let zome_file_tree = integrity_zome_file_tree(&applied.file_tree, dna, &integrity_name)?;
+        let description = match (&link_type.to, &link_type.anchor_field) {
+            (_, Some(anchor_field)) => format!(
+                "index of \"{}\" by \"{}\" in zome \"{}\"",
+                link_type.from, anchor_field, integrity_name
+            ),
+            (Some(to), None) => format!(
+                "link type from \"{}\" to \"{}\" in zome \"{}\"",
+                link_type.from, to, integrity_name
+            ),
+            (None, None) => {
+                return Err(ScaffoldError::InvalidArguments(format!(
+                    "link type from \"{}\" needs either \"to\" or \"anchor_field\"",
+                    link_type.from
+                )))
+            }
+        };
+        let scaffolded = match link_type.anchor_field {
+            Some(_) => scaffold_link_type(
+                zome_file_tree,
+                template_file_tree,
+                &Some(from),
+                &to,
+                &None,
+                &None,
+                &None,
+                &tag_fields,
+                &link_type.anchor_field,
+                &link_type.max_links,
+                &Some(zome.name.clone()),
+            ),
+            None => scaffold_link_type(
+                zome_file_tree,
+                template_file_tree,
+                &Some(from),
+                &to,
+                &Some(link_type.delete),
+                &Some(link_type.only_creator_can_delete),
+                &Some(link_type.bidireccional),
+                &tag_fields,
+                &None,
+                &link_type.max_links,
+                &Some(zome.name.clone()),
+            ),
+        };
+        match scaffolded {
+            Ok(ScaffoldedTemplate { file_tree, .. }) => {
+                applied.file_tree = file_tree;
+                applied.scaffolded.push(description);
+            }
+            Err(ScaffoldError::LinkTypeAlreadyExists(_, _, _)) => {
+                applied.skipped.push(description);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    for collection in &zome.collections {
+        let collection_type = CollectionType::from_str(collection.collection_type.as_str())?;
+        let entry_type = parse_entry_type_reference(collection.entry_type.as_str())?;
+
+        let zome_file_tree = integrity_zome_file_tree(&applied.file_tree, dna, &integrity_name)?;
+        let description = format!(
+            "collection \"{}\" in zome \"{}\"",
+            collection.name, integrity_name
+        );
+        match scaffold_collection(
+            zome_file_tree,
+            template_file_tree,
+            &collection.name,
+            &Some(collection_type),
+            &Some(entry_type),
+            &Some(zome.name.clone()),
+        ) {
+            Ok(ScaffoldedTemplate { file_tree, .. }) => {
+                applied.file_tree = file_tree;
+                applied.scaffolded.push(description);
+            }
+            Err(ScaffoldError::LinkTypeAlreadyExists(_, _, _)) => {
+                applied.skipped.push(description);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+fn integrity_zome_file_tree(
+    file_tree: &FileTree,
+    dna: &DnaSpec,
+    integrity_name: &str,
+) -> ScaffoldResult<ZomeFileTree> {
+    let dna_file_tree = DnaFileTree::get_or_choose(file_tree.clone(), &Some(dna.name.clone()))?;
+    ZomeFileTree::get_or_choose_integrity(dna_file_tree, &Some(integrity_name.to_string()))
+}
+
+fn default_zomes_path(dna_name: &str, zome_kind: &str) -> PathBuf {
+    PathBuf::new()
+        .join("dnas")
+        .join(dna_name)
+        .join("zomes")
+        .join(zome_kind)
+}
+
+/// Orders the entry types so that the ones that are linked from other entry types' fields get scaffolded first
+fn sort_entry_types_by_dependencies(
+    entry_types: &[EntryTypeSpec],
+) -> ScaffoldResult<Vec<EntryTypeSpec>> {
+    let mut pending: Vec<(EntryTypeSpec, Vec<String>)> = vec![];
+
+    for entry_type in entry_types {
+        let mut dependencies: Vec<String> = vec![];
+        for field in &entry_type.fields {
+            if let Some(Referenceable::EntryType(r)) = parse_fields(field.as_str())?.linked_from {
+                let dependency = r.entry_type.to_case(Case::Snake);
+                if dependency != entry_type.name.to_case(Case::Snake) {
+                    dependencies.push(dependency);
+                }
+            }
+        }
+        pending.push((entry_type.clone(), dependencies));
+    }
+
+    let mut sorted: Vec<EntryTypeSpec> = vec![];
+
+    while !pending.is_empty() {
+        let pending_names: Vec<String> = pending
+            .iter()
+            .map(|(e, _)| e.name.to_case(Case::Snake))
+            .collect();
+
+        let Some(position) = pending
+            .iter()
+            .position(|(_, deps)| deps.iter().all(|d| !pending_names.contains(d)))
+        else {
+            return Err(ScaffoldError::InvalidArguments(String::from(
+                "the entry types in the app spec have circular dependencies between their fields",
+            )));
+        };
+
+        sorted.push(pending.remove(position).0);
+    }
+
+    Ok(sorted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_types(yaml: &str) -> Vec<EntryTypeSpec> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_sort_entry_types_by_dependencies() {
+        let entry_types = entry_types(
+            r#"
+- name: comment
+  fields:
+    - post_hash:ActionHash::Post
+    - parent_hash:Option<ActionHash>::comment
+- name: post
+  fields:
+    - title:String:TextField
+    - author:AgentPubKey::author
+"#,
+        );
+
+        let sorted: Vec<String> = sort_entry_types_by_dependencies(&entry_types)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+
+        assert_eq!(sorted, vec![String::from("post"), String::from("comment")]);
+    }
+
+    #[test]
+    fn test_sort_entry_types_with_circular_dependencies() {
+        let entry_types = entry_types(
+            r#"
+- name: post
+  fields:
+    - comment_hash:ActionHash::comment
+- name: comment
+  fields:
+    - post_hash:ActionHash::post
+"#,
+        );
+
+        assert!(sort_entry_types_by_dependencies(&entry_types).is_err());
+    }
+}
@@ -5,6 +5,7 @@ use std::{ffi::OsString, path::PathBuf, str::FromStr};
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
     file_tree::{dir_exists, dir_to_file_tree, file_exists, FileTree},
+    utils::check_can_prompt,
 };
 
 static LIT_TEMPLATES: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/templates/lit");
@@ -74,10 +75,16 @@ pub fn guess_or_choose_framework(app_file_tree: &FileTree) -> ScaffoldResult<UiF
             return Ok(UiFramework::Vanilla);
         }
     }
+    check_can_prompt(
+        "UI framework",
+        "no flag available, initialize a custom template with \"hc scaffold template init\"",
+    )?;
     choose_ui_framework()
 }
 
 pub fn choose_ui_framework() -> ScaffoldResult<UiFramework> {
+    check_can_prompt("template", "--template <TEMPLATE>")?;
+
     let frameworks = vec!["Vue", "Svelte", "Lit"];
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Choose UI framework:")
@@ -89,6 +96,8 @@ pub fn choose_ui_framework() -> ScaffoldResult<UiFramework> {
 }
 
 pub fn choose_non_vanilla_ui_framework() -> ScaffoldResult<UiFramework> {
+    check_can_prompt("template", "--template <TEMPLATE>")?;
+
     let frameworks = vec!["Vue", "Svelte", "Lit"];
     let selection = Select::with_theme(&ColorfulTheme::default())
         .with_prompt("Choose UI framework:")
@@ -17,7 +17,7 @@ use holochain_types::prelude::{DnaManifest, ZomeManifest};
 
 use crate::{
     error::{ScaffoldError, ScaffoldResult},
-    utils::choose_directory_path,
+    utils::{check_can_prompt, choose_directory_path},
 };
 
 pub mod coordinator;
@@ -64,6 +64,7 @@ impl ZomeFileTree {
                     ))
             }
             (_, None) => {
+                check_can_prompt("integrity zome", "--zome <ZOME_NAME>")?;
                 choose_integrity_zome(&dna_file_tree.dna_manifest.name(), &integrity_zomes)
             }
             (_, Some(name)) => integrity_zomes
@@ -97,7 +98,7 @@ impl ZomeFileTree {
     }
 }
 
-fn zome_crate_path(
+pub fn zome_crate_path(
     dna_file_tree: &DnaFileTree,
     zome_manifest: &ZomeManifest,
 ) -> ScaffoldResult<PathBuf> {
@@ -172,7 +173,7 @@ fn choose_integrity_zome(
 /// 5. If all package paths without the [crate name]/Cargo.toml ending are equal, assume this is the zomes folder
 ///    and it is not being differentiated between integrity and coordinator zomes in that project
 ///
-fn try_to_guess_integrity_zomes_location(
+pub fn try_to_guess_integrity_zomes_location(
     dna_file_tree: &DnaFileTree,
 ) -> ScaffoldResult<Option<PathBuf>> {
     let members = get_workspace_members(dna_file_tree.file_tree_ref())?;
@@ -254,7 +255,7 @@ fn try_to_guess_integrity_zomes_location(
 /// 5. If all package paths without the [crate name]/Cargo.toml ending are equal, assume this is the zomes folder
 ///    and it is not being differentiated between integrity and coordinator zomes in that project
 ///
-fn try_to_guess_coordinator_zomes_location(
+pub fn try_to_guess_coordinator_zomes_location(
     dna_file_tree: &DnaFileTree,
 ) -> ScaffoldResult<Option<PathBuf>> {
     let members = get_workspace_members(dna_file_tree.file_tree_ref())?;
@@ -397,25 +398,31 @@ pub fn scaffold_integrity_zome(
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     let path_to_scaffold_in = match path {
         Some(p) => p.clone(),
-        None => match try_to_guess_integrity_zomes_location(&dna_file_tree)? {
-            Some(p) => {
-                if Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt(format!("Scaffold integrity zome in folder {:?}?", p))
-                    .interact()?
-                {
-                    p
-                } else {
-                    choose_directory_path(
-                        &String::from("Where should the integrity zome be scaffolded instead?"),
-                        dna_file_tree.file_tree_ref(),
-                    )?
+        None => {
+            check_can_prompt("integrity zome path", "--integrity <PATH>")?;
+
+            match try_to_guess_integrity_zomes_location(&dna_file_tree)? {
+                Some(p) => {
+                    if Confirm::with_theme(&ColorfulTheme::default())
+                        .with_prompt(format!("Scaffold integrity zome in folder {:?}?", p))
+                        .interact()?
+                    {
+                        p
+                    } else {
+                        choose_directory_path(
+                            &String::from("Where should the integrity zome be scaffolded instead?"),
+                            "--integrity <PATH>",
+                            dna_file_tree.file_tree_ref(),
+                        )?
+                    }
                 }
+                None => choose_directory_path(
+                    &String::from("Where should the integrity zome be scaffolded?"),
+                    "--integrity <PATH>",
+                    dna_file_tree.file_tree_ref(),
+                )?,
             }
-            None => choose_directory_path(
-                &String::from("Where should the integrity zome be scaffolded?"),
-                dna_file_tree.file_tree_ref(),
-            )?,
-        },
+        }
     };
 
     scaffold_integrity_zome_with_path(
@@ -477,19 +484,31 @@ pub fn scaffold_coordinator_zome(
 
     let path_to_scaffold_in = match path {
         Some(p) => p.clone(),
-        None => match try_to_guess_coordinator_zomes_location(&dna_file_tree)? {
-            Some(p) => {
-                if Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt(format!("Scaffold coordinator zome in {:?}?", p))
-                    .interact()?
-                {
-                    p
-                } else {
-                    choose_directory_path(&prompt, dna_file_tree.file_tree_ref())?
+        None => {
+            check_can_prompt("coordinator zome path", "--coordinator <PATH>")?;
+
+            match try_to_guess_coordinator_zomes_location(&dna_file_tree)? {
+                Some(p) => {
+                    if Confirm::with_theme(&ColorfulTheme::default())
+                        .with_prompt(format!("Scaffold coordinator zome in {:?}?", p))
+                        .interact()?
+                    {
+                        p
+                    } else {
+                        choose_directory_path(
+                            &prompt,
+                            "--coordinator <PATH>",
+                            dna_file_tree.file_tree_ref(),
+                        )?
+                    }
                 }
+                None => choose_directory_path(
+                    &prompt,
+                    "--coordinator <PATH>",
+                    dna_file_tree.file_tree_ref(),
+                )?,
             }
-            None => choose_directory_path(&prompt, dna_file_tree.file_tree_ref())?,
-        },
+        }
     };
 
     scaffold_coordinator_zome_in_path(
@@ -8,6 +8,7 @@ use crate::{
     error::{ScaffoldError, ScaffoldResult},
     file_tree::find_map_rust_files,
     scaffold::dna::DnaFileTree,
+    utils::check_can_prompt,
 };
 
 use super::ZomeFileTree;
@@ -146,6 +147,14 @@ pub fn find_extern_function_or_choose(
         functions_by_zome.insert(coordinator_zome.name.to_string(), all_extern_functions);
     }
 
+    // There is no flag to pick another function, the one being looked for has to be added first
+    check_can_prompt(
+        prompt,
+        &format!(
+            "a \"{}\" extern function in one of the coordinator zomes",
+            fn_name_to_find
+        ),
+    )?;
     let (zome_name, fn_name) = choose_extern_function(&functions_by_zome, &prompt)?;
 
     let chosen_zome = coordinator_zomes
@@ -1,7 +1,10 @@
-use dialoguer::{theme::ColorfulTheme, MultiSelect};
+use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
 use holochain_types::prelude::{DnaManifest, ZomeManifest};
 
-use crate::error::ScaffoldResult;
+use crate::{
+    error::{ScaffoldError, ScaffoldResult},
+    utils::check_can_prompt,
+};
 
 /// Prompts a MultiSelect dialog to select one or multiple integrity zomes
 ///
@@ -29,6 +32,8 @@ pub fn select_integrity_zomes(
         None => "Select integrity zome (SPACE to select/unselect):",
     };
 
+    check_can_prompt(prompt, "--dependencies <ZOME,...>")?;
+
     let selected_options = MultiSelect::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .items(&integrity_zomes)
@@ -61,3 +66,48 @@ pub fn get_coordinator_zomes_for_integrity(
             .collect(),
     }
 }
+
+/// Returns the coordinator zome that depends on the given integrity zome in which the zome functions should be scaffolded,
+/// which is the given one if any, or the only one there is, or the one chosen by the user among all of them
+pub fn get_or_choose_coordinator_zome_for_integrity(
+    dna_manifest: &DnaManifest,
+    integrity_zome_name: &String,
+    coordinator_zome_name: &Option<String>,
+    prompt: &str,
+) -> ScaffoldResult<ZomeManifest> {
+    let coordinator_zomes_for_integrity =
+        get_coordinator_zomes_for_integrity(dna_manifest, integrity_zome_name);
+
+    if let Some(name) = coordinator_zome_name {
+        return coordinator_zomes_for_integrity
+            .into_iter()
+            .find(|z| z.name.0.eq(name))
+            .ok_or(ScaffoldError::CoordinatorZomeNotFound(
+                name.clone(),
+                dna_manifest.name(),
+            ));
+    }
+
+    match coordinator_zomes_for_integrity.len() {
+        0 => Err(ScaffoldError::NoCoordinatorZomesFoundForIntegrityZome(
+            dna_manifest.name(),
+            integrity_zome_name.clone(),
+        )),
+        1 => Ok(coordinator_zomes_for_integrity[0].clone()),
+        _ => {
+            check_can_prompt("coordinator zome", "--coordinator-zome <COORDINATOR_ZOME>")?;
+
+            let names: Vec<String> = coordinator_zomes_for_integrity
+                .iter()
+                .map(|z| z.name.0.to_string())
+                .collect();
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(prompt)
+                .default(0)
+                .items(&names[..])
+                .interact()?;
+
+            Ok(coordinator_zomes_for_integrity[selection].clone())
+        }
+    }
+}
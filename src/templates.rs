@@ -13,6 +13,7 @@ use crate::file_tree::{
     FileTree,
 };
 use crate::scaffold::web_app::uis::{guess_or_choose_framework, template_for_ui_framework};
+use crate::utils::check_can_prompt;
 
 pub mod get;
 pub mod helpers;
@@ -279,6 +280,8 @@ pub fn choose_or_get_template(
         (_, 0) => Err(ScaffoldError::NoTemplatesFound),
         (None, 1) => Ok(templates[0].clone()),
         (None, _) => {
+            check_can_prompt("template", "--template <TEMPLATE>")?;
+
             let option = Select::with_theme(&ColorfulTheme::default())
                 .with_prompt("Which template should we use?")
                 .default(0)
@@ -6,7 +6,7 @@ use serde::Serialize;
 use crate::{
     error::ScaffoldResult,
     file_tree::{file_content, FileTree},
-    scaffold::entry_type::definitions::Referenceable,
+    scaffold::entry_type::definitions::{FieldDefinition, Referenceable},
 };
 
 use super::{
@@ -22,7 +22,13 @@ pub struct ScaffoldLinkTypeData {
     pub from_referenceable: Referenceable,
     pub to_referenceable: Option<Referenceable>,
     pub delete: bool,
+    pub only_creator_can_delete: bool,
     pub bidireccional: Option<String>,
+    pub tag: Option<FieldDefinition>,
+    pub anchor_field: Option<FieldDefinition>,
+    /// A value of the anchor field that the tests update the indexed entry to, different from the one it was created with
+    pub anchor_update_sample: Option<String>,
+    pub max_links: Option<usize>,
 }
 pub fn scaffold_link_type_templates(
     mut app_file_tree: FileTree,
@@ -34,7 +40,12 @@ pub fn scaffold_link_type_templates(
     from_referenceable: &Referenceable,
     to_referenceable: &Option<Referenceable>,
     delete: bool,
+    only_creator_can_delete: bool,
     bidireccional: &Option<String>,
+    tag: &Option<FieldDefinition>,
+    anchor_field: &Option<FieldDefinition>,
+    anchor_update_sample: &Option<String>,
+    max_links: Option<usize>,
 ) -> ScaffoldResult<ScaffoldedTemplate> {
     let data = ScaffoldLinkTypeData {
         app_name: app_name.clone(),
@@ -44,7 +55,12 @@ pub fn scaffold_link_type_templates(
         link_type_name: link_type_name.clone(),
         to_referenceable: to_referenceable.clone(),
         delete,
+        only_creator_can_delete,
         bidireccional: bidireccional.clone(),
+        tag: tag.clone(),
+        anchor_field: anchor_field.clone(),
+        anchor_update_sample: anchor_update_sample.clone(),
+        max_links,
     };
 
     let h = build_handlebars(&template_file_tree)?;
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{ffi::OsString, path::PathBuf};
 
 use convert_case::{Case, Casing};
@@ -7,9 +8,40 @@ use dialoguer::{theme::ColorfulTheme, Input, Select};
 use crate::error::{ScaffoldError, ScaffoldResult};
 use crate::file_tree::{dir_content, FileTree};
 
-pub fn choose_directory_path(prompt: &String, app_file_tree: &FileTree) -> ScaffoldResult<PathBuf> {
+static PROMPTS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Disables all interactive prompts for the rest of the process
+/// From then on, any value that would have been prompted for results in a `ScaffoldError::MissingArguments` instead
+pub fn disable_prompts() {
+    PROMPTS_DISABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn prompts_disabled() -> bool {
+    PROMPTS_DISABLED.load(Ordering::Relaxed)
+}
+
+/// Raises an error if prompts are disabled, naming the missing argument and how to supply it
+pub fn check_can_prompt(argument: &str, supplied_by: &str) -> ScaffoldResult<()> {
+    match prompts_disabled() {
+        true => Err(ScaffoldError::MissingArguments(vec![(
+            argument.to_string(),
+            supplied_by.to_string(),
+        )])),
+        false => Ok(()),
+    }
+}
+
+/// Lets the user navigate the folders of the app to choose one,
+/// or fails naming the flag that supplies it if prompts are disabled
+pub fn choose_directory_path(
+    prompt: &String,
+    supplied_by: &str,
+    app_file_tree: &FileTree,
+) -> ScaffoldResult<PathBuf> {
     let mut chosen_directory: Option<PathBuf> = None;
 
+    check_can_prompt(prompt, supplied_by)?;
+
     let mut current_path = PathBuf::new();
 
     while let None = chosen_directory {
@@ -66,6 +98,8 @@ fn get_folders_names(folder: &BTreeMap<OsString, FileTree>) -> Vec<String> {
 
 /// "yes" or "no" input dialog, with the option to specify a recommended answer (yes = true, no = false)
 pub fn input_yes_or_no(prompt: &String, recommended: Option<bool>) -> ScaffoldResult<bool> {
+    check_can_prompt(prompt, "no flag available, run interactively")?;
+
     let mut yes_recommended = "";
     let mut no_recommended = "";
 
@@ -90,6 +124,8 @@ pub fn input_yes_or_no(prompt: &String, recommended: Option<bool>) -> ScaffoldRe
 }
 
 pub fn input_with_case(prompt: &String, case: Case) -> ScaffoldResult<String> {
+    check_can_prompt(prompt, "no flag available, run interactively")?;
+
     let input: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .validate_with(|input: &String| -> Result<(), String> {
@@ -108,6 +144,8 @@ pub fn input_with_case_and_initial_text(
     case: Case,
     initial_text: &String,
 ) -> ScaffoldResult<String> {
+    check_can_prompt(prompt, "no flag available, run interactively")?;
+
     let input: String = Input::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .with_initial_text(initial_text)
@@ -123,6 +161,8 @@ pub fn input_with_case_and_initial_text(
 }
 
 pub fn input_no_whitespace(prompt: &String) -> ScaffoldResult<String> {
+    check_can_prompt(prompt, "no flag available, run interactively")?;
+
     let input = Input::with_theme(&ColorfulTheme::default())
         .with_prompt(prompt)
         .validate_with(|input: &String| -> Result<(), &str> {
@@ -161,3 +201,26 @@ pub fn check_no_whitespace(input: &String, identifier: &str) -> ScaffoldResult<(
 pub fn unparse(file: &syn::File) -> String {
     prettyplease::unparse(file).replace("///", "//")
 }
+
+/// Whether any of the given attributes has the given name, eg. "hdk_extern"
+pub fn has_attribute(attrs: &[syn::Attribute], attribute: &str) -> bool {
+    attrs
+        .iter()
+        .any(|a| a.path().segments.iter().any(|s| s.ident.eq(attribute)))
+}
+
+/// Finds the top level function with the given name in the given file
+pub fn find_fn<'a>(file: &'a syn::File, fn_name: &str) -> Option<&'a syn::ItemFn> {
+    file.items.iter().find_map(|item| match item {
+        syn::Item::Fn(item_fn) if item_fn.sig.ident.eq(fn_name) => Some(item_fn),
+        _ => None,
+    })
+}
+
+/// Finds the top level function with the given name in any of the given files
+pub fn find_fn_in_files<'a>(
+    files: &'a BTreeMap<PathBuf, syn::File>,
+    fn_name: &str,
+) -> Option<&'a syn::ItemFn> {
+    files.values().find_map(|file| find_fn(file, fn_name))
+}